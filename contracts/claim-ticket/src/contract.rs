@@ -0,0 +1,364 @@
+#[cfg(not(feature = "library"))]
+use cosmwasm_std::entry_point;
+use cosmwasm_std::{
+    to_json_binary, Addr, Binary, Deps, DepsMut, Empty, Env, MessageInfo, Order, Response, StdResult, WasmMsg,
+};
+use cw2::set_contract_version;
+use cw20::Expiration;
+use cw_storage_plus::Bound;
+
+use crate::error::ContractError;
+use crate::msg::{
+    AllNftInfoResponse, ApprovalResponse, ContractInfoResponse, Cw721ReceiveMsg, ExecuteMsg, InstantiateMsg,
+    MintMsg, NftInfoResponse, NumTokensResponse, OwnerOfResponse, QueryMsg, ReceiverExecuteMsg, TokensResponse,
+};
+use crate::state::{Approval, Config, TokenInfo, CONFIG, OPERATORS, OWNER_TOKENS, TOKENS, TOKEN_COUNT};
+
+// version info for migration info
+const CONTRACT_NAME: &str = "crates.io:liquid-claim-ticket";
+const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+const DEFAULT_LIMIT: u32 = 10;
+const MAX_LIMIT: u32 = 50;
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn instantiate(
+    deps: DepsMut,
+    _env: Env,
+    _info: MessageInfo,
+    msg: InstantiateMsg,
+) -> Result<Response, ContractError> {
+    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+
+    let config = Config {
+        name: msg.name,
+        symbol: msg.symbol,
+        minter: deps.api.addr_validate(&msg.minter)?,
+    };
+    CONFIG.save(deps.storage, &config)?;
+    TOKEN_COUNT.save(deps.storage, &0)?;
+
+    Ok(Response::default())
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn execute(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: ExecuteMsg,
+) -> Result<Response, ContractError> {
+    match msg {
+        ExecuteMsg::Mint(msg) => execute_mint(deps, info, msg),
+        ExecuteMsg::TransferNft { recipient, token_id } => execute_transfer_nft(deps, env, info, recipient, token_id),
+        ExecuteMsg::SendNft { contract, token_id, msg } => execute_send_nft(deps, env, info, contract, token_id, msg),
+        ExecuteMsg::Approve { spender, token_id, expires } => {
+            execute_approve(deps, env, info, spender, token_id, expires)
+        },
+        ExecuteMsg::Revoke { spender, token_id } => execute_revoke(deps, env, info, spender, token_id),
+        ExecuteMsg::ApproveAll { operator, expires } => execute_approve_all(deps, env, info, operator, expires),
+        ExecuteMsg::RevokeAll { operator } => execute_revoke_all(deps, info, operator),
+        ExecuteMsg::Burn { token_id } => execute_burn(deps, env, info, token_id),
+    }
+}
+
+// is_operator tells if the sender may handle all the tickets of the owner
+fn is_operator(deps: Deps, env: &Env, owner: &Addr, sender: &Addr) -> StdResult<bool> {
+    Ok(match OPERATORS.may_load(deps.storage, (owner, sender))? {
+        Some(expires) => !expires.is_expired(&env.block),
+        None => false,
+    })
+}
+
+// check_can_approve fails unless the sender owns the ticket or is an operator of the owner
+fn check_can_approve(deps: Deps, env: &Env, sender: &Addr, token: &TokenInfo) -> Result<(), ContractError> {
+    if token.owner == *sender || is_operator(deps, env, &token.owner, sender)? {
+        return Ok(());
+    }
+    Err(ContractError::Unauthorized {})
+}
+
+// check_can_send fails unless the sender owns the ticket, is approved on it or is an operator of the owner
+fn check_can_send(deps: Deps, env: &Env, sender: &Addr, token: &TokenInfo) -> Result<(), ContractError> {
+    let approved = token
+        .approvals
+        .iter()
+        .any(|approval| approval.spender == *sender && !approval.is_expired(&env.block));
+    if approved {
+        return Ok(());
+    }
+    check_can_approve(deps, env, sender, token)
+}
+
+// transfer_ticket moves a ticket to the recipient and clears its approvals
+fn transfer_ticket(
+    deps: DepsMut,
+    env: &Env,
+    sender: &Addr,
+    recipient: &Addr,
+    token_id: &str,
+) -> Result<(), ContractError> {
+    let mut token = TOKENS.load(deps.storage, token_id)?;
+    check_can_send(deps.as_ref(), env, sender, &token)?;
+    OWNER_TOKENS.remove(deps.storage, (&token.owner, token_id));
+    OWNER_TOKENS.save(deps.storage, (recipient, token_id), &Empty {})?;
+    token.owner = recipient.clone();
+    token.approvals = vec![];
+    TOKENS.save(deps.storage, token_id, &token)?;
+    Ok(())
+}
+
+pub fn execute_mint(deps: DepsMut, info: MessageInfo, msg: MintMsg) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    if info.sender != config.minter {
+        return Err(ContractError::Unauthorized {});
+    }
+    if TOKENS.has(deps.storage, &msg.token_id) {
+        return Err(ContractError::TicketExists { token_id: msg.token_id });
+    }
+    let owner = deps.api.addr_validate(&msg.owner)?;
+    let token = TokenInfo {
+        owner: owner.clone(),
+        approvals: vec![],
+        token_uri: msg.token_uri,
+        extension: msg.extension,
+    };
+    TOKENS.save(deps.storage, &msg.token_id, &token)?;
+    OWNER_TOKENS.save(deps.storage, (&owner, &msg.token_id), &Empty {})?;
+    TOKEN_COUNT.update(deps.storage, |count| -> StdResult<_> { Ok(count + 1) })?;
+
+    let res = Response::new()
+        .add_attribute("action", "mint")
+        .add_attribute("from", info.sender)
+        .add_attribute("owner", owner)
+        .add_attribute("token_id", msg.token_id);
+    Ok(res)
+}
+
+pub fn execute_transfer_nft(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    recipient: String,
+    token_id: String,
+) -> Result<Response, ContractError> {
+    let recipient = deps.api.addr_validate(&recipient)?;
+    transfer_ticket(deps, &env, &info.sender, &recipient, &token_id)?;
+
+    let res = Response::new()
+        .add_attribute("action", "transferNft")
+        .add_attribute("from", info.sender)
+        .add_attribute("recipient", recipient)
+        .add_attribute("token_id", token_id);
+    Ok(res)
+}
+
+pub fn execute_send_nft(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    contract: String,
+    token_id: String,
+    msg: Binary,
+) -> Result<Response, ContractError> {
+    let contract = deps.api.addr_validate(&contract)?;
+    transfer_ticket(deps, &env, &info.sender, &contract, &token_id)?;
+
+    let receive = ReceiverExecuteMsg::ReceiveNft(Cw721ReceiveMsg {
+        sender: info.sender.to_string(),
+        token_id: token_id.clone(),
+        msg,
+    });
+    let res = Response::new()
+        .add_message(WasmMsg::Execute {
+            contract_addr: contract.to_string(),
+            msg: to_json_binary(&receive)?,
+            funds: vec![],
+        })
+        .add_attribute("action", "sendNft")
+        .add_attribute("from", info.sender)
+        .add_attribute("recipient", contract)
+        .add_attribute("token_id", token_id);
+    Ok(res)
+}
+
+pub fn execute_approve(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    spender: String,
+    token_id: String,
+    expires: Option<Expiration>,
+) -> Result<Response, ContractError> {
+    let spender = deps.api.addr_validate(&spender)?;
+    let mut token = TOKENS.load(deps.storage, &token_id)?;
+    check_can_approve(deps.as_ref(), &env, &info.sender, &token)?;
+    let expires = expires.unwrap_or_default();
+    if expires.is_expired(&env.block) {
+        return Err(ContractError::Expired {});
+    }
+    // a new approval replaces the one of the same spender
+    token.approvals.retain(|approval| approval.spender != spender);
+    token.approvals.push(Approval { spender: spender.clone(), expires });
+    TOKENS.save(deps.storage, &token_id, &token)?;
+
+    let res = Response::new()
+        .add_attribute("action", "approve")
+        .add_attribute("from", info.sender)
+        .add_attribute("spender", spender)
+        .add_attribute("token_id", token_id);
+    Ok(res)
+}
+
+pub fn execute_revoke(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    spender: String,
+    token_id: String,
+) -> Result<Response, ContractError> {
+    let spender_addr = deps.api.addr_validate(&spender)?;
+    let mut token = TOKENS.load(deps.storage, &token_id)?;
+    check_can_approve(deps.as_ref(), &env, &info.sender, &token)?;
+    let approvals = token.approvals.len();
+    token.approvals.retain(|approval| approval.spender != spender_addr);
+    if token.approvals.len() == approvals {
+        return Err(ContractError::ApprovalNotFound { spender });
+    }
+    TOKENS.save(deps.storage, &token_id, &token)?;
+
+    let res = Response::new()
+        .add_attribute("action", "revoke")
+        .add_attribute("from", info.sender)
+        .add_attribute("spender", spender)
+        .add_attribute("token_id", token_id);
+    Ok(res)
+}
+
+pub fn execute_approve_all(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    operator: String,
+    expires: Option<Expiration>,
+) -> Result<Response, ContractError> {
+    let operator = deps.api.addr_validate(&operator)?;
+    let expires = expires.unwrap_or_default();
+    if expires.is_expired(&env.block) {
+        return Err(ContractError::Expired {});
+    }
+    OPERATORS.save(deps.storage, (&info.sender, &operator), &expires)?;
+
+    let res = Response::new()
+        .add_attribute("action", "approveAll")
+        .add_attribute("from", info.sender)
+        .add_attribute("operator", operator);
+    Ok(res)
+}
+
+pub fn execute_revoke_all(deps: DepsMut, info: MessageInfo, operator: String) -> Result<Response, ContractError> {
+    let operator = deps.api.addr_validate(&operator)?;
+    OPERATORS.remove(deps.storage, (&info.sender, &operator));
+
+    let res = Response::new()
+        .add_attribute("action", "revokeAll")
+        .add_attribute("from", info.sender)
+        .add_attribute("operator", operator);
+    Ok(res)
+}
+
+pub fn execute_burn(deps: DepsMut, env: Env, info: MessageInfo, token_id: String) -> Result<Response, ContractError> {
+    let token = TOKENS.load(deps.storage, &token_id)?;
+    check_can_send(deps.as_ref(), &env, &info.sender, &token)?;
+    TOKENS.remove(deps.storage, &token_id);
+    OWNER_TOKENS.remove(deps.storage, (&token.owner, &token_id));
+    TOKEN_COUNT.update(deps.storage, |count| -> StdResult<_> { Ok(count.saturating_sub(1)) })?;
+
+    let res = Response::new()
+        .add_attribute("action", "burn")
+        .add_attribute("from", info.sender)
+        .add_attribute("token_id", token_id);
+    Ok(res)
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
+    match msg {
+        QueryMsg::OwnerOf { token_id, include_expired } => {
+            to_json_binary(&query_owner_of(deps, env, token_id, include_expired.unwrap_or(false))?)
+        },
+        QueryMsg::NftInfo { token_id } => to_json_binary(&query_nft_info(deps, token_id)?),
+        QueryMsg::AllNftInfo { token_id, include_expired } => {
+            to_json_binary(&query_all_nft_info(deps, env, token_id, include_expired.unwrap_or(false))?)
+        },
+        QueryMsg::Tokens { owner, start_after, limit } => to_json_binary(&query_tokens(deps, owner, start_after, limit)?),
+        QueryMsg::AllTokens { start_after, limit } => to_json_binary(&query_all_tokens(deps, start_after, limit)?),
+        QueryMsg::NumTokens {} => to_json_binary(&NumTokensResponse { count: TOKEN_COUNT.load(deps.storage)? }),
+        QueryMsg::ContractInfo {} => to_json_binary(&query_contract_info(deps)?),
+    }
+}
+
+fn owner_of(env: &Env, token: &TokenInfo, include_expired: bool) -> OwnerOfResponse {
+    let approvals = token
+        .approvals
+        .iter()
+        .filter(|approval| include_expired || !approval.is_expired(&env.block))
+        .map(|approval| ApprovalResponse { spender: approval.spender.to_string(), expires: approval.expires })
+        .collect();
+    OwnerOfResponse { owner: token.owner.to_string(), approvals }
+}
+
+pub fn query_owner_of(deps: Deps, env: Env, token_id: String, include_expired: bool) -> StdResult<OwnerOfResponse> {
+    let token = TOKENS.load(deps.storage, &token_id)?;
+    Ok(owner_of(&env, &token, include_expired))
+}
+
+pub fn query_nft_info(deps: Deps, token_id: String) -> StdResult<NftInfoResponse> {
+    let token = TOKENS.load(deps.storage, &token_id)?;
+    Ok(NftInfoResponse { token_uri: token.token_uri, extension: token.extension })
+}
+
+pub fn query_all_nft_info(
+    deps: Deps,
+    env: Env,
+    token_id: String,
+    include_expired: bool,
+) -> StdResult<AllNftInfoResponse> {
+    let token = TOKENS.load(deps.storage, &token_id)?;
+    Ok(AllNftInfoResponse {
+        access: owner_of(&env, &token, include_expired),
+        info: NftInfoResponse { token_uri: token.token_uri, extension: token.extension },
+    })
+}
+
+pub fn query_tokens(
+    deps: Deps,
+    owner: String,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<TokensResponse> {
+    let owner = deps.api.addr_validate(&owner)?;
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after.as_deref().map(Bound::exclusive);
+    let tokens = OWNER_TOKENS
+        .prefix(&owner)
+        .keys(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .collect::<StdResult<Vec<_>>>()?;
+    Ok(TokensResponse { tokens })
+}
+
+pub fn query_all_tokens(deps: Deps, start_after: Option<String>, limit: Option<u32>) -> StdResult<TokensResponse> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after.as_deref().map(Bound::exclusive);
+    let tokens = TOKENS
+        .keys(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .collect::<StdResult<Vec<_>>>()?;
+    Ok(TokensResponse { tokens })
+}
+
+pub fn query_contract_info(deps: Deps) -> StdResult<ContractInfoResponse> {
+    let config = CONFIG.load(deps.storage)?;
+    Ok(ContractInfoResponse { name: config.name, symbol: config.symbol })
+}
@@ -0,0 +1,20 @@
+use cosmwasm_std::StdError;
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum ContractError {
+    #[error("{0}")]
+    Std(#[from] StdError),
+
+    #[error("Unauthorized")]
+    Unauthorized {},
+
+    #[error("Ticket {token_id} already exists")]
+    TicketExists { token_id: String },
+
+    #[error("Approval has already expired")]
+    Expired {},
+
+    #[error("No approval of {spender} to revoke")]
+    ApprovalNotFound { spender: String },
+}
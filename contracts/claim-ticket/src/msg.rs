@@ -0,0 +1,120 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use cosmwasm_std::Binary;
+use cw20::Expiration;
+
+use crate::state::TicketMetadata;
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct InstantiateMsg {
+    pub name: String,
+    pub symbol: String,
+    /// This is the staking contract address, the only one minting tickets
+    pub minter: String,
+}
+
+/// ExecuteMsg is the cw721 execute interface, with tickets minted by the staking contract
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ExecuteMsg {
+    /// TransferNft moves a ticket to the recipient, the claim of the request moves with it
+    TransferNft { recipient: String, token_id: String },
+    /// SendNft moves a ticket to a contract and calls ReceiveNft on it
+    SendNft { contract: String, token_id: String, msg: Binary },
+    /// Approve lets the spender transfer, send or burn one ticket
+    Approve { spender: String, token_id: String, expires: Option<Expiration> },
+    /// Revoke removes the approval of the spender on one ticket
+    Revoke { spender: String, token_id: String },
+    /// ApproveAll lets the operator handle all the tickets of the sender
+    ApproveAll { operator: String, expires: Option<Expiration> },
+    /// RevokeAll removes the operator of the sender
+    RevokeAll { operator: String },
+    /// Mint a new ticket, only the minter can call it
+    Mint(MintMsg),
+    /// Burn destroys a ticket, the request can't be claimed by ticket anymore
+    Burn { token_id: String },
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct MintMsg {
+    /// id of the unstaking request
+    pub token_id: String,
+    /// address receiving the ticket
+    pub owner: String,
+    pub token_uri: Option<String>,
+    pub extension: TicketMetadata,
+}
+
+/// Cw721ReceiveMsg is sent to the contract a ticket is sent to
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Cw721ReceiveMsg {
+    pub sender: String,
+    pub token_id: String,
+    pub msg: Binary,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ReceiverExecuteMsg {
+    ReceiveNft(Cw721ReceiveMsg),
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum QueryMsg {
+    /// OwnerOf shows the owner of a ticket and its approvals
+    OwnerOf { token_id: String, include_expired: Option<bool> },
+    /// NftInfo shows the metadata of a ticket
+    NftInfo { token_id: String },
+    /// AllNftInfo shows the owner and the metadata of a ticket
+    AllNftInfo { token_id: String, include_expired: Option<bool> },
+    /// Tokens lists the tickets of an owner
+    Tokens { owner: String, start_after: Option<String>, limit: Option<u32> },
+    /// AllTokens lists all the tickets
+    AllTokens { start_after: Option<String>, limit: Option<u32> },
+    /// NumTokens shows how many tickets exist
+    NumTokens {},
+    /// ContractInfo shows the name and symbol of the collection
+    ContractInfo {},
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ApprovalResponse {
+    pub spender: String,
+    pub expires: Expiration,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct OwnerOfResponse {
+    pub owner: String,
+    pub approvals: Vec<ApprovalResponse>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct NftInfoResponse {
+    pub token_uri: Option<String>,
+    pub extension: TicketMetadata,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct AllNftInfoResponse {
+    pub access: OwnerOfResponse,
+    pub info: NftInfoResponse,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct TokensResponse {
+    pub tokens: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct NumTokensResponse {
+    pub count: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ContractInfoResponse {
+    pub name: String,
+    pub symbol: String,
+}
@@ -0,0 +1,55 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use cosmwasm_std::{Addr, BlockInfo, Empty, Timestamp, Uint128};
+use cw20::Expiration;
+use cw_storage_plus::{Item, Map};
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Config {
+    pub name: String,
+    pub symbol: String,
+    /// Only the minter can mint tickets, it is the staking contract
+    pub minter: Addr,
+}
+
+/// TicketMetadata describes the unstaking request behind a ticket
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct TicketMetadata {
+    /// native tokens requested
+    pub amount: Uint128,
+    /// block height of the unstake
+    pub height: u64,
+    /// estimated block time from which the request can be claimed
+    pub eta: Timestamp,
+}
+
+/// Approval lets the spender transfer, send or burn one ticket until it expires
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Approval {
+    pub spender: Addr,
+    pub expires: Expiration,
+}
+
+impl Approval {
+    pub fn is_expired(&self, block: &BlockInfo) -> bool {
+        self.expires.is_expired(block)
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct TokenInfo {
+    pub owner: Addr,
+    /// approvals are cleared when the ticket changes hands
+    pub approvals: Vec<Approval>,
+    pub token_uri: Option<String>,
+    pub extension: TicketMetadata,
+}
+
+pub const CONFIG: Item<Config> = Item::new("config");
+pub const TOKEN_COUNT: Item<u64> = Item::new("num_tokens");
+pub const TOKENS: Map<&str, TokenInfo> = Map::new("tokens");
+/// OWNER_TOKENS indexes the tickets by owner
+pub const OWNER_TOKENS: Map<(&Addr, &str), Empty> = Map::new("owner_tokens");
+/// OPERATORS holds the accounts an owner lets handle all of its tickets
+pub const OPERATORS: Map<(&Addr, &Addr), Expiration> = Map::new("operators");
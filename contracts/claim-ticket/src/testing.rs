@@ -0,0 +1,126 @@
+use cosmwasm_std::testing::{mock_dependencies, mock_env, mock_info, MockApi, MockQuerier, MockStorage};
+use cosmwasm_std::{from_json, to_json_binary, Binary, CosmosMsg, OwnedDeps, Timestamp, Uint128, WasmMsg};
+use cw20::Expiration;
+
+use crate::contract::{execute, instantiate, query};
+use crate::msg::{
+    AllNftInfoResponse, Cw721ReceiveMsg, ExecuteMsg, InstantiateMsg, MintMsg, NumTokensResponse, OwnerOfResponse,
+    QueryMsg, ReceiverExecuteMsg, TokensResponse,
+};
+use crate::state::TicketMetadata;
+use crate::ContractError;
+
+const STAKING: &str = "staking";
+
+type MockDeps = OwnedDeps<MockStorage, MockApi, MockQuerier>;
+
+fn setup() -> MockDeps {
+    let mut deps = mock_dependencies();
+    let msg = InstantiateMsg { name: "Claim Ticket".to_string(), symbol: "TICKET".to_string(), minter: STAKING.to_string() };
+    instantiate(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+    deps
+}
+
+fn mint_msg(token_id: &str, owner: &str) -> ExecuteMsg {
+    ExecuteMsg::Mint(MintMsg {
+        token_id: token_id.to_string(),
+        owner: owner.to_string(),
+        token_uri: None,
+        extension: TicketMetadata { amount: Uint128::new(1_000), height: 12_345, eta: Timestamp::from_seconds(100) },
+    })
+}
+
+fn run(deps: &mut MockDeps, sender: &str, msg: ExecuteMsg) -> Result<(), ContractError> {
+    execute(deps.as_mut(), mock_env(), mock_info(sender, &[]), msg).map(|_| ())
+}
+
+fn owner_of(deps: &MockDeps, token_id: &str) -> OwnerOfResponse {
+    let msg = QueryMsg::OwnerOf { token_id: token_id.to_string(), include_expired: None };
+    from_json(query(deps.as_ref(), mock_env(), msg).unwrap()).unwrap()
+}
+
+#[test]
+fn only_the_minter_mints() {
+    let mut deps = setup();
+    let err = run(&mut deps, "alice", mint_msg("1", "alice")).unwrap_err();
+    assert!(matches!(err, ContractError::Unauthorized {}));
+
+    run(&mut deps, STAKING, mint_msg("1", "alice")).unwrap();
+    let err = run(&mut deps, STAKING, mint_msg("1", "bob")).unwrap_err();
+    assert!(matches!(err, ContractError::TicketExists { .. }));
+
+    let msg = QueryMsg::AllNftInfo { token_id: "1".to_string(), include_expired: None };
+    let info: AllNftInfoResponse = from_json(query(deps.as_ref(), mock_env(), msg).unwrap()).unwrap();
+    assert_eq!(info.access.owner, "alice");
+    assert_eq!(info.info.extension.amount.u128(), 1_000);
+    assert_eq!(info.info.extension.height, 12_345);
+    let count: NumTokensResponse = from_json(query(deps.as_ref(), mock_env(), QueryMsg::NumTokens {}).unwrap()).unwrap();
+    assert_eq!(count.count, 1);
+}
+
+#[test]
+fn transfer_needs_owner_approval_or_operator() {
+    let mut deps = setup();
+    run(&mut deps, STAKING, mint_msg("1", "alice")).unwrap();
+    run(&mut deps, STAKING, mint_msg("2", "alice")).unwrap();
+    let transfer = |recipient: &str, token_id: &str| ExecuteMsg::TransferNft {
+        recipient: recipient.to_string(),
+        token_id: token_id.to_string(),
+    };
+
+    let err = run(&mut deps, "bob", transfer("bob", "1")).unwrap_err();
+    assert!(matches!(err, ContractError::Unauthorized {}));
+    run(&mut deps, "alice", transfer("bob", "1")).unwrap();
+    assert_eq!(owner_of(&deps, "1").owner, "bob");
+
+    // an approval is good for one ticket and is cleared by the transfer
+    let msg = ExecuteMsg::Approve { spender: "carol".to_string(), token_id: "1".to_string(), expires: None };
+    run(&mut deps, "bob", msg).unwrap();
+    run(&mut deps, "carol", transfer("carol", "1")).unwrap();
+    assert!(owner_of(&deps, "1").approvals.is_empty());
+    let err = run(&mut deps, "carol", transfer("carol", "2")).unwrap_err();
+    assert!(matches!(err, ContractError::Unauthorized {}));
+
+    // an operator handles all the tickets of the owner until it expires or is revoked
+    let expires = Expiration::AtHeight(mock_env().block.height + 1);
+    run(&mut deps, "alice", ExecuteMsg::ApproveAll { operator: "dave".to_string(), expires: Some(expires) }).unwrap();
+    run(&mut deps, "dave", transfer("dave", "2")).unwrap();
+    let msg = QueryMsg::Tokens { owner: "dave".to_string(), start_after: None, limit: None };
+    let tokens: TokensResponse = from_json(query(deps.as_ref(), mock_env(), msg).unwrap()).unwrap();
+    assert_eq!(tokens.tokens, vec!["2".to_string()]);
+    let msg = QueryMsg::Tokens { owner: "alice".to_string(), start_after: None, limit: None };
+    let tokens: TokensResponse = from_json(query(deps.as_ref(), mock_env(), msg).unwrap()).unwrap();
+    assert!(tokens.tokens.is_empty());
+}
+
+#[test]
+fn send_calls_the_receiver() {
+    let mut deps = setup();
+    run(&mut deps, STAKING, mint_msg("1", "alice")).unwrap();
+    let msg = ExecuteMsg::SendNft { contract: "market".to_string(), token_id: "1".to_string(), msg: Binary::from(b"{}") };
+    let res = execute(deps.as_mut(), mock_env(), mock_info("alice", &[]), msg).unwrap();
+    let receive = ReceiverExecuteMsg::ReceiveNft(Cw721ReceiveMsg {
+        sender: "alice".to_string(),
+        token_id: "1".to_string(),
+        msg: Binary::from(b"{}"),
+    });
+    assert_eq!(res.messages[0].msg, CosmosMsg::Wasm(WasmMsg::Execute {
+        contract_addr: "market".to_string(),
+        msg: to_json_binary(&receive).unwrap(),
+        funds: vec![],
+    }));
+    assert_eq!(owner_of(&deps, "1").owner, "market");
+}
+
+#[test]
+fn burn_removes_the_ticket() {
+    let mut deps = setup();
+    run(&mut deps, STAKING, mint_msg("1", "alice")).unwrap();
+    let err = run(&mut deps, "bob", ExecuteMsg::Burn { token_id: "1".to_string() }).unwrap_err();
+    assert!(matches!(err, ContractError::Unauthorized {}));
+    run(&mut deps, "alice", ExecuteMsg::Burn { token_id: "1".to_string() }).unwrap();
+    let msg = QueryMsg::OwnerOf { token_id: "1".to_string(), include_expired: None };
+    assert!(query(deps.as_ref(), mock_env(), msg).is_err());
+    let count: NumTokensResponse = from_json(query(deps.as_ref(), mock_env(), QueryMsg::NumTokens {}).unwrap()).unwrap();
+    assert_eq!(count.count, 0);
+}
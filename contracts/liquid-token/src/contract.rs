@@ -0,0 +1,311 @@
+#[cfg(not(feature = "library"))]
+use cosmwasm_std::entry_point;
+use cosmwasm_std::{
+    to_json_binary, Addr, Binary, Decimal, Deps, DepsMut, Env, MessageInfo, QuerierWrapper, QueryRequest,
+    Response, StdResult, Storage, WasmQuery,
+};
+
+use cw2::set_contract_version;
+use cw20::{BalanceResponse, Cw20ExecuteMsg, MinterResponse};
+use cw20_base::allowances::query_allowance;
+use cw20_base::contract::{query_balance, query_download_logo, query_marketing_info, query_minter, query_token_info};
+use cw20_base::enumerable::{query_all_accounts, query_all_allowances};
+use cw20_base::state::{BALANCES, TOKEN_INFO};
+
+use crate::error::ContractError;
+use crate::msg::{ExecuteMsg, InstantiateMsg, QueryMsg, TotalSupplyAtResponse, NativeBalanceResponse,
+    NativeSupplyResponse, ConfigResponse, PendingSwapResponse, StakingConfigResponse, StakingQueryMsg,
+    StakingStatusResponse};
+use crate::state::{Config, PendingSwapContract, CONFIG, BALANCE_SNAPSHOTS, PENDING_SWAP_CONTRACT,
+    TOTAL_SUPPLY_SNAPSHOTS};
+
+// version info for migration info
+const CONTRACT_NAME: &str = "crates.io:liquid-token";
+const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn instantiate(
+    mut deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: InstantiateMsg,
+) -> Result<Response, ContractError> {
+    let staking_addr = deps.api.addr_validate(&msg.staking_addr)?;
+    let swap_addr = msg.swap_addr.map(|addr| deps.api.addr_validate(&addr)).transpose()?;
+    CONFIG.save(deps.storage, &Config {
+        staking_addr: staking_addr.clone(),
+        swap_addr,
+    })?;
+    // no initial balances, the staking contract mints every token
+    let base_msg = cw20_base::msg::InstantiateMsg {
+        name: msg.name,
+        symbol: msg.symbol,
+        decimals: msg.decimals,
+        initial_balances: vec![],
+        mint: Some(MinterResponse { minter: staking_addr.to_string(), cap: None }),
+        marketing: msg.marketing,
+    };
+    let res = cw20_base::contract::instantiate(deps.branch(), env.clone(), info, base_msg)?;
+    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+    checkpoint_total_supply(deps.storage, env.block.height)?;
+
+    Ok(res)
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn execute(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: ExecuteMsg,
+) -> Result<Response, ContractError> {
+    match msg {
+        ExecuteMsg::Transfer { recipient, amount } => {
+            execute_base(deps, env, info, Cw20ExecuteMsg::Transfer { recipient, amount })
+        },
+        ExecuteMsg::Burn { amount } => execute_base(deps, env, info, Cw20ExecuteMsg::Burn { amount }),
+        ExecuteMsg::Send { contract, amount, msg } => {
+            ensure_send_target(deps.as_ref(), &contract)?;
+            execute_base(deps, env, info, Cw20ExecuteMsg::Send { contract, amount, msg })
+        },
+        ExecuteMsg::IncreaseAllowance { spender, amount, expires } => {
+            execute_base(deps, env, info, Cw20ExecuteMsg::IncreaseAllowance { spender, amount, expires })
+        },
+        ExecuteMsg::DecreaseAllowance { spender, amount, expires } => {
+            execute_base(deps, env, info, Cw20ExecuteMsg::DecreaseAllowance { spender, amount, expires })
+        },
+        ExecuteMsg::TransferFrom { owner, recipient, amount } => {
+            execute_base(deps, env, info, Cw20ExecuteMsg::TransferFrom { owner, recipient, amount })
+        },
+        ExecuteMsg::SendFrom { owner, contract, amount, msg } => {
+            ensure_send_target(deps.as_ref(), &contract)?;
+            execute_base(deps, env, info, Cw20ExecuteMsg::SendFrom { owner, contract, amount, msg })
+        },
+        ExecuteMsg::BurnFrom { owner, amount } => {
+            execute_base(deps, env, info, Cw20ExecuteMsg::BurnFrom { owner, amount })
+        },
+        ExecuteMsg::Mint { recipient, amount } => {
+            execute_base(deps, env, info, Cw20ExecuteMsg::Mint { recipient, amount })
+        },
+        ExecuteMsg::UpdateMarketing { project, description, marketing } => {
+            execute_base(deps, env, info, Cw20ExecuteMsg::UpdateMarketing { project, description, marketing })
+        },
+        ExecuteMsg::UploadLogo(logo) => execute_base(deps, env, info, Cw20ExecuteMsg::UploadLogo(logo)),
+        ExecuteMsg::SetSwapContract { address } => execute_set_swap_contract(deps, env, info, address),
+        ExecuteMsg::ExecuteSwapContract {} => execute_swap_contract(deps, env, info),
+        ExecuteMsg::CancelSwapContract {} => execute_cancel_swap_contract(deps, info),
+    }
+}
+
+pub fn execute_set_swap_contract(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    address: Option<String>,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    let staking_config = query_staking_config(&deps.querier, &config.staking_addr)?;
+    ensure_owner(&staking_config, &info.sender)?;
+    let swap_addr = address.map(|addr| deps.api.addr_validate(&addr)).transpose()?;
+    if staking_config.timelock_delay == 0 {
+        return set_swap_contract(deps, swap_addr, info.sender);
+    }
+    let eta = env.block.time.plus_seconds(staking_config.timelock_delay);
+    PENDING_SWAP_CONTRACT.save(deps.storage, &PendingSwapContract { swap_addr: swap_addr.clone(), eta })?;
+
+    let res = Response::new()
+        .add_attribute("action", "queueSwapContract")
+        .add_attribute("from", info.sender)
+        .add_attribute("address", swap_addr.map(String::from).unwrap_or_default())
+        .add_attribute("eta", eta.seconds().to_string());
+    Ok(res)
+}
+
+pub fn execute_swap_contract(deps: DepsMut, env: Env, info: MessageInfo) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    ensure_owner(&query_staking_config(&deps.querier, &config.staking_addr)?, &info.sender)?;
+    let pending = PENDING_SWAP_CONTRACT
+        .may_load(deps.storage)?
+        .ok_or(ContractError::NoPendingChange {})?;
+    if env.block.time < pending.eta {
+        return Err(ContractError::TimelockNotExpired { eta: pending.eta.seconds() });
+    }
+    PENDING_SWAP_CONTRACT.remove(deps.storage);
+    set_swap_contract(deps, pending.swap_addr, info.sender)
+}
+
+pub fn execute_cancel_swap_contract(deps: DepsMut, info: MessageInfo) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    ensure_owner(&query_staking_config(&deps.querier, &config.staking_addr)?, &info.sender)?;
+    if PENDING_SWAP_CONTRACT.may_load(deps.storage)?.is_none() {
+        return Err(ContractError::NoPendingChange {});
+    }
+    PENDING_SWAP_CONTRACT.remove(deps.storage);
+
+    let res = Response::new()
+        .add_attribute("action", "cancelSwapContract")
+        .add_attribute("from", info.sender);
+    Ok(res)
+}
+
+// set_swap_contract registers the swap contract as a target of Send
+fn set_swap_contract(deps: DepsMut, swap_addr: Option<Addr>, sender: Addr) -> Result<Response, ContractError> {
+    let mut config = CONFIG.load(deps.storage)?;
+    config.swap_addr = swap_addr;
+    CONFIG.save(deps.storage, &config)?;
+
+    let res = Response::new()
+        .add_attribute("action", "setSwapContract")
+        .add_attribute("from", sender)
+        .add_attribute("address", config.swap_addr.map(String::from).unwrap_or_default());
+    Ok(res)
+}
+
+// ensure_owner checks that the sender is the staking contract owner
+fn ensure_owner(staking_config: &StakingConfigResponse, sender: &Addr) -> Result<(), ContractError> {
+    if staking_config.owner.as_deref() != Some(sender.as_str()) {
+        return Err(ContractError::Unauthorized {});
+    }
+    Ok(())
+}
+
+// execute_base runs a cw20 message on cw20-base and checkpoints the balances it changed
+fn execute_base(
+    mut deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: Cw20ExecuteMsg,
+) -> Result<Response, ContractError> {
+    // accounts whose balance the message changes
+    let accounts = match &msg {
+        Cw20ExecuteMsg::Transfer { recipient, .. } => vec![info.sender.to_string(), recipient.clone()],
+        Cw20ExecuteMsg::Send { contract, .. } => vec![info.sender.to_string(), contract.clone()],
+        Cw20ExecuteMsg::Burn { .. } => vec![info.sender.to_string()],
+        Cw20ExecuteMsg::Mint { recipient, .. } => vec![recipient.clone()],
+        Cw20ExecuteMsg::TransferFrom { owner, recipient, .. } => vec![owner.clone(), recipient.clone()],
+        Cw20ExecuteMsg::SendFrom { owner, contract, .. } => vec![owner.clone(), contract.clone()],
+        Cw20ExecuteMsg::BurnFrom { owner, .. } => vec![owner.clone()],
+        _ => vec![],
+    };
+    let res = cw20_base::contract::execute(deps.branch(), env.clone(), info, msg)?;
+
+    for account in accounts {
+        let address = deps.api.addr_validate(&account)?;
+        checkpoint_balance(deps.storage, &address, env.block.height)?;
+    }
+    checkpoint_total_supply(deps.storage, env.block.height)?;
+
+    Ok(res)
+}
+
+// ensure_send_target checks that Send hooks only go to the staking or swap contract
+fn ensure_send_target(deps: Deps, contract: &str) -> Result<(), ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    let contract_addr = deps.api.addr_validate(contract)?;
+    if contract_addr != config.staking_addr && Some(&contract_addr) != config.swap_addr.as_ref() {
+        return Err(ContractError::InvalidSendTarget { contract: contract.to_string() });
+    }
+    Ok(())
+}
+
+// query_staking_config returns the owner and timelock delay of the staking contract
+fn query_staking_config(querier: &QuerierWrapper, staking_addr: &Addr) -> StdResult<StakingConfigResponse> {
+    querier.query(&QueryRequest::Wasm(WasmQuery::Smart {
+        contract_addr: staking_addr.to_string(),
+        msg: to_json_binary(&StakingQueryMsg::ConfigInfo {})?,
+    }))
+}
+
+// query_staking_ratio returns the ratio of native / issued reported by the staking contract
+fn query_staking_ratio(querier: &QuerierWrapper, staking_addr: &Addr) -> StdResult<Decimal> {
+    let response: StakingStatusResponse = querier.query(&QueryRequest::Wasm(WasmQuery::Smart {
+        contract_addr: staking_addr.to_string(),
+        msg: to_json_binary(&StakingQueryMsg::StatusInfo {})?,
+    }))?;
+    Ok(response.ratio)
+}
+
+// checkpoint_balance copies the cw20-base balance of an address into the snapshots
+fn checkpoint_balance(storage: &mut dyn Storage, address: &Addr, height: u64) -> StdResult<()> {
+    let balance = BALANCES.may_load(storage, address)?.unwrap_or_default();
+    if BALANCE_SNAPSHOTS.may_load(storage, address)?.unwrap_or_default() != balance {
+        BALANCE_SNAPSHOTS.save(storage, address, &balance, height)?;
+    }
+    Ok(())
+}
+
+// checkpoint_total_supply copies the cw20-base total supply into the snapshots
+fn checkpoint_total_supply(storage: &mut dyn Storage, height: u64) -> StdResult<()> {
+    let total_supply = TOKEN_INFO.load(storage)?.total_supply;
+    if TOTAL_SUPPLY_SNAPSHOTS.may_load(storage)? != Some(total_supply) {
+        TOTAL_SUPPLY_SNAPSHOTS.save(storage, &total_supply, height)?;
+    }
+    Ok(())
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
+    match msg {
+        QueryMsg::Balance { address } => to_json_binary(&query_balance(deps, address)?),
+        QueryMsg::BalanceAt { address, height } => to_json_binary(&query_balance_at(deps, address, height)?),
+        QueryMsg::TokenInfo {} => to_json_binary(&query_token_info(deps)?),
+        QueryMsg::TotalSupplyAt { height } => to_json_binary(&query_total_supply_at(deps, height)?),
+        QueryMsg::Minter {} => to_json_binary(&query_minter(deps)?),
+        QueryMsg::Allowance { owner, spender } => to_json_binary(&query_allowance(deps, owner, spender)?),
+        QueryMsg::AllAllowances { owner, start_after, limit } => {
+            to_json_binary(&query_all_allowances(deps, owner, start_after, limit)?)
+        },
+        QueryMsg::AllAccounts { start_after, limit } => {
+            to_json_binary(&query_all_accounts(deps, start_after, limit)?)
+        },
+        QueryMsg::MarketingInfo {} => to_json_binary(&query_marketing_info(deps)?),
+        QueryMsg::DownloadLogo {} => to_json_binary(&query_download_logo(deps)?),
+        QueryMsg::NativeBalance { address } => to_json_binary(&query_native_balance(deps, address)?),
+        QueryMsg::NativeSupply {} => to_json_binary(&query_native_supply(deps)?),
+        QueryMsg::ConfigInfo {} => to_json_binary(&query_config(deps)?),
+    }
+}
+
+pub fn query_native_balance(deps: Deps, address: String) -> StdResult<NativeBalanceResponse> {
+    let config = CONFIG.load(deps.storage)?;
+    let balance = query_balance(deps, address)?.balance;
+    let ratio = query_staking_ratio(&deps.querier, &config.staking_addr)?;
+    Ok(NativeBalanceResponse { balance, native: balance * ratio, ratio })
+}
+
+pub fn query_native_supply(deps: Deps) -> StdResult<NativeSupplyResponse> {
+    let config = CONFIG.load(deps.storage)?;
+    let total_supply = TOKEN_INFO.load(deps.storage)?.total_supply;
+    let ratio = query_staking_ratio(&deps.querier, &config.staking_addr)?;
+    Ok(NativeSupplyResponse { total_supply, native: total_supply * ratio, ratio })
+}
+
+pub fn query_config(deps: Deps) -> StdResult<ConfigResponse> {
+    let config = CONFIG.load(deps.storage)?;
+    let staking_config = query_staking_config(&deps.querier, &config.staking_addr)?;
+    let pending_swap = PENDING_SWAP_CONTRACT.may_load(deps.storage)?.map(|pending| PendingSwapResponse {
+        swap_addr: pending.swap_addr.map(String::from),
+        eta: pending.eta.seconds(),
+    });
+    Ok(ConfigResponse {
+        owner: staking_config.owner,
+        staking_addr: config.staking_addr.to_string(),
+        swap_addr: config.swap_addr.map(String::from),
+        pending_swap,
+    })
+}
+
+pub fn query_balance_at(deps: Deps, address: String, height: u64) -> StdResult<BalanceResponse> {
+    let address = deps.api.addr_validate(&address)?;
+    let balance = BALANCE_SNAPSHOTS
+        .may_load_at_height(deps.storage, &address, height)?
+        .unwrap_or_default();
+    Ok(BalanceResponse { balance })
+}
+
+pub fn query_total_supply_at(deps: Deps, height: u64) -> StdResult<TotalSupplyAtResponse> {
+    let total_supply = TOTAL_SUPPLY_SNAPSHOTS
+        .may_load_at_height(deps.storage, height)?
+        .unwrap_or_default();
+    Ok(TotalSupplyAtResponse { total_supply })
+}
@@ -0,0 +1,23 @@
+use cosmwasm_std::StdError;
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum ContractError {
+    #[error("{0}")]
+    Std(#[from] StdError),
+
+    #[error("{0}")]
+    Base(#[from] cw20_base::ContractError),
+
+    #[error("Unauthorized")]
+    Unauthorized {},
+
+    #[error("No pending swap contract change")]
+    NoPendingChange {},
+
+    #[error("Swap contract change can't be executed before {eta}")]
+    TimelockNotExpired { eta: u64 },
+
+    #[error("Tokens can only be sent to the staking or swap contract, not {contract}")]
+    InvalidSendTarget { contract: String },
+}
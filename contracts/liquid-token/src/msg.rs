@@ -0,0 +1,157 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use cosmwasm_std::{Binary, Decimal, Uint128};
+use cw20::{Expiration, Logo};
+use cw20_base::msg::InstantiateMarketingInfo;
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct InstantiateMsg {
+    pub name: String,
+    pub symbol: String,
+    pub decimals: u8,
+    /// This is the staking contract address, the only minter of the token
+    pub staking_addr: String,
+    /// This is the swap contract address, it can also be registered later with SetSwapContract
+    pub swap_addr: Option<String>,
+    pub marketing: Option<InstantiateMarketingInfo>,
+}
+
+/// ExecuteMsg is the cw20 execute interface, plus the registration of the swap contract
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ExecuteMsg {
+    /// Transfer moves tokens to another account
+    Transfer { recipient: String, amount: Uint128 },
+    /// Burn destroys tokens of the sender
+    Burn { amount: Uint128 },
+    /// Send moves tokens to the staking or swap contract and calls its Receive hook with `msg`
+    Send { contract: String, amount: Uint128, msg: Binary },
+    /// IncreaseAllowance lets the spender use more tokens of the sender, `expires` replaces the expiration
+    IncreaseAllowance { spender: String, amount: Uint128, expires: Option<Expiration> },
+    /// DecreaseAllowance lets the spender use fewer tokens of the sender, `expires` replaces the expiration
+    DecreaseAllowance { spender: String, amount: Uint128, expires: Option<Expiration> },
+    /// TransferFrom moves tokens of the owner using the allowance of the sender
+    TransferFrom { owner: String, recipient: String, amount: Uint128 },
+    /// SendFrom moves tokens of the owner to the staking or swap contract using the allowance of the sender,
+    /// and calls its Receive hook with `msg`
+    SendFrom { owner: String, contract: String, amount: Uint128, msg: Binary },
+    /// BurnFrom destroys tokens of the owner using the allowance of the sender
+    BurnFrom { owner: String, amount: Uint128 },
+    /// Mint creates tokens, only the staking contract can call it
+    Mint { recipient: String, amount: Uint128 },
+    /// UpdateMarketing updates the marketing info, none leaves a field unchanged
+    UpdateMarketing { project: Option<String>, description: Option<String>, marketing: Option<String> },
+    /// UploadLogo sets the logo, only the marketing address can call it
+    UploadLogo(Logo),
+    /// Admin call this method to queue the registration of the swap contract, none removes it.
+    /// The admin is the staking contract owner and the change waits for the staking timelock delay
+    SetSwapContract { address: Option<String> },
+    /// Admin call this method to register the queued swap contract once the timelock has passed
+    ExecuteSwapContract {},
+    /// Admin call this method to drop the queued swap contract change
+    CancelSwapContract {},
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum QueryMsg {
+    /// Balance shows the current balance of the address, returns cw20::BalanceResponse
+    Balance { address: String },
+    /// BalanceAt shows the balance of the address at the start of the block `height`,
+    /// returns cw20::BalanceResponse
+    BalanceAt { address: String, height: u64 },
+    /// TokenInfo shows name, symbol, decimals and total supply, returns cw20::TokenInfoResponse
+    TokenInfo {},
+    /// TotalSupplyAt shows the total supply at the start of the block `height`
+    TotalSupplyAt { height: u64 },
+    /// Minter shows the staking contract, returns cw20::MinterResponse
+    Minter {},
+    /// Allowance shows how much the spender can use from the owner account, returns cw20::AllowanceResponse
+    Allowance { owner: String, spender: String },
+    /// AllAllowances shows the allowances given by the owner, returns cw20::AllAllowancesResponse
+    AllAllowances { owner: String, start_after: Option<String>, limit: Option<u32> },
+    /// AllAccounts shows the accounts holding a balance, returns cw20::AllAccountsResponse
+    AllAccounts { start_after: Option<String>, limit: Option<u32> },
+    /// MarketingInfo shows the project, description, marketing address and logo,
+    /// returns cw20::MarketingInfoResponse
+    MarketingInfo {},
+    /// DownloadLogo shows the embedded logo, returns cw20::DownloadLogoResponse
+    DownloadLogo {},
+    /// NativeBalance shows the balance of the address in native tokens at the current staking ratio
+    NativeBalance { address: String },
+    /// NativeSupply shows the total supply in native tokens at the current staking ratio
+    NativeSupply {},
+    /// ConfigInfo shows the admin, the registered staking and swap contracts and the queued swap contract
+    ConfigInfo {},
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct TotalSupplyAtResponse {
+    pub total_supply: Uint128,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct NativeBalanceResponse {
+    /// liquid token balance
+    pub balance: Uint128,
+    /// native tokens the balance is worth
+    pub native: Uint128,
+    /// ratio of native / issued reported by the staking contract
+    pub ratio: Decimal,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct NativeSupplyResponse {
+    /// liquid token total supply
+    pub total_supply: Uint128,
+    /// native tokens the total supply is worth
+    pub native: Uint128,
+    /// ratio of native / issued reported by the staking contract
+    pub ratio: Decimal,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ConfigResponse {
+    /// Admin registering the swap contract, the staking contract owner, none once ownership is renounced
+    pub owner: Option<String>,
+    /// Staking contract, the only minter
+    pub staking_addr: String,
+    /// Swap contract, if registered
+    pub swap_addr: Option<String>,
+    /// Swap contract change waiting for the timelock, if any
+    pub pending_swap: Option<PendingSwapResponse>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PendingSwapResponse {
+    /// Swap contract to register, none removes it
+    pub swap_addr: Option<String>,
+    /// block time in seconds from which the change can be executed
+    pub eta: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum StakingQueryMsg {
+    /// ConfigInfo shows the config of the contract
+    ConfigInfo {},
+    /// StatusInfo shows staking info of the contract
+    StatusInfo {},
+}
+
+/// StakingConfigResponse is the part of the staking ConfigInfo response used here
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct StakingConfigResponse {
+    /// Admin to change config, none once ownership is renounced
+    pub owner: Option<String>,
+    /// Delay in seconds before a queued admin change can be executed
+    pub timelock_delay: u64,
+}
+
+/// StakingStatusResponse is the part of the staking StatusInfo response used here
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct StakingStatusResponse {
+    /// ratio of native / issued (or how many native tokens that one derivative token is nominally worth)
+    pub ratio: Decimal,
+}
@@ -0,0 +1,41 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use cosmwasm_std::{Addr, Timestamp, Uint128};
+use cw_storage_plus::{Item, SnapshotItem, SnapshotMap, Strategy};
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Config {
+    /// Staking contract, the only minter and a target of Send, its owner is the admin of this contract
+    pub staking_addr: Addr,
+    /// Swap contract, a target of Send
+    pub swap_addr: Option<Addr>,
+}
+
+pub const CONFIG: Item<Config> = Item::new("config");
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PendingSwapContract {
+    /// Swap contract to register, none removes it
+    pub swap_addr: Option<Addr>,
+    /// block time from which the change can be executed
+    pub eta: Timestamp,
+}
+
+/// swap contract change waiting for the staking timelock
+pub const PENDING_SWAP_CONTRACT: Item<PendingSwapContract> = Item::new("pending_swap_contract");
+
+/// balances checkpointed on every block they change in, mirrors the cw20-base balances
+pub const BALANCE_SNAPSHOTS: SnapshotMap<&Addr, Uint128> = SnapshotMap::new(
+    "balance_snapshots",
+    "balance_snapshots__checkpoints",
+    "balance_snapshots__changelog",
+    Strategy::EveryBlock,
+);
+/// total supply checkpointed on every block it changes in
+pub const TOTAL_SUPPLY_SNAPSHOTS: SnapshotItem<Uint128> = SnapshotItem::new(
+    "total_supply_snapshots",
+    "total_supply_snapshots__checkpoints",
+    "total_supply_snapshots__changelog",
+    Strategy::EveryBlock,
+);
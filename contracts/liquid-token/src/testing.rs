@@ -0,0 +1,130 @@
+use cosmwasm_std::testing::{mock_dependencies, mock_env, mock_info, MockApi, MockQuerier, MockStorage};
+use cosmwasm_std::{
+    from_json, to_json_binary, ContractResult, Decimal, Env, OwnedDeps, SystemError, SystemResult, Uint128,
+    WasmQuery,
+};
+
+use crate::contract::{execute, instantiate, query};
+use crate::msg::{
+    ConfigResponse, ExecuteMsg, InstantiateMsg, NativeBalanceResponse, QueryMsg, StakingConfigResponse,
+    StakingQueryMsg, StakingStatusResponse,
+};
+use crate::ContractError;
+
+const STAKING: &str = "staking";
+const SWAP: &str = "swap";
+const OWNER: &str = "owner";
+const DAY: u64 = 24 * 60 * 60;
+
+type MockDeps = OwnedDeps<MockStorage, MockApi, MockQuerier>;
+
+// setup instantiates the token with a staking contract owned by `owner`, at a ratio of 1.5
+fn setup(owner: Option<&str>, timelock_delay: u64) -> MockDeps {
+    let mut deps = mock_dependencies();
+    let owner = owner.map(String::from);
+    deps.querier.update_wasm(move |query| match query {
+        WasmQuery::Smart { contract_addr, msg } if contract_addr == STAKING => {
+            let res = match from_json(msg).unwrap() {
+                StakingQueryMsg::ConfigInfo {} => {
+                    to_json_binary(&StakingConfigResponse { owner: owner.clone(), timelock_delay })
+                },
+                StakingQueryMsg::StatusInfo {} => {
+                    to_json_binary(&StakingStatusResponse { ratio: Decimal::percent(150) })
+                },
+            };
+            SystemResult::Ok(ContractResult::Ok(res.unwrap()))
+        },
+        _ => SystemResult::Err(SystemError::UnsupportedRequest { kind: "wasm".to_string() }),
+    });
+    let msg = InstantiateMsg {
+        name: "Liquid Arch".to_string(),
+        symbol: "sARCH".to_string(),
+        decimals: 6,
+        staking_addr: STAKING.to_string(),
+        swap_addr: None,
+        marketing: None,
+    };
+    instantiate(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+    let msg = ExecuteMsg::Mint { recipient: "alice".to_string(), amount: Uint128::new(1_000) };
+    execute(deps.as_mut(), mock_env(), mock_info(STAKING, &[]), msg).unwrap();
+    deps
+}
+
+fn env_at(seconds: u64) -> Env {
+    let mut env = mock_env();
+    env.block.time = env.block.time.plus_seconds(seconds);
+    env
+}
+
+fn config(deps: &MockDeps) -> ConfigResponse {
+    from_json(query(deps.as_ref(), mock_env(), QueryMsg::ConfigInfo {}).unwrap()).unwrap()
+}
+
+fn send(deps: &mut MockDeps, contract: &str) -> Result<(), ContractError> {
+    let msg = ExecuteMsg::Send { contract: contract.to_string(), amount: Uint128::new(10), msg: Default::default() };
+    execute(deps.as_mut(), mock_env(), mock_info("alice", &[]), msg).map(|_| ())
+}
+
+#[test]
+fn send_only_to_staking_and_swap() {
+    let mut deps = setup(Some(OWNER), 0);
+    send(&mut deps, STAKING).unwrap();
+    let err = send(&mut deps, SWAP).unwrap_err();
+    assert!(matches!(err, ContractError::InvalidSendTarget { .. }));
+
+    let msg = ExecuteMsg::SetSwapContract { address: Some(SWAP.to_string()) };
+    execute(deps.as_mut(), mock_env(), mock_info(OWNER, &[]), msg).unwrap();
+    send(&mut deps, SWAP).unwrap();
+}
+
+#[test]
+fn native_balance_uses_staking_ratio() {
+    let deps = setup(Some(OWNER), 0);
+    let msg = QueryMsg::NativeBalance { address: "alice".to_string() };
+    let res: NativeBalanceResponse = from_json(query(deps.as_ref(), mock_env(), msg).unwrap()).unwrap();
+    assert_eq!(res.balance.u128(), 1_000);
+    assert_eq!(res.native.u128(), 1_500);
+}
+
+#[test]
+fn admin_is_the_staking_owner() {
+    let mut deps = setup(Some(OWNER), 0);
+    assert_eq!(config(&deps).owner, Some(OWNER.to_string()));
+    // the instantiator has no rights
+    let msg = ExecuteMsg::SetSwapContract { address: Some(SWAP.to_string()) };
+    let err = execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg.clone()).unwrap_err();
+    assert!(matches!(err, ContractError::Unauthorized {}));
+
+    // nobody once the staking ownership is renounced
+    let mut deps = setup(None, 0);
+    assert_eq!(config(&deps).owner, None);
+    let err = execute(deps.as_mut(), mock_env(), mock_info(OWNER, &[]), msg).unwrap_err();
+    assert!(matches!(err, ContractError::Unauthorized {}));
+}
+
+#[test]
+fn swap_contract_waits_for_the_staking_timelock() {
+    let mut deps = setup(Some(OWNER), DAY);
+    let msg = ExecuteMsg::SetSwapContract { address: Some(SWAP.to_string()) };
+    execute(deps.as_mut(), mock_env(), mock_info(OWNER, &[]), msg).unwrap();
+    let pending = config(&deps).pending_swap.unwrap();
+    assert_eq!(pending.swap_addr, Some(SWAP.to_string()));
+    assert_eq!(pending.eta, env_at(DAY).block.time.seconds());
+    assert!(send(&mut deps, SWAP).is_err());
+
+    let apply = ExecuteMsg::ExecuteSwapContract {};
+    let err = execute(deps.as_mut(), env_at(DAY - 1), mock_info(OWNER, &[]), apply.clone()).unwrap_err();
+    assert!(matches!(err, ContractError::TimelockNotExpired { .. }));
+    execute(deps.as_mut(), env_at(DAY), mock_info(OWNER, &[]), apply.clone()).unwrap();
+    assert_eq!(config(&deps).swap_addr, Some(SWAP.to_string()));
+    assert_eq!(config(&deps).pending_swap, None);
+    send(&mut deps, SWAP).unwrap();
+
+    // a cancelled change can't be executed
+    let msg = ExecuteMsg::SetSwapContract { address: None };
+    execute(deps.as_mut(), mock_env(), mock_info(OWNER, &[]), msg).unwrap();
+    execute(deps.as_mut(), mock_env(), mock_info(OWNER, &[]), ExecuteMsg::CancelSwapContract {}).unwrap();
+    let err = execute(deps.as_mut(), env_at(DAY), mock_info(OWNER, &[]), apply).unwrap_err();
+    assert!(matches!(err, ContractError::NoPendingChange {}));
+    assert_eq!(config(&deps).swap_addr, Some(SWAP.to_string()));
+}
@@ -0,0 +1,368 @@
+#[cfg(not(feature = "library"))]
+use cosmwasm_std::entry_point;
+use cosmwasm_std::{
+    to_json_binary, Addr, Binary, Decimal, Deps, DepsMut, Env, Fraction, MessageInfo, QuerierWrapper,
+    QueryRequest, Response, StdError, StdResult, Storage, Uint128, WasmMsg, WasmQuery,
+};
+
+use cw2::set_contract_version;
+use cw20::{BalanceResponse, Cw20ExecuteMsg, Cw20ReceiveMsg, Expiration, TokenInfoResponse};
+use cw20_base::allowances::{deduct_allowance, query_allowance};
+
+use crate::error::ContractError;
+use crate::msg::{ExecuteMsg, InstantiateMsg, QueryMsg, SharesOfResponse, ConfigResponse,
+    StakingQueryMsg, StakingStatusResponse};
+use crate::state::{Config, CONFIG, SHARES, TOTAL_SHARES};
+
+// version info for migration info
+const CONTRACT_NAME: &str = "crates.io:rebasing-token";
+const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn instantiate(
+    deps: DepsMut,
+    _env: Env,
+    _info: MessageInfo,
+    msg: InstantiateMsg,
+) -> Result<Response, ContractError> {
+    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+
+    let config = Config {
+        name: msg.name,
+        symbol: msg.symbol,
+        decimals: msg.decimals,
+        liquid_token_addr: deps.api.addr_validate(&msg.liquid_token_addr)?,
+        staking_addr: deps.api.addr_validate(&msg.staking_addr)?,
+    };
+    CONFIG.save(deps.storage, &config)?;
+    TOTAL_SHARES.save(deps.storage, &Uint128::zero())?;
+
+    Ok(Response::default())
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn execute(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: ExecuteMsg,
+) -> Result<Response, ContractError> {
+    match msg {
+        ExecuteMsg::Wrap { amount } => execute_wrap(deps, env, info, amount),
+        ExecuteMsg::Unwrap { amount } => execute_unwrap(deps, info, amount),
+        ExecuteMsg::UnwrapShares { shares } => execute_unwrap_shares(deps, info, shares),
+        ExecuteMsg::Transfer { recipient, amount } => execute_transfer(deps, info, recipient, amount),
+        ExecuteMsg::Send { contract, amount, msg } => execute_send(deps, info, contract, amount, msg),
+        ExecuteMsg::IncreaseAllowance { spender, amount, expires } => {
+            execute_increase_allowance(deps, env, info, spender, amount, expires)
+        },
+        ExecuteMsg::DecreaseAllowance { spender, amount, expires } => {
+            execute_decrease_allowance(deps, env, info, spender, amount, expires)
+        },
+        ExecuteMsg::TransferFrom { owner, recipient, amount } => {
+            execute_transfer_from(deps, env, info, owner, recipient, amount)
+        },
+        ExecuteMsg::SendFrom { owner, contract, amount, msg } => {
+            execute_send_from(deps, env, info, owner, contract, amount, msg)
+        },
+    }
+}
+
+// query_staking_ratio returns the ratio of native / issued reported by the staking contract
+fn query_staking_ratio(querier: &QuerierWrapper, staking_addr: &Addr) -> StdResult<Decimal> {
+    let response: StakingStatusResponse = querier.query(&QueryRequest::Wasm(WasmQuery::Smart {
+        contract_addr: staking_addr.to_string(),
+        msg: to_json_binary(&StakingQueryMsg::StatusInfo {})?,
+    }))?;
+    if response.ratio == Decimal::zero() {
+        return Err(StdError::generic_err("Staking ratio is zero"));
+    }
+    Ok(response.ratio)
+}
+
+// to_balance returns the rebased balance of shares, rounded down
+fn to_balance(shares: Uint128, ratio: Decimal) -> Uint128 {
+    shares.multiply_ratio(ratio.numerator(), ratio.denominator())
+}
+
+// to_shares returns the shares needed for a rebased amount, rounded up so moving them
+// always moves at least the amount
+fn to_shares(amount: Uint128, ratio: Decimal) -> Uint128 {
+    let shares = amount.multiply_ratio(ratio.denominator(), ratio.numerator());
+    if to_balance(shares, ratio) < amount {
+        shares + Uint128::new(1)
+    } else {
+        shares
+    }
+}
+
+// take_shares removes the shares worth a rebased amount from an address and returns them
+fn take_shares(
+    storage: &mut dyn Storage,
+    address: &Addr,
+    amount: Uint128,
+    ratio: Decimal,
+) -> Result<Uint128, ContractError> {
+    if amount.is_zero() {
+        return Err(ContractError::ZeroAmount {});
+    }
+    let shares = to_shares(amount, ratio);
+    let available = SHARES.may_load(storage, address)?.unwrap_or_default();
+    if shares > available {
+        return Err(ContractError::InsufficientBalance { balance: to_balance(available, ratio) });
+    }
+    SHARES.save(storage, address, &(available - shares))?;
+    Ok(shares)
+}
+
+fn add_shares(storage: &mut dyn Storage, address: &Addr, shares: Uint128) -> StdResult<()> {
+    SHARES.update(storage, address, |current: Option<Uint128>| -> StdResult<_> {
+        Ok(current.unwrap_or_default() + shares)
+    })?;
+    Ok(())
+}
+
+pub fn execute_wrap(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    amount: Uint128,
+) -> Result<Response, ContractError> {
+    if amount.is_zero() {
+        return Err(ContractError::ZeroAmount {});
+    }
+    let config = CONFIG.load(deps.storage)?;
+    // one share per wrapped liquid token
+    add_shares(deps.storage, &info.sender, amount)?;
+    TOTAL_SHARES.update(deps.storage, |total| -> StdResult<_> { Ok(total + amount) })?;
+
+    let res = Response::new()
+        .add_message(WasmMsg::Execute {
+            contract_addr: config.liquid_token_addr.to_string(),
+            msg: to_json_binary(&Cw20ExecuteMsg::TransferFrom {
+                owner: info.sender.to_string(),
+                recipient: env.contract.address.to_string(),
+                amount,
+            })?,
+            funds: vec![],
+        })
+        .add_attribute("action", "wrap")
+        .add_attribute("from", info.sender)
+        .add_attribute("shares", amount);
+    Ok(res)
+}
+
+pub fn execute_unwrap(deps: DepsMut, info: MessageInfo, amount: Uint128) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    let ratio = query_staking_ratio(&deps.querier, &config.staking_addr)?;
+    let shares = take_shares(deps.storage, &info.sender, amount, ratio)?;
+    unwrap(deps, &config, info.sender, amount, shares)
+}
+
+pub fn execute_unwrap_shares(deps: DepsMut, info: MessageInfo, shares: Uint128) -> Result<Response, ContractError> {
+    if shares.is_zero() {
+        return Err(ContractError::ZeroAmount {});
+    }
+    let config = CONFIG.load(deps.storage)?;
+    let ratio = query_staking_ratio(&deps.querier, &config.staking_addr)?;
+    let available = SHARES.may_load(deps.storage, &info.sender)?.unwrap_or_default();
+    if shares > available {
+        return Err(ContractError::InsufficientShares { shares: available });
+    }
+    SHARES.save(deps.storage, &info.sender, &(available - shares))?;
+    unwrap(deps, &config, info.sender, to_balance(shares, ratio), shares)
+}
+
+// unwrap burns shares already taken from the sender and gives back as many liquid tokens
+fn unwrap(
+    deps: DepsMut,
+    config: &Config,
+    sender: Addr,
+    amount: Uint128,
+    shares: Uint128,
+) -> Result<Response, ContractError> {
+    TOTAL_SHARES.update(deps.storage, |total| -> StdResult<_> {
+        total.checked_sub(shares).map_err(StdError::overflow)
+    })?;
+
+    let res = Response::new()
+        .add_message(WasmMsg::Execute {
+            contract_addr: config.liquid_token_addr.to_string(),
+            msg: to_json_binary(&Cw20ExecuteMsg::Transfer { recipient: sender.to_string(), amount: shares })?,
+            funds: vec![],
+        })
+        .add_attribute("action", "unwrap")
+        .add_attribute("from", sender)
+        .add_attribute("amount", amount)
+        .add_attribute("shares", shares);
+    Ok(res)
+}
+
+pub fn execute_transfer(
+    deps: DepsMut,
+    info: MessageInfo,
+    recipient: String,
+    amount: Uint128,
+) -> Result<Response, ContractError> {
+    let recipient = deps.api.addr_validate(&recipient)?;
+    let config = CONFIG.load(deps.storage)?;
+    let ratio = query_staking_ratio(&deps.querier, &config.staking_addr)?;
+    let shares = take_shares(deps.storage, &info.sender, amount, ratio)?;
+    add_shares(deps.storage, &recipient, shares)?;
+
+    let res = Response::new()
+        .add_attribute("action", "transfer")
+        .add_attribute("from", info.sender)
+        .add_attribute("to", recipient)
+        .add_attribute("amount", amount)
+        .add_attribute("shares", shares);
+    Ok(res)
+}
+
+pub fn execute_send(
+    deps: DepsMut,
+    info: MessageInfo,
+    contract: String,
+    amount: Uint128,
+    msg: Binary,
+) -> Result<Response, ContractError> {
+    let contract = deps.api.addr_validate(&contract)?;
+    let config = CONFIG.load(deps.storage)?;
+    let ratio = query_staking_ratio(&deps.querier, &config.staking_addr)?;
+    let shares = take_shares(deps.storage, &info.sender, amount, ratio)?;
+    add_shares(deps.storage, &contract, shares)?;
+
+    let receive_msg = Cw20ReceiveMsg { sender: info.sender.to_string(), amount, msg };
+    let res = Response::new()
+        .add_message(receive_msg.into_cosmos_msg(contract.to_string())?)
+        .add_attribute("action", "send")
+        .add_attribute("from", info.sender)
+        .add_attribute("to", contract)
+        .add_attribute("amount", amount)
+        .add_attribute("shares", shares);
+    Ok(res)
+}
+
+pub fn execute_increase_allowance(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    spender: String,
+    amount: Uint128,
+    expires: Option<Expiration>,
+) -> Result<Response, ContractError> {
+    // allowances are kept in rebased units, as cw20-base does it
+    Ok(cw20_base::allowances::execute_increase_allowance(deps, env, info, spender, amount, expires)?)
+}
+
+pub fn execute_decrease_allowance(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    spender: String,
+    amount: Uint128,
+    expires: Option<Expiration>,
+) -> Result<Response, ContractError> {
+    Ok(cw20_base::allowances::execute_decrease_allowance(deps, env, info, spender, amount, expires)?)
+}
+
+pub fn execute_transfer_from(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    owner: String,
+    recipient: String,
+    amount: Uint128,
+) -> Result<Response, ContractError> {
+    let owner = deps.api.addr_validate(&owner)?;
+    let recipient = deps.api.addr_validate(&recipient)?;
+    deduct_allowance(deps.storage, &owner, &info.sender, &env.block, amount)?;
+    let config = CONFIG.load(deps.storage)?;
+    let ratio = query_staking_ratio(&deps.querier, &config.staking_addr)?;
+    let shares = take_shares(deps.storage, &owner, amount, ratio)?;
+    add_shares(deps.storage, &recipient, shares)?;
+
+    let res = Response::new()
+        .add_attribute("action", "transferFrom")
+        .add_attribute("from", owner)
+        .add_attribute("to", recipient)
+        .add_attribute("by", info.sender)
+        .add_attribute("amount", amount)
+        .add_attribute("shares", shares);
+    Ok(res)
+}
+
+pub fn execute_send_from(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    owner: String,
+    contract: String,
+    amount: Uint128,
+    msg: Binary,
+) -> Result<Response, ContractError> {
+    let owner = deps.api.addr_validate(&owner)?;
+    let contract = deps.api.addr_validate(&contract)?;
+    deduct_allowance(deps.storage, &owner, &info.sender, &env.block, amount)?;
+    let config = CONFIG.load(deps.storage)?;
+    let ratio = query_staking_ratio(&deps.querier, &config.staking_addr)?;
+    let shares = take_shares(deps.storage, &owner, amount, ratio)?;
+    add_shares(deps.storage, &contract, shares)?;
+
+    let receive_msg = Cw20ReceiveMsg { sender: info.sender.to_string(), amount, msg };
+    let res = Response::new()
+        .add_message(receive_msg.into_cosmos_msg(contract.to_string())?)
+        .add_attribute("action", "sendFrom")
+        .add_attribute("from", owner)
+        .add_attribute("to", contract)
+        .add_attribute("by", info.sender)
+        .add_attribute("amount", amount)
+        .add_attribute("shares", shares);
+    Ok(res)
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
+    match msg {
+        QueryMsg::Balance { address } => to_json_binary(&query_balance(deps, address)?),
+        QueryMsg::TokenInfo {} => to_json_binary(&query_token_info(deps)?),
+        QueryMsg::Allowance { owner, spender } => to_json_binary(&query_allowance(deps, owner, spender)?),
+        QueryMsg::SharesOf { address } => to_json_binary(&query_shares_of(deps, address)?),
+        QueryMsg::ConfigInfo {} => to_json_binary(&query_config(deps)?),
+    }
+}
+
+pub fn query_balance(deps: Deps, address: String) -> StdResult<BalanceResponse> {
+    let shares = query_shares_of(deps, address)?;
+    Ok(BalanceResponse { balance: shares.balance })
+}
+
+pub fn query_token_info(deps: Deps) -> StdResult<TokenInfoResponse> {
+    let config = CONFIG.load(deps.storage)?;
+    let total_shares = TOTAL_SHARES.load(deps.storage)?;
+    let ratio = query_staking_ratio(&deps.querier, &config.staking_addr)?;
+
+    Ok(TokenInfoResponse {
+        name: config.name,
+        symbol: config.symbol,
+        decimals: config.decimals,
+        total_supply: to_balance(total_shares, ratio),
+    })
+}
+
+pub fn query_shares_of(deps: Deps, address: String) -> StdResult<SharesOfResponse> {
+    let address = deps.api.addr_validate(&address)?;
+    let config = CONFIG.load(deps.storage)?;
+    let shares = SHARES.may_load(deps.storage, &address)?.unwrap_or_default();
+    let ratio = query_staking_ratio(&deps.querier, &config.staking_addr)?;
+
+    Ok(SharesOfResponse { shares, balance: to_balance(shares, ratio), ratio })
+}
+
+pub fn query_config(deps: Deps) -> StdResult<ConfigResponse> {
+    let config = CONFIG.load(deps.storage)?;
+    Ok(ConfigResponse {
+        liquid_token_addr: config.liquid_token_addr.to_string(),
+        staking_addr: config.staking_addr.to_string(),
+    })
+}
@@ -0,0 +1,20 @@
+use cosmwasm_std::{StdError, Uint128};
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum ContractError {
+    #[error("{0}")]
+    Std(#[from] StdError),
+
+    #[error("{0}")]
+    Base(#[from] cw20_base::ContractError),
+
+    #[error("Amount must be more than zero")]
+    ZeroAmount {},
+
+    #[error("Insufficient balance, only {balance} available")]
+    InsufficientBalance { balance: Uint128 },
+
+    #[error("Insufficient shares, only {shares} available")]
+    InsufficientShares { shares: Uint128 },
+}
@@ -0,0 +1,91 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use cosmwasm_std::{Binary, Decimal, Uint128};
+use cw20::Expiration;
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct InstantiateMsg {
+    pub name: String,
+    pub symbol: String,
+    pub decimals: u8,
+    /// This is the liquid token contract address
+    pub liquid_token_addr: String,
+    /// This is the staking contract address
+    pub staking_addr: String,
+}
+
+/// ExecuteMsg is the cw20 transfer and allowance interface plus wrapping, amounts are in rebased
+/// units (native tokens) unless said otherwise
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ExecuteMsg {
+    /// Wrap takes `amount` liquid tokens from the sender, using the allowance given to this contract,
+    /// and credits the sender with as many shares
+    Wrap { amount: Uint128 },
+    /// Unwrap takes the shares worth `amount` from the sender and gives back as many liquid tokens
+    Unwrap { amount: Uint128 },
+    /// UnwrapShares takes exactly `shares` from the sender and gives back as many liquid tokens,
+    /// it lets a holder exit with every share where Unwrap rounds up
+    UnwrapShares { shares: Uint128 },
+    /// Transfer moves tokens to another account
+    Transfer { recipient: String, amount: Uint128 },
+    /// Send moves tokens to a contract and calls its Receive hook with `msg`
+    Send { contract: String, amount: Uint128, msg: Binary },
+    /// IncreaseAllowance lets the spender use more tokens of the sender, `expires` replaces the expiration
+    IncreaseAllowance { spender: String, amount: Uint128, expires: Option<Expiration> },
+    /// DecreaseAllowance lets the spender use fewer tokens of the sender, `expires` replaces the expiration
+    DecreaseAllowance { spender: String, amount: Uint128, expires: Option<Expiration> },
+    /// TransferFrom moves tokens of the owner using the allowance of the sender
+    TransferFrom { owner: String, recipient: String, amount: Uint128 },
+    /// SendFrom moves tokens of the owner to a contract using the allowance of the sender,
+    /// and calls its Receive hook with `msg`
+    SendFrom { owner: String, contract: String, amount: Uint128, msg: Binary },
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum QueryMsg {
+    /// Balance shows the rebased balance of the address, returns cw20::BalanceResponse
+    Balance { address: String },
+    /// TokenInfo shows name, symbol, decimals and rebased total supply, returns cw20::TokenInfoResponse
+    TokenInfo {},
+    /// Allowance shows how much the spender can use from the owner account, returns cw20::AllowanceResponse
+    Allowance { owner: String, spender: String },
+    /// SharesOf shows the shares (wrapped liquid tokens) of the address
+    SharesOf { address: String },
+    /// ConfigInfo shows the wrapped liquid token and the staking contract
+    ConfigInfo {},
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct SharesOfResponse {
+    /// shares of the address
+    pub shares: Uint128,
+    /// rebased balance, shares × ratio rounded down
+    pub balance: Uint128,
+    /// ratio of native / issued reported by the staking contract
+    pub ratio: Decimal,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ConfigResponse {
+    /// Liquid token wrapped by this contract
+    pub liquid_token_addr: String,
+    /// Staking contract reporting the ratio balances are rebased with
+    pub staking_addr: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum StakingQueryMsg {
+    /// StatusInfo shows staking info of the contract
+    StatusInfo {},
+}
+
+/// StakingStatusResponse is the part of the staking StatusInfo response used here
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct StakingStatusResponse {
+    /// ratio of native / issued (or how many native tokens that one derivative token is nominally worth)
+    pub ratio: Decimal,
+}
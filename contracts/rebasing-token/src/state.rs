@@ -0,0 +1,21 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use cosmwasm_std::{Addr, Uint128};
+use cw_storage_plus::{Item, Map};
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Config {
+    pub name: String,
+    pub symbol: String,
+    pub decimals: u8,
+    /// Liquid token wrapped by this contract
+    pub liquid_token_addr: Addr,
+    /// Staking contract reporting the ratio balances are rebased with
+    pub staking_addr: Addr,
+}
+
+pub const CONFIG: Item<Config> = Item::new("config");
+/// shares of an address, one share is one wrapped liquid token
+pub const SHARES: Map<&Addr, Uint128> = Map::new("shares");
+pub const TOTAL_SHARES: Item<Uint128> = Item::new("total_shares");
@@ -0,0 +1,115 @@
+use cosmwasm_std::testing::{mock_dependencies, mock_env, mock_info, MockApi, MockQuerier, MockStorage};
+use cosmwasm_std::{
+    from_json, to_json_binary, ContractResult, CosmosMsg, Decimal, OwnedDeps, SubMsg, SystemError,
+    SystemResult, Uint128, WasmMsg, WasmQuery,
+};
+use cw20::{BalanceResponse, Cw20ExecuteMsg, TokenInfoResponse};
+
+use crate::contract::{execute, instantiate, query};
+use crate::msg::{ExecuteMsg, InstantiateMsg, QueryMsg, SharesOfResponse, StakingStatusResponse};
+use crate::ContractError;
+
+const STAKING: &str = "staking";
+const LIQUID_TOKEN: &str = "liquid_token";
+
+type MockDeps = OwnedDeps<MockStorage, MockApi, MockQuerier>;
+
+// setup instantiates the wrapper at a staking ratio of 1.5 and has alice wrap `shares`
+fn setup(shares: u128) -> MockDeps {
+    let mut deps = mock_dependencies();
+    deps.querier.update_wasm(|query| match query {
+        WasmQuery::Smart { contract_addr, .. } if contract_addr == STAKING => {
+            let res = to_json_binary(&StakingStatusResponse { ratio: Decimal::percent(150) }).unwrap();
+            SystemResult::Ok(ContractResult::Ok(res))
+        },
+        _ => SystemResult::Err(SystemError::UnsupportedRequest { kind: "wasm".to_string() }),
+    });
+    let msg = InstantiateMsg {
+        name: "Rebasing Arch".to_string(),
+        symbol: "rARCH".to_string(),
+        decimals: 6,
+        liquid_token_addr: LIQUID_TOKEN.to_string(),
+        staking_addr: STAKING.to_string(),
+    };
+    instantiate(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+    let msg = ExecuteMsg::Wrap { amount: Uint128::new(shares) };
+    execute(deps.as_mut(), mock_env(), mock_info("alice", &[]), msg).unwrap();
+    deps
+}
+
+fn shares_of(deps: &MockDeps, address: &str) -> SharesOfResponse {
+    let msg = QueryMsg::SharesOf { address: address.to_string() };
+    from_json(query(deps.as_ref(), mock_env(), msg).unwrap()).unwrap()
+}
+
+fn unwrapped(msgs: &[SubMsg]) -> Uint128 {
+    match &msgs[0].msg {
+        CosmosMsg::Wasm(WasmMsg::Execute { contract_addr, msg, .. }) if contract_addr == LIQUID_TOKEN => {
+            match from_json(msg).unwrap() {
+                Cw20ExecuteMsg::Transfer { recipient, amount } if recipient == "alice" => amount,
+                msg => panic!("unexpected message {:?}", msg),
+            }
+        },
+        msg => panic!("unexpected message {:?}", msg),
+    }
+}
+
+#[test]
+fn balances_round_down() {
+    let deps = setup(3);
+    let res = shares_of(&deps, "alice");
+    assert_eq!(res.shares.u128(), 3);
+    assert_eq!(res.balance.u128(), 4);
+    let balance: BalanceResponse =
+        from_json(query(deps.as_ref(), mock_env(), QueryMsg::Balance { address: "alice".to_string() }).unwrap())
+            .unwrap();
+    assert_eq!(balance.balance.u128(), 4);
+    let info: TokenInfoResponse = from_json(query(deps.as_ref(), mock_env(), QueryMsg::TokenInfo {}).unwrap()).unwrap();
+    assert_eq!(info.total_supply.u128(), 4);
+}
+
+#[test]
+fn transfers_round_up_against_the_sender() {
+    let mut deps = setup(3);
+    // 1 rebased unit costs a whole share worth 1.5
+    let msg = ExecuteMsg::Transfer { recipient: "bob".to_string(), amount: Uint128::new(1) };
+    execute(deps.as_mut(), mock_env(), mock_info("alice", &[]), msg).unwrap();
+    assert_eq!(shares_of(&deps, "alice").shares.u128(), 2);
+    assert_eq!(shares_of(&deps, "bob").shares.u128(), 1);
+    assert_eq!(shares_of(&deps, "bob").balance.u128(), 1);
+
+    // 2 rebased units need 2 shares, bob only holds one
+    let msg = ExecuteMsg::Transfer { recipient: "alice".to_string(), amount: Uint128::new(2) };
+    let err = execute(deps.as_mut(), mock_env(), mock_info("bob", &[]), msg).unwrap_err();
+    assert!(matches!(err, ContractError::InsufficientBalance { balance } if balance.u128() == 1));
+}
+
+#[test]
+fn unwrap_takes_the_shares_worth_the_amount() {
+    let mut deps = setup(3);
+    let msg = ExecuteMsg::Unwrap { amount: Uint128::new(2) };
+    let res = execute(deps.as_mut(), mock_env(), mock_info("alice", &[]), msg).unwrap();
+    assert_eq!(unwrapped(&res.messages).u128(), 2);
+    assert_eq!(shares_of(&deps, "alice").shares.u128(), 1);
+
+    let msg = ExecuteMsg::Unwrap { amount: Uint128::zero() };
+    let err = execute(deps.as_mut(), mock_env(), mock_info("alice", &[]), msg).unwrap_err();
+    assert!(matches!(err, ContractError::ZeroAmount {}));
+}
+
+#[test]
+fn unwrap_shares_takes_exactly_the_shares() {
+    let mut deps = setup(3);
+    let msg = ExecuteMsg::UnwrapShares { shares: Uint128::new(4) };
+    let err = execute(deps.as_mut(), mock_env(), mock_info("alice", &[]), msg).unwrap_err();
+    assert!(matches!(err, ContractError::InsufficientShares { shares } if shares.u128() == 3));
+
+    let msg = ExecuteMsg::UnwrapShares { shares: Uint128::new(3) };
+    let res = execute(deps.as_mut(), mock_env(), mock_info("alice", &[]), msg).unwrap();
+    assert_eq!(unwrapped(&res.messages).u128(), 3);
+    let amount = res.attributes.iter().find(|attr| attr.key == "amount").unwrap();
+    assert_eq!(amount.value, "4");
+    assert!(shares_of(&deps, "alice").shares.is_zero());
+    let info: TokenInfoResponse = from_json(query(deps.as_ref(), mock_env(), QueryMsg::TokenInfo {}).unwrap()).unwrap();
+    assert!(info.total_supply.is_zero());
+}
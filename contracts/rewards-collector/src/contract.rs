@@ -0,0 +1,81 @@
+#[cfg(not(feature = "library"))]
+use cosmwasm_std::entry_point;
+use cosmwasm_std::{
+    to_json_binary, Binary, Deps, DepsMut, Env, MessageInfo, Response, StdResult, WasmMsg,
+};
+use cw2::set_contract_version;
+
+use crate::error::ContractError;
+use crate::msg::{ConfigResponse, ExecuteMsg, InstantiateMsg, QueryMsg, StakingExecuteMsg};
+use crate::state::{Config, CONFIG};
+
+// version info for migration info
+const CONTRACT_NAME: &str = "crates.io:liquid-rewards-collector";
+const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn instantiate(
+    deps: DepsMut,
+    _env: Env,
+    _info: MessageInfo,
+    msg: InstantiateMsg,
+) -> Result<Response, ContractError> {
+    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+
+    let config = Config {
+        staking_addr: deps.api.addr_validate(&msg.staking_addr)?,
+        bond_denom: deps.querier.query_bonded_denom()?,
+    };
+    CONFIG.save(deps.storage, &config)?;
+
+    Ok(Response::default())
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn execute(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: ExecuteMsg,
+) -> Result<Response, ContractError> {
+    match msg {
+        ExecuteMsg::Forward {} => execute_forward(deps, env, info),
+    }
+}
+
+pub fn execute_forward(deps: DepsMut, env: Env, info: MessageInfo) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    let balance = deps
+        .querier
+        .query_balance(&env.contract.address, &config.bond_denom)?;
+
+    // nothing to deposit is not an error, the staking contract forwards on every check
+    let mut res = Response::new();
+    if !balance.amount.is_zero() {
+        res = res.add_message(WasmMsg::Execute {
+            contract_addr: config.staking_addr.to_string(),
+            msg: to_json_binary(&StakingExecuteMsg::DepositRewards {})?,
+            funds: vec![balance.clone()],
+        });
+    }
+    res = res
+        .add_attribute("action", "forward")
+        .add_attribute("from", info.sender)
+        .add_attribute("amount", balance.amount);
+    Ok(res)
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
+    match msg {
+        QueryMsg::ConfigInfo {} => to_json_binary(&query_config(deps)?),
+    }
+}
+
+pub fn query_config(deps: Deps) -> StdResult<ConfigResponse> {
+    let config = CONFIG.load(deps.storage)?;
+    Ok(ConfigResponse {
+        staking_addr: config.staking_addr.to_string(),
+        bond_denom: config.bond_denom,
+    })
+}
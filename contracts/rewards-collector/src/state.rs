@@ -0,0 +1,15 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use cosmwasm_std::Addr;
+use cw_storage_plus::Item;
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Config {
+    /// Staking contract the rewards are deposited to
+    pub staking_addr: Addr,
+    /// Denomination of the delegator rewards
+    pub bond_denom: String,
+}
+
+pub const CONFIG: Item<Config> = Item::new("config");
@@ -0,0 +1,31 @@
+use cosmwasm_std::testing::{mock_dependencies, mock_env, mock_info, MOCK_CONTRACT_ADDR};
+use cosmwasm_std::{coins, from_json, to_json_binary, CosmosMsg, WasmMsg};
+
+use crate::contract::{execute, instantiate, query};
+use crate::msg::{ConfigResponse, ExecuteMsg, InstantiateMsg, QueryMsg, StakingExecuteMsg};
+
+const BOND_DENOM: &str = "uarch";
+const STAKING: &str = "staking";
+
+#[test]
+fn forward_deposits_the_balance() {
+    let mut deps = mock_dependencies();
+    deps.querier.update_staking(BOND_DENOM, &[], &[]);
+    let msg = InstantiateMsg { staking_addr: STAKING.to_string() };
+    instantiate(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+    let config: ConfigResponse = from_json(query(deps.as_ref(), mock_env(), QueryMsg::ConfigInfo {}).unwrap()).unwrap();
+    assert_eq!(config.bond_denom, BOND_DENOM);
+
+    // nothing to forward yet
+    let res = execute(deps.as_mut(), mock_env(), mock_info("anyone", &[]), ExecuteMsg::Forward {}).unwrap();
+    assert!(res.messages.is_empty());
+
+    deps.querier.update_balance(MOCK_CONTRACT_ADDR, coins(1_234, BOND_DENOM));
+    let res = execute(deps.as_mut(), mock_env(), mock_info("anyone", &[]), ExecuteMsg::Forward {}).unwrap();
+    assert_eq!(res.messages.len(), 1);
+    assert_eq!(res.messages[0].msg, CosmosMsg::Wasm(WasmMsg::Execute {
+        contract_addr: STAKING.to_string(),
+        msg: to_json_binary(&StakingExecuteMsg::DepositRewards {}).unwrap(),
+        funds: coins(1_234, BOND_DENOM),
+    }));
+}
@@ -0,0 +1,78 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use cosmwasm_std::{Binary, Coin, CosmosMsg, CustomMsg, CustomQuery};
+
+/// ArchwayMsg is the custom message handled by the Archway rewards module
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ArchwayMsg {
+    /// UpdateContractMetadata sets the metadata owner and the address receiving the contract rewards
+    UpdateContractMetadata {
+        owner_address: Option<String>,
+        rewards_address: Option<String>,
+    },
+    /// WithdrawRewards sends the accumulated rewards records to the rewards address,
+    /// either the oldest `records_limit` records or the given `record_ids`
+    WithdrawRewards {
+        records_limit: Option<u64>,
+        record_ids: Vec<u64>,
+    },
+}
+
+impl CustomMsg for ArchwayMsg {}
+
+impl From<ArchwayMsg> for CosmosMsg<ArchwayMsg> {
+    fn from(msg: ArchwayMsg) -> Self {
+        CosmosMsg::Custom(msg)
+    }
+}
+
+/// ArchwayQuery is the custom query handled by the Archway rewards module
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ArchwayQuery {
+    /// ContractMetadata shows the metadata of a contract
+    ContractMetadata { contract_address: String },
+    /// RewardsRecords shows the rewards records waiting to be withdrawn by a rewards address
+    RewardsRecords {
+        rewards_address: String,
+        pagination: Option<PageRequest>,
+    },
+}
+
+impl CustomQuery for ArchwayQuery {}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema, Default)]
+pub struct PageRequest {
+    pub key: Option<Binary>,
+    pub limit: Option<u64>,
+    pub count_total: bool,
+    pub reverse: bool,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PageResponse {
+    pub next_key: Option<Binary>,
+    pub total: Option<u64>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ContractMetadataResponse {
+    pub owner_address: String,
+    pub rewards_address: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct RewardsRecord {
+    pub id: u64,
+    pub rewards_address: String,
+    pub rewards: Vec<Coin>,
+    pub calculated_height: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct RewardsRecordsResponse {
+    pub records: Vec<RewardsRecord>,
+    pub pagination: Option<PageResponse>,
+}
@@ -0,0 +1,10 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// CollectorExecuteMsg is the execute interface of the rewards collector contract
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum CollectorExecuteMsg {
+    /// Forward deposits the rewards held by the collector back with DepositRewards
+    Forward {},
+}
@@ -1,23 +1,53 @@
+use std::collections::BTreeMap;
+
 #[cfg(not(feature = "library"))]
 use cosmwasm_std::entry_point;
 use cosmwasm_std::{
-    coin, coins, to_binary, Addr, BankMsg, Binary, Decimal, Deps, DepsMut, 
-    DistributionMsg, Env, MessageInfo, QuerierWrapper, QueryRequest, WasmQuery,
-    Response, StakingMsg, StdError, StdResult, Uint128, WasmMsg
+    coin, coins, to_json_binary, Addr, BankMsg, Binary, CosmosMsg, Decimal, Deps, DepsMut, 
+    DistributionMsg, Env, GovMsg, MessageInfo, QuerierWrapper, QueryRequest, Reply, Storage, SubMsg, SubMsgResult, WasmQuery,
+    Empty, Order, Response, StakingMsg, StdError, StdResult, Timestamp, Uint128, VoteOption, WasmMsg,
+    WeightedVoteOption,
 };
+use cw_storage_plus::Bound;
 
 use cw2::set_contract_version;
-use cw20::{BalanceResponse, Cw20Contract, Cw20ExecuteMsg, Cw20ReceiveMsg, 
+use cw20::{BalanceResponse, Cw20ExecuteMsg, Cw20ReceiveMsg, 
     TokenInfoResponse, Cw20QueryMsg};
 
-use crate::linked_list::{LinkedList, NodeWithId, node_update_value, linked_list, linked_list_read,
-    linked_list_append, linked_list_remove_head, linked_list_get_list};
+use crate::archway::{ArchwayMsg, ArchwayQuery, PageRequest, RewardsRecordsResponse};
+use crate::collector::CollectorExecuteMsg;
+use crate::proto::cancel_unbonding_delegation;
+use crate::hooks::HookExecuteMsg;
+use crate::gov::SnapshotQueryMsg;
+use crate::ticket::{OwnerOfResponse, TicketExecuteMsg, TicketMetadata, TicketMintMsg, TicketQueryMsg};
+use crate::tokenfactory;
 use crate::error::ContractError;
 use crate::msg::{ExecuteMsg, ConfigResponse, StatusResponse, UnstakingQueueResponse, 
-    InstantiateMsg, QueryMsg};
-use crate::state::{ConfigInfo, Supply, CONFIG, TOTAL_SUPPLY, CLAIMABLE, UNDER_UNSTAKING};
+    InstantiateMsg, QueryMsg, RatioSnapshotWithId, RatioHistoryResponse, AprResponse, Operation,
+    PendingOwnerResponse, PendingChangeWithId, PendingChangesResponse, Role, RoleMembersResponse,
+    HasRoleResponse, ArchwayRewardsResponse, RequestStatusResponse, TicketResponse, NodeWithId,
+    VerifyQueueResponse, SolvencyResponse, ProposalResponse, VoteOfResponse, HookInfo, HooksResponse};
+use crate::state::{ConfigInfo, Supply, PausedInfo, DepositLimits, PendingOwner, AdminChange, PendingChange, 
+    RatioSnapshot, NativeLedger, Node, Unbonding, HookConfig, HOOKS, MAX_HOOKS, HOOK_GAS_LIMIT, GovProposal, GovVote, VoteTally, GOV_PROPOSALS, GOV_VOTES, UNSTAKING_QUEUE, UnstakeRequest, UNSTAKE_REQUESTS, UNCLAIMED_REQUESTS, UNBONDINGS, UNBONDING_PERIOD, CONFIG, PAUSED, DEPOSIT_LIMITS, STAKED, PENDING_OWNER, PENDING_CHANGES, PENDING_CHANGES_NEXT_ID, 
+    DEFAULT_TIMELOCK_DELAY, DEFAULT_HARVEST_INTERVAL, MAX_HARVEST_BOUNTY_PERCENT, DEFAULT_QUEUE_BATCH_SIZE, MAX_QUEUE_BATCH_SIZE, MINIMUM_INITIAL_STAKE, LOCKED_LIQUID_TOKENS, LAST_HARVEST, PENDING_HARVESTER, ROLES, TOTAL_SUPPLY, LIQUID_SUPPLY, LEDGER, CLAIMABLE, UNDER_UNSTAKING, RATIO_HISTORY, 
+    RATIO_HISTORY_NEXT_ID, RATIO_HISTORY_CAPACITY};
 
 const FALLBACK_RATIO: Decimal = Decimal::one();
+const SECONDS_PER_YEAR: u64 = 365 * 24 * 60 * 60;
+
+// number of Archway rewards records withdrawn at once
+const ARCHWAY_REWARDS_RECORDS_LIMIT: u64 = 50;
+
+// settings for pagination
+const DEFAULT_LIMIT: u32 = 10;
+const MAX_LIMIT: u32 = 50;
+// number of queue nodes walked by VerifyQueue by default
+const DEFAULT_VERIFY_LIMIT: u32 = 1000;
+// number of unstaking requests marked claimed by one Claim
+const CLAIM_BATCH_SIZE: usize = 30;
+
+// reply id of hooks whose failure is ignored
+const HOOK_REPLY_ID: u64 = 1;
 
 // version info for migration info
 const CONTRACT_NAME: &str = "crates.io:liquid-staking";
@@ -25,108 +55,160 @@ const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
 
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn instantiate(
-    deps: DepsMut,
+    deps: DepsMut<ArchwayQuery>,
     _env: Env,
     info: MessageInfo,
     msg: InstantiateMsg,
-) -> Result<Response, ContractError> {
+) -> Result<Response<ArchwayMsg>, ContractError> {
     set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
 
-    let linked_list_init = LinkedList {
-        head_id: 0,
-        tail_id: 0,
-        length: 0
-    };
-    linked_list(deps.storage).save(&linked_list_init)?;
-
     let denom = deps.querier.query_bonded_denom()?;
     let config_init = ConfigInfo {
-        owner: info.sender,
+        owner: Some(info.sender),
         bond_denom: denom,
         liquid_token_addr: Addr::unchecked("none"), // msg.liquid_token_addr,
+        liquid_denom: None,
         validator: msg.validator,
+        timelock_delay: DEFAULT_TIMELOCK_DELAY,
+        harvest_bounty: Decimal::percent(1),
+        harvest_interval: DEFAULT_HARVEST_INTERVAL,
+        rewards_collector: None,
+        ticket_addr: None,
+        queue_batch_size: DEFAULT_QUEUE_BATCH_SIZE,
     };
     CONFIG.save(deps.storage, &config_init)?;
+    PAUSED.save(deps.storage, &PausedInfo::default())?;
+    DEPOSIT_LIMITS.save(deps.storage, &DepositLimits::default())?;
 
     // set supply to 0
     let supply_init = Supply::default();
     TOTAL_SUPPLY.save(deps.storage, &supply_init)?;
+    LEDGER.save(deps.storage, &NativeLedger::default())?;
 
     Ok(Response::default())
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn execute(
-    deps: DepsMut,
+    deps: DepsMut<ArchwayQuery>,
     env: Env,
     info: MessageInfo,
     msg: ExecuteMsg,
-) -> Result<Response, ContractError> {
+) -> Result<Response<ArchwayMsg>, ContractError> {
     match msg {
         ExecuteMsg::Stake {} => execute_stake(deps, env, info),
+        ExecuteMsg::Unstake {} => execute_unstake_denom(deps, env, info),
         ExecuteMsg::Claim {} => execute_claim(deps, info),
-        ExecuteMsg::SetLiquidToken { address } => execute_set_liquid_token(deps, info, address),
+        ExecuteMsg::CancelUnstake { request_id, amount } => execute_cancel_unstake(deps, env, info, request_id, amount),
+        ExecuteMsg::ClaimRequests { ids } => execute_claim_requests(deps, info, ids),
+        ExecuteMsg::ClaimTicket { id } => execute_claim_ticket(deps, info, id),
+        ExecuteMsg::Harvest {} => execute_harvest(deps, env, info),
+        ExecuteMsg::ProcessQueue { limit } => execute_process_queue(deps, env, info, limit),
+        ExecuteMsg::SetQueueBatchSize { size } => execute_set_queue_batch_size(deps, env, info, size),
+        ExecuteMsg::RepairQueue {} => execute_repair_queue(deps, info),
+        ExecuteMsg::AddHook { address, revert_on_error } => execute_add_hook(deps, env, info, address, revert_on_error),
+        ExecuteMsg::RemoveHook { address } => execute_remove_hook(deps, info, address),
+        ExecuteMsg::Vote { proposal_id, vote } => execute_vote(deps, env, info, proposal_id, vote),
+        ExecuteMsg::RegisterProposal { proposal_id, voting_end } => {
+            execute_register_proposal(deps, env, info, proposal_id, voting_end)
+        },
+        ExecuteMsg::CastVote { proposal_id } => execute_cast_vote(deps, env, info, proposal_id),
+        ExecuteMsg::DepositRewards {} => execute_deposit_rewards(deps, env, info),
+        ExecuteMsg::SetLiquidToken { address } => execute_set_liquid_token(deps, env, info, address),
+        ExecuteMsg::CreateLiquidDenom { subdenom } => execute_create_liquid_denom(deps, env, info, subdenom),
+        ExecuteMsg::SetTimelockDelay { delay } => execute_set_timelock_delay(deps, env, info, delay),
+        ExecuteMsg::SetHarvestConfig { bounty, interval } => {
+            execute_set_harvest_config(deps, env, info, bounty, interval)
+        },
+        ExecuteMsg::SetContractMetadata { owner_address, rewards_address } => {
+            execute_set_contract_metadata(deps, env, info, owner_address, rewards_address)
+        },
+        ExecuteMsg::SetRewardsCollector { address } => execute_set_rewards_collector(deps, env, info, address),
+        ExecuteMsg::SetTicketContract { address } => execute_set_ticket_contract(deps, env, info, address),
+        ExecuteMsg::SetDepositLimits { deposit_cap, address_cap, min_stake } => {
+            execute_set_deposit_limits(deps, env, info, deposit_cap, address_cap, min_stake)
+        },
+        ExecuteMsg::SweepUnaccounted { recipient } => execute_sweep_unaccounted(deps, env, info, recipient),
+        ExecuteMsg::ExecutePendingChange { id } => execute_pending_change(deps, env, info, id),
+        ExecuteMsg::CancelPendingChange { id } => execute_cancel_pending_change(deps, info, id),
+        ExecuteMsg::ProposeNewOwner { owner, expiry } => execute_propose_new_owner(deps, info, owner, expiry),
+        ExecuteMsg::AcceptOwnership {} => execute_accept_ownership(deps, env, info),
+        ExecuteMsg::CancelOwnershipProposal {} => execute_cancel_ownership_proposal(deps, info),
+        ExecuteMsg::RenounceOwnership {} => execute_renounce_ownership(deps, info),
+        ExecuteMsg::GrantRole { role, address } => execute_grant_role(deps, info, role, address),
+        ExecuteMsg::RevokeRole { role, address } => execute_revoke_role(deps, info, role, address),
+        ExecuteMsg::Pause { operation } => execute_pause(deps, info, operation),
+        ExecuteMsg::Unpause { operation } => execute_unpause(deps, info, operation),
         ExecuteMsg::Receive(msg) => execute_receive(deps, env, info, msg),
-        ExecuteMsg::_ProcessToken { balance_before } => _process_token(deps, env, info, balance_before),
-        ExecuteMsg::_PerformCheck {} => _perform_check(deps, env, info),
+        ExecuteMsg::_ProcessToken { rewards, harvester } => {
+            _process_token(deps, env, info, rewards, harvester)
+        },
+        ExecuteMsg::_PerformCheck { harvester } => _perform_check(deps, env, info, harvester),
         ExecuteMsg::_MintLiquidToken { receiver, native_amount } => _mint_liquid_token(deps, env, info, receiver, native_amount),
     }
 }
 
 // process unstaking queue then stake remain available native token
 pub fn _process_token(
-    deps: DepsMut,
+    deps: DepsMut<ArchwayQuery>,
     env: Env,
     info: MessageInfo,
-    balance_before: Uint128,
-) -> Result<Response, ContractError> {
+    rewards: Uint128,
+    harvester: Option<Addr>,
+) -> Result<Response<ArchwayMsg>, ContractError> {
     // only allow this contract to call itself
     if info.sender != env.contract.address {
         return Err(ContractError::Unauthorized {});
     }
 
+    // a bounty is only paid from rewards deposited within the harvest
+    PENDING_HARVESTER.remove(deps.storage);
+
     let zero_balance = Uint128::zero();
     // check how many available native token we have
     let config = CONFIG.load(deps.storage)?;
-    let mut balance = deps
+    let actual_balance = deps
         .querier
-        .query_balance(&env.contract.address, &config.bond_denom)?;
-    let claimed_reward = balance.amount.checked_sub(balance_before).map_err(StdError::overflow)?;
+        .query_balance(&env.contract.address, &config.bond_denom)?.amount;
+    let mut ledger = LEDGER.load(deps.storage)?;
+    let (mut claimed_reward, _) = sync_ledger(
+        deps.storage,
+        &mut ledger,
+        env.block.time,
+        actual_balance,
+        rewards,
+    )?;
+    let mut balance = coin(ledger.balance.u128(), &config.bond_denom);
+    let mut res = Response::new();
+    // pay the harvest bounty out of the rewards
+    if let Some(harvester) = harvester {
+        let bounty = claimed_reward * config.harvest_bounty;
+        if !bounty.is_zero() {
+            claimed_reward = claimed_reward.checked_sub(bounty).map_err(StdError::overflow)?;
+            balance.amount = balance.amount.checked_sub(bounty).map_err(StdError::overflow)?;
+            ledger.balance = ledger.balance.checked_sub(bounty).map_err(StdError::overflow)?;
+            res = res
+                .add_message(BankMsg::Send {
+                    to_address: harvester.to_string(),
+                    amount: coins(bounty.u128(), &config.bond_denom),
+                })
+                .add_attribute("bounty", bounty);
+        }
+    }
 
     let mut supply = TOTAL_SUPPLY.load(deps.storage)?;
     supply.native += claimed_reward;
+    if !claimed_reward.is_zero() {
+        let liquid_supply = get_liquid_supply(deps.storage, &deps.querier, &config)?;
+        record_ratio_snapshot(deps.storage, &env, &supply, liquid_supply)?;
+    }
     balance.amount = balance.amount.checked_sub(supply.claims).map_err(StdError::overflow)?;
     // process unstaking queue
-    let unstaking_requests: Vec<NodeWithId> = linked_list_get_list(deps.storage, 50)?;
-    for request in unstaking_requests {
-        if balance.amount == zero_balance {
-            break;
-        }
-        let payout: Uint128;
-        if request.info.value <= balance.amount {
-            payout = request.info.value;
-            linked_list_remove_head(deps.storage)?;
-        } else {
-            payout = balance.amount;
-            node_update_value(deps.storage, request.id, request.info.value.checked_sub(payout).map_err(StdError::overflow)?)?;
-        }
-        supply.unstakings = supply.unstakings.checked_sub(payout).map_err(StdError::overflow)?;
-        balance.amount = balance.amount.checked_sub(payout).map_err(StdError::overflow)?;
-        supply.claims += payout;
-        CLAIMABLE.update(
-            deps.storage,
-            &request.info.receiver,
-            |claimable: Option<Uint128>| -> StdResult<_> { Ok(claimable.unwrap_or_default() + payout) },
-        )?;
-        UNDER_UNSTAKING.update(
-            deps.storage,
-            &request.info.receiver,
-            |unstaking: Option<Uint128>| -> StdResult<_> { Ok(unstaking.unwrap_or_default().checked_sub(payout)?) },
-        )?;
-    }
-    let mut res = Response::new();
+    let (available, _) = pay_unstaking_queue(deps.storage, &mut supply, balance.amount, config.queue_batch_size)?;
+    balance.amount = available;
     // and bond remain available to the validator
     if supply.unstakings == zero_balance && balance.amount > zero_balance{
+        ledger.balance = ledger.balance.checked_sub(balance.amount).map_err(StdError::overflow)?;
         res = res.add_message(StakingMsg::Delegate {
             validator: config.validator,
             amount: balance.clone(),
@@ -136,6 +218,15 @@ pub fn _process_token(
         let bonded = get_bonded(&deps.querier, &env.contract.address)?;
         if bonded > supply.native {
             let unstake_amount = bonded.checked_sub(supply.native).map_err(StdError::overflow)?;
+            ledger.unbonding += unstake_amount;
+            UNBONDINGS.push_back(
+                deps.storage,
+                Unbonding {
+                    amount: unstake_amount,
+                    completion: env.block.time.plus_seconds(UNBONDING_PERIOD),
+                    height: env.block.height,
+                },
+            )?;
             res = res.add_message(StakingMsg::Undelegate {
                 validator: config.validator,
                 amount: coin(unstake_amount.u128(), &config.bond_denom),
@@ -143,6 +234,7 @@ pub fn _process_token(
         }
     }
     TOTAL_SUPPLY.save(deps.storage, &supply)?;
+    LEDGER.save(deps.storage, &ledger)?;
 
     res = res
         .add_attribute("action", "_processToken")
@@ -151,47 +243,328 @@ pub fn _process_token(
 }
 
 // claim staking reward, process withdraw queue, then stake available native token
-pub fn _perform_check(deps: DepsMut, env: Env, info: MessageInfo) -> Result<Response, ContractError> {
+pub fn _perform_check(
+    deps: DepsMut<ArchwayQuery>,
+    env: Env,
+    info: MessageInfo,
+    harvester: Option<Addr>,
+) -> Result<Response<ArchwayMsg>, ContractError> {
     // only allow this contract to call itself
     if info.sender != env.contract.address {
         return Err(ContractError::Unauthorized {});
     }
     
     let config = CONFIG.load(deps.storage)?;
-    let balance_before = deps
-        .querier
-        .query_balance(&env.contract.address, &config.bond_denom)?.amount;
     // claim reward then process available native token
     let mut res = Response::new();
-    // claim staking rewards
-    let bonded = get_bonded(&deps.querier, &env.contract.address)?;
-    if bonded > Uint128::zero() {
-        res = res.add_message(DistributionMsg::WithdrawDelegatorReward {
-            validator: config.validator,
+    let mut rewards = Uint128::zero();
+    // claim staking rewards, the amount is known up front unless they go to the collector
+    if let Some(delegation) = deps.querier.query_delegation(&env.contract.address, &config.validator)? {
+        if !delegation.amount.amount.is_zero() {
+            res = res.add_message(DistributionMsg::WithdrawDelegatorReward {
+                validator: config.validator.clone(),
+            });
+            match &config.rewards_collector {
+                // the collector deposits them back at once, paying the bounty out of them
+                Some(collector) => {
+                    if let Some(harvester) = &harvester {
+                        PENDING_HARVESTER.save(deps.storage, harvester)?;
+                    }
+                    res = res.add_message(WasmMsg::Execute {
+                        contract_addr: collector.to_string(),
+                        msg: to_json_binary(&CollectorExecuteMsg::Forward {})?,
+                        funds: vec![],
+                    });
+                },
+                None => {
+                    rewards += delegation
+                        .accumulated_rewards
+                        .iter()
+                        .filter(|reward| reward.denom == config.bond_denom)
+                        .fold(Uint128::zero(), |acc, reward| acc + reward.amount);
+                },
+            }
+        }
+    }
+    // claim Archway contract rewards, they are compounded like staking rewards. A failing
+    // rewards query must not block staking, it counts as no rewards to withdraw
+    let (records, archway_rewards) = get_archway_rewards(&deps.querier, &env.contract.address, &config.bond_denom)
+        .unwrap_or_default();
+    if records > 0 {
+        rewards += archway_rewards;
+        res = res.add_message(ArchwayMsg::WithdrawRewards {
+            records_limit: Some(ARCHWAY_REWARDS_RECORDS_LIMIT),
+            record_ids: vec![],
         })
     }
     // process unstaking queue and available native token
-    let msg = to_binary(&ExecuteMsg::_ProcessToken { balance_before })?;
+    let msg = to_json_binary(&ExecuteMsg::_ProcessToken { rewards, harvester })?;
     res = res.add_message(WasmMsg::Execute {
             contract_addr: env.contract.address.to_string(),
             msg,
             funds: vec![],
         })
         .add_attribute("action", "_performCheck")
-        .add_attribute("balance", balance_before);
+        .add_attribute("rewards", rewards);
     Ok(res)
 }
 
+// pay_unstaking_queue pays the unstaking queue from its head with the available native tokens,
+// walking at most `limit` requests one node at a time. The queue head is the stored cursor: filled
+// requests leave the queue and a partly filled one keeps what is left, so the next call resumes there
+fn pay_unstaking_queue(
+    storage: &mut dyn Storage,
+    supply: &mut Supply,
+    mut available: Uint128,
+    limit: u32,
+) -> StdResult<(Uint128, u32)> {
+    let mut processed = 0;
+    while processed < limit && !available.is_zero() {
+        let (id, request) = match UNSTAKING_QUEUE.front(storage)? {
+            Some(front) => front,
+            None => break,
+        };
+        processed += 1;
+        let payout: Uint128;
+        if request.value <= available {
+            payout = request.value;
+            UNSTAKING_QUEUE.pop_front(storage)?;
+        } else {
+            payout = available;
+            let value = request.value.checked_sub(payout).map_err(StdError::overflow)?;
+            UNSTAKING_QUEUE.save(storage, id, Node { value, ..request.clone() })?;
+        }
+        supply.unstakings = supply.unstakings.checked_sub(payout).map_err(StdError::overflow)?;
+        available = available.checked_sub(payout).map_err(StdError::overflow)?;
+        supply.claims += payout;
+        // ticketed requests are paid to the ticket holder, the others to the receiver
+        let mut ticketed = false;
+        if let Some(mut unstake_request) = UNSTAKE_REQUESTS.may_load(storage, id)? {
+            unstake_request.filled += payout;
+            UNSTAKE_REQUESTS.save(storage, id, &unstake_request)?;
+            ticketed = unstake_request.ticket.is_some();
+            if !ticketed {
+                UNCLAIMED_REQUESTS.save(storage, (&request.receiver, id), &Empty {})?;
+            }
+        }
+        if !ticketed {
+            CLAIMABLE.update(
+                storage,
+                &request.receiver,
+                |claimable: Option<Uint128>| -> StdResult<_> { Ok(claimable.unwrap_or_default() + payout) },
+            )?;
+        }
+        UNDER_UNSTAKING.update(
+            storage,
+            &request.receiver,
+            |unstaking: Option<Uint128>| -> StdResult<_> { Ok(unstaking.unwrap_or_default().checked_sub(payout)?) },
+        )?;
+    }
+    Ok((available, processed))
+}
+
+// reconcile_ledger books the native tokens that arrived since the last update: the expected
+// rewards first, then up to `matured` of the unbondings whose completion time has passed.
+// It returns the rewards and unbondings booked and what is left unaccounted
+// (tokens sent directly to the contract, or unbondings back before their time)
+fn reconcile_ledger(ledger: &mut NativeLedger, actual_balance: Uint128, rewards: Uint128, matured: Uint128) -> (Uint128, Uint128, Uint128) {
+    let inflow = actual_balance.saturating_sub(ledger.balance);
+    let rewards = rewards.min(inflow);
+    let matured = (inflow - rewards).min(matured).min(ledger.unbonding);
+    ledger.unbonding -= matured;
+    ledger.balance += rewards + matured;
+    (rewards, matured, inflow - rewards - matured)
+}
+
+// get_matured_unbonding returns the pending unbondings whose completion time has passed
+fn get_matured_unbonding(storage: &dyn Storage, now: Timestamp) -> StdResult<Uint128> {
+    let mut matured = Uint128::zero();
+    for item in UNBONDINGS.iter(storage)? {
+        let (_, unbonding) = item?;
+        if unbonding.completion > now {
+            break;
+        }
+        matured += unbonding.amount;
+    }
+    Ok(matured)
+}
+
+// sync_ledger reconciles the ledger with the actual balance and drops the unbondings it booked,
+// oldest first. It returns the rewards booked and what is left unaccounted
+fn sync_ledger(
+    storage: &mut dyn Storage,
+    ledger: &mut NativeLedger,
+    now: Timestamp,
+    actual_balance: Uint128,
+    rewards: Uint128,
+) -> StdResult<(Uint128, Uint128)> {
+    let matured = get_matured_unbonding(storage, now)?;
+    let (rewards, mut booked, unaccounted) =
+        reconcile_ledger(ledger, actual_balance, rewards, matured);
+    while !booked.is_zero() {
+        let (id, unbonding) = match UNBONDINGS.front(storage)? {
+            Some(front) => front,
+            None => break,
+        };
+        if unbonding.amount <= booked {
+            booked -= unbonding.amount;
+            UNBONDINGS.pop_front(storage)?;
+        } else {
+            UNBONDINGS.save(
+                storage,
+                id,
+                Unbonding {
+                    amount: unbonding.amount - booked,
+                    ..unbonding
+                },
+            )?;
+            booked = Uint128::zero();
+        }
+    }
+    Ok((rewards, unaccounted))
+}
+
+fn get_ratio(native: Uint128, issued: Uint128) -> Decimal {
+    if issued.is_zero() {
+        FALLBACK_RATIO
+    } else {
+        Decimal::from_ratio(native, issued)
+    }
+}
+
+// record_ratio_snapshot appends the current ratio to the history, dropping the oldest
+// snapshot once the history is full
+fn record_ratio_snapshot(
+    storage: &mut dyn Storage,
+    env: &Env,
+    supply: &Supply,
+    issued: Uint128,
+) -> StdResult<()> {
+    let id = RATIO_HISTORY_NEXT_ID.may_load(storage)?.unwrap_or_default();
+    let snapshot = RatioSnapshot {
+        height: env.block.height,
+        time: env.block.time,
+        ratio: get_ratio(supply.native, issued),
+        native: supply.native,
+        issued,
+    };
+    RATIO_HISTORY.save(storage, id, &snapshot)?;
+    if id >= RATIO_HISTORY_CAPACITY {
+        RATIO_HISTORY.remove(storage, id - RATIO_HISTORY_CAPACITY);
+    }
+    RATIO_HISTORY_NEXT_ID.save(storage, &(id + 1))?;
+    Ok(())
+}
+
+// book_withdrawn_rewards books the pending delegator rewards that a delegation change about to be
+// sent withdraws to this contract, so they are compounded instead of left unaccounted. While a
+// rewards collector is set they are withdrawn to the collector, which deposits them back
+fn book_withdrawn_rewards(
+    storage: &mut dyn Storage,
+    querier: &QuerierWrapper<ArchwayQuery>,
+    env: &Env,
+    config: &ConfigInfo,
+    ledger: &mut NativeLedger,
+    supply: &mut Supply,
+) -> StdResult<Uint128> {
+    if config.rewards_collector.is_some() {
+        return Ok(Uint128::zero());
+    }
+    let rewards = match querier.query_delegation(&env.contract.address, &config.validator)? {
+        Some(delegation) => delegation
+            .accumulated_rewards
+            .iter()
+            .filter(|reward| reward.denom == config.bond_denom)
+            .fold(Uint128::zero(), |acc, reward| acc + reward.amount),
+        None => Uint128::zero(),
+    };
+    if !rewards.is_zero() {
+        ledger.balance += rewards;
+        supply.native += rewards;
+        let liquid_supply = get_liquid_supply(storage, querier, config)?;
+        record_ratio_snapshot(storage, env, supply, liquid_supply)?;
+    }
+    Ok(rewards)
+}
+
+// has_role tells whether the address holds the role, the owner holds every role
+fn has_role(storage: &dyn Storage, address: &Addr, role: Role) -> StdResult<bool> {
+    let config = CONFIG.load(storage)?;
+    if config.owner.as_ref() == Some(address) {
+        return Ok(true);
+    }
+    match role {
+        Role::Owner => Ok(false),
+        _ => Ok(ROLES.may_load(storage, (role.as_str(), address))?.is_some()),
+    }
+}
+
+// ensure_has_role fails unless the address holds the role
+fn ensure_has_role(storage: &dyn Storage, address: &Addr, role: Role) -> Result<(), ContractError> {
+    if !has_role(storage, address, role)? {
+        return Err(ContractError::Unauthorized {});
+    }
+    Ok(())
+}
+
+// ensure_not_paused fails if the given operation is halted
+fn ensure_not_paused(storage: &dyn Storage, operation: Operation) -> Result<(), ContractError> {
+    let paused = PAUSED.load(storage)?;
+    let is_paused = match operation {
+        Operation::Stake => paused.stake,
+        Operation::Unstake => paused.unstake,
+        Operation::Claim => paused.claim,
+    };
+    if is_paused {
+        return Err(ContractError::Paused { operation: operation.to_string() });
+    }
+    Ok(())
+}
+
+// check_deposit_limits fails if staking amount would break the minimum stake or a deposit cap
+fn check_deposit_limits(storage: &dyn Storage, staker: &Addr, amount: Uint128) -> Result<(), ContractError> {
+    let limits = DEPOSIT_LIMITS.load(storage)?;
+    if amount < limits.min_stake {
+        return Err(ContractError::StakeBelowMinimum { min: limits.min_stake });
+    }
+    if let Some(cap) = limits.deposit_cap {
+        let supply = TOTAL_SUPPLY.load(storage)?;
+        if supply.native + amount > cap {
+            return Err(ContractError::DepositCapExceeded { cap, remaining: cap.saturating_sub(supply.native) });
+        }
+    }
+    if let Some(cap) = limits.address_cap {
+        let staked = STAKED.may_load(storage, staker)?.unwrap_or_default();
+        if staked + amount > cap {
+            return Err(ContractError::AddressCapExceeded { cap, remaining: cap.saturating_sub(staked) });
+        }
+    }
+    Ok(())
+}
+
+// set_paused flags one operation, or all of them when none is given
+fn set_paused(paused: &mut PausedInfo, operation: Option<Operation>, value: bool) {
+    match operation {
+        Some(Operation::Stake) => paused.stake = value,
+        Some(Operation::Unstake) => paused.unstake = value,
+        Some(Operation::Claim) => paused.claim = value,
+        None => {
+            paused.stake = value;
+            paused.unstake = value;
+            paused.claim = value;
+        },
+    }
+}
+
 // get_bonded returns the total amount of delegations from contract
 // it ensures they are all the same denom
-fn get_bonded(querier: &QuerierWrapper, contract: &Addr) -> Result<Uint128, ContractError> {
+fn get_bonded(querier: &QuerierWrapper<ArchwayQuery>, contract: &Addr) -> Result<Uint128, ContractError> {
     let bonds = querier.query_all_delegations(contract)?;
     if bonds.is_empty() {
         return Ok(Uint128::zero());
     }
     let denom = bonds[0].amount.denom.as_str();
-    bonds.iter().fold(Ok(Uint128::zero()), |racc, d| {
-        let acc = racc?;
+    bonds.iter().try_fold(Uint128::zero(), |acc, d| {
         if d.amount.denom.as_str() != denom {
             Err(ContractError::DifferentBondDenom {
                 denom1: denom.into(),
@@ -203,24 +576,198 @@ fn get_bonded(querier: &QuerierWrapper, contract: &Addr) -> Result<Uint128, Cont
     })
 }
 
-fn get_token_supply(querier: &QuerierWrapper, token_addr: Addr,) -> StdResult<Uint128> {
+// get_archway_rewards returns the number of Archway rewards records waiting for the contract
+// and the native tokens they hold
+fn get_archway_rewards(
+    querier: &QuerierWrapper<ArchwayQuery>,
+    contract: &Addr,
+    denom: &str,
+) -> StdResult<(u64, Uint128)> {
+    let response: RewardsRecordsResponse = querier.query(&QueryRequest::Custom(ArchwayQuery::RewardsRecords {
+        rewards_address: contract.to_string(),
+        pagination: Some(PageRequest {
+            limit: Some(ARCHWAY_REWARDS_RECORDS_LIMIT),
+            ..PageRequest::default()
+        }),
+    }))?;
+    let rewards = response
+        .records
+        .iter()
+        .flat_map(|record| record.rewards.iter())
+        .filter(|reward| reward.denom == denom)
+        .fold(Uint128::zero(), |acc, reward| acc + reward.amount);
+    Ok((response.records.len() as u64, rewards))
+}
+
+fn get_token_supply(querier: &QuerierWrapper<ArchwayQuery>, token_addr: Addr,) -> StdResult<Uint128> {
     let cw20_query_response: TokenInfoResponse =
        querier.query(&QueryRequest::Wasm(WasmQuery::Smart {
             contract_addr: token_addr.to_string(),
-            msg: to_binary(&Cw20QueryMsg::TokenInfo {})?,
+            msg: to_json_binary(&Cw20QueryMsg::TokenInfo {})?,
     }))?;
 
     Ok(cw20_query_response.total_supply)
  }
 
+// prepare_hooks builds the message to every registered hook contract, hooks that don't revert
+// the action on error, or every hook when `ignore_errors` is set, are sent as gas limited
+// submessages replying on error
+fn prepare_hooks(storage: &dyn Storage, msg: HookExecuteMsg, ignore_errors: bool) -> StdResult<Vec<SubMsg<ArchwayMsg>>> {
+    HOOKS
+        .range(storage, None, None, Order::Ascending)
+        .map(|item| {
+            let (address, hook) = item?;
+            let msg = msg.clone().into_cosmos_msg(address)?;
+            Ok(if hook.revert_on_error && !ignore_errors {
+                SubMsg::new(msg)
+            } else {
+                SubMsg::reply_on_error(msg, HOOK_REPLY_ID).with_gas_limit(HOOK_GAS_LIMIT)
+            })
+        })
+        .collect()
+}
+
+// get_liquid_supply returns the supply of the liquid token, the cw20 token or the TokenFactory denom
+fn get_liquid_supply(
+    storage: &dyn Storage,
+    querier: &QuerierWrapper<ArchwayQuery>,
+    config: &ConfigInfo,
+) -> StdResult<Uint128> {
+    match config.liquid_denom {
+        Some(_) => Ok(LIQUID_SUPPLY.may_load(storage)?.unwrap_or_default()),
+        None => get_token_supply(querier, config.liquid_token_addr.clone()),
+    }
+}
+
+// mint_liquid_token returns the message minting liquid tokens to the recipient,
+// the supply of the TokenFactory denom is updated at once
+fn mint_liquid_token(
+    storage: &mut dyn Storage,
+    config: &ConfigInfo,
+    contract: &Addr,
+    recipient: &Addr,
+    amount: Uint128,
+) -> StdResult<CosmosMsg<ArchwayMsg>> {
+    match &config.liquid_denom {
+        Some(denom) => {
+            LIQUID_SUPPLY.update(storage, |supply| -> StdResult<_> { Ok(supply + amount) })?;
+            Ok(tokenfactory::mint(contract.as_str(), &coin(amount.u128(), denom), recipient.as_str()))
+        },
+        // built as a plain wasm message so it fits the Archway custom response
+        None => Ok(WasmMsg::Execute {
+            contract_addr: config.liquid_token_addr.to_string(),
+            msg: to_json_binary(&Cw20ExecuteMsg::Mint { recipient: recipient.to_string(), amount })?,
+            funds: vec![],
+        }.into()),
+    }
+}
+
+// burn_liquid_token returns the message burning liquid tokens held by this contract,
+// the supply of the TokenFactory denom is updated at once
+fn burn_liquid_token(
+    storage: &mut dyn Storage,
+    config: &ConfigInfo,
+    contract: &Addr,
+    amount: Uint128,
+) -> StdResult<CosmosMsg<ArchwayMsg>> {
+    match &config.liquid_denom {
+        Some(denom) => {
+            LIQUID_SUPPLY.update(storage, |supply| -> StdResult<_> {
+                supply.checked_sub(amount).map_err(StdError::overflow)
+            })?;
+            Ok(tokenfactory::burn(contract.as_str(), &coin(amount.u128(), denom), contract.as_str()))
+        },
+        None => Ok(WasmMsg::Execute {
+            contract_addr: config.liquid_token_addr.to_string(),
+            msg: to_json_binary(&Cw20ExecuteMsg::Burn { amount })?,
+            funds: vec![],
+        }.into()),
+    }
+}
+
+// get_snapshot_balance returns the liquid token balance of an address at the start of a block
+fn get_snapshot_balance(
+    querier: &QuerierWrapper<ArchwayQuery>,
+    token_addr: &Addr,
+    address: &Addr,
+    height: u64,
+) -> StdResult<Uint128> {
+    let response: BalanceResponse = querier.query(&QueryRequest::Wasm(WasmQuery::Smart {
+        contract_addr: token_addr.to_string(),
+        msg: to_json_binary(&SnapshotQueryMsg::BalanceAt { address: address.to_string(), height })?,
+    }))?;
+    Ok(response.balance)
+}
+
+// tally_of returns the voting power behind a vote option
+fn tally_of<'a>(tally: &'a mut VoteTally, option: &VoteOption) -> &'a mut Uint128 {
+    match option {
+        VoteOption::Yes => &mut tally.yes,
+        VoteOption::Abstain => &mut tally.abstain,
+        VoteOption::No => &mut tally.no,
+        VoteOption::NoWithVeto => &mut tally.no_with_veto,
+    }
+}
+
+// get_vote_weights splits the voting power of the contract between the options of a tally,
+// rounding down and giving the remainder to the heaviest option so the weights add up to 1
+fn get_vote_weights(tally: &VoteTally) -> Vec<WeightedVoteOption> {
+    let votes = [
+        (VoteOption::Yes, tally.yes),
+        (VoteOption::Abstain, tally.abstain),
+        (VoteOption::No, tally.no),
+        (VoteOption::NoWithVeto, tally.no_with_veto),
+    ];
+    let total = votes.iter().fold(Uint128::zero(), |acc, (_, power)| acc + *power);
+    let mut options: Vec<WeightedVoteOption> = votes
+        .iter()
+        .filter(|(_, power)| !power.is_zero())
+        .map(|(option, power)| WeightedVoteOption { option: option.clone(), weight: Decimal::from_ratio(*power, total) })
+        .collect();
+    let assigned = options.iter().fold(Decimal::zero(), |acc, option| acc + option.weight);
+    if let Some(heaviest) = options.iter_mut().max_by_key(|option| option.weight) {
+        heaviest.weight += Decimal::one() - assigned;
+    }
+    options
+}
+
+// cast_tally votes with the current tally of a proposal, the gov module replaces the vote sent before
+fn cast_tally(proposal: &mut GovProposal, proposal_id: u64, now: Timestamp) -> Option<GovMsg> {
+    let options = get_vote_weights(&proposal.tally);
+    if options.is_empty() {
+        return None;
+    }
+    proposal.cast_at = Some(now);
+    Some(GovMsg::VoteWeighted { proposal_id, options })
+}
+
+// get_request_holder returns who an unstaking request is paid to,
+// the holder of its claim ticket if any or else the receiver
+fn get_request_holder(
+    querier: &QuerierWrapper<ArchwayQuery>,
+    unstake_request: &UnstakeRequest,
+    id: u64,
+) -> StdResult<String> {
+    let ticket_addr = match &unstake_request.ticket {
+        Some(ticket_addr) => ticket_addr,
+        None => return Ok(unstake_request.receiver.to_string()),
+    };
+    let owner_response: OwnerOfResponse =
+        querier.query(&QueryRequest::Wasm(WasmQuery::Smart {
+            contract_addr: ticket_addr.to_string(),
+            msg: to_json_binary(&TicketQueryMsg::OwnerOf { token_id: id.to_string(), include_expired: None })?,
+        }))?;
+    Ok(owner_response.owner)
+}
+
 // mint new liquid token to native token sender
 pub fn _mint_liquid_token(
-    deps: DepsMut,
+    deps: DepsMut<ArchwayQuery>,
     env: Env,
     info: MessageInfo,
     receiver: Addr,
     native_amount: Uint128,
-) -> Result<Response, ContractError> {
+) -> Result<Response<ArchwayMsg>, ContractError> {
     // only allow this contract to call itself
     if info.sender != env.contract.address {
         return Err(ContractError::Unauthorized {});
@@ -229,35 +776,50 @@ pub fn _mint_liquid_token(
     let config = CONFIG.load(deps.storage)?;
     // calculate to_mint and update total supply
     let mut supply = TOTAL_SUPPLY.load(deps.storage)?;
-    let liquid_supply = get_token_supply(&deps.querier, config.liquid_token_addr.clone())?;
+    let liquid_supply = get_liquid_supply(deps.storage, &deps.querier, &config)?;
+    let mut res = Response::new();
     let to_mint = if liquid_supply.is_zero() {
-        FALLBACK_RATIO * native_amount
+        // first stake: lock part of the liquid token in this contract for good
+        if native_amount < Uint128::new(MINIMUM_INITIAL_STAKE) {
+            return Err(ContractError::InsufficientInitialDeposit { min: Uint128::new(MINIMUM_INITIAL_STAKE) });
+        }
+        let locked = Uint128::new(LOCKED_LIQUID_TOKENS);
+        res = res
+            .add_message(mint_liquid_token(deps.storage, &config, &env.contract.address, &env.contract.address, locked)?)
+            .add_attribute("locked", locked);
+        (FALLBACK_RATIO * native_amount).checked_sub(locked).map_err(StdError::overflow)?
+    } else if supply.native.is_zero() {
+        // shares without backing can't be priced, minting 1:1 would hand the deposit to them
+        return Err(ContractError::DrainedPool { issued: liquid_supply });
     } else {
         native_amount.multiply_ratio(liquid_supply, supply.native)
     };
+    if to_mint.is_zero() {
+        return Err(ContractError::ZeroShares {});
+    }
     supply.native += native_amount;
     TOTAL_SUPPLY.save(deps.storage, &supply)?;
 
-    let mut res = Response::new()
+    res = res
         .add_attribute("action", "_mintLiquidToken")
         .add_attribute("from", receiver.clone())
         .add_attribute("staked", native_amount)
         .add_attribute("minted", to_mint);
 
-    // transfer cw20 liquid token to staker
-    // Cw20Contract is a function helper that provides several queries and message builder.
-    let cw20 = Cw20Contract(config.liquid_token_addr);
-    // Build a cw20 transfer send msg, that send collected funds to target address
-    let msg = cw20.call(Cw20ExecuteMsg::Mint {
-        recipient: receiver.into_string(),
-        amount: to_mint,
-    })?;
-    res = res.add_message(msg);
+    // mint liquid token to staker
+    res = res.add_message(mint_liquid_token(deps.storage, &config, &env.contract.address, &receiver, to_mint)?);
+    let hooks = prepare_hooks(deps.storage, HookExecuteMsg::StakeHook {
+        address: receiver.to_string(),
+        amount: native_amount,
+        minted: to_mint,
+    }, false)?;
+    res = res.add_submessages(hooks);
     
     Ok(res)
 }
 
-pub fn execute_stake(deps: DepsMut, env: Env, info: MessageInfo) -> Result<Response, ContractError> {
+pub fn execute_stake(deps: DepsMut<ArchwayQuery>, env: Env, info: MessageInfo) -> Result<Response<ArchwayMsg>, ContractError> {
+    ensure_not_paused(deps.storage, Operation::Stake)?;
     // ensure we have the proper denom
     let config = CONFIG.load(deps.storage)?;
     // payment finds the proper coin (or throws an error)
@@ -268,10 +830,21 @@ pub fn execute_stake(deps: DepsMut, env: Env, info: MessageInfo) -> Result<Respo
         .ok_or_else(|| ContractError::EmptyBalance {
             denom: config.bond_denom.clone(),
         })?;
+    check_deposit_limits(deps.storage, &info.sender, payment.amount)?;
+    STAKED.update(
+        deps.storage,
+        &info.sender,
+        |staked: Option<Uint128>| -> StdResult<_> { Ok(staked.unwrap_or_default() + payment.amount) },
+    )?;
+
+    LEDGER.update(deps.storage, |mut ledger| -> StdResult<_> {
+        ledger.balance += payment.amount;
+        Ok(ledger)
+    })?;
 
     let contract_addr = env.contract.address;
-    let msg1 = to_binary(&ExecuteMsg::_PerformCheck {})?;
-    let msg2 = to_binary(&ExecuteMsg::_MintLiquidToken { receiver: info.sender, native_amount: payment.amount })?;
+    let msg1 = to_json_binary(&ExecuteMsg::_PerformCheck { harvester: None })?;
+    let msg2 = to_json_binary(&ExecuteMsg::_MintLiquidToken { receiver: info.sender, native_amount: payment.amount })?;
     
     let res = Response::new()
         .add_message(WasmMsg::Execute {
@@ -288,55 +861,237 @@ pub fn execute_stake(deps: DepsMut, env: Env, info: MessageInfo) -> Result<Respo
 }
 
 pub fn execute_unstake(
-    deps: DepsMut,
+    deps: DepsMut<ArchwayQuery>,
     env: Env,
     sender: Addr,
     amount: Uint128,
-) -> Result<Response, ContractError> {
+) -> Result<Response<ArchwayMsg>, ContractError> {
+    ensure_not_paused(deps.storage, Operation::Unstake)?;
     let contract_addr = env.contract.address;
     let config = CONFIG.load(deps.storage)?;
 
-    // burn liquid token
-    let cw20 = Cw20Contract(config.liquid_token_addr.clone());
-    // Build a cw20 transfer send msg, that send collected funds to target address
-    let msg1 = cw20.call(Cw20ExecuteMsg::Burn {
-        amount,
-    })?;
-
     // put unstaker to unstaking queue, update info
     let mut supply = TOTAL_SUPPLY.load(deps.storage)?;
-    let liquid_supply = get_token_supply(&deps.querier, config.liquid_token_addr)?;
+    let liquid_supply = get_liquid_supply(deps.storage, &deps.querier, &config)?;
+    // burn liquid token, once the supply before the burn is known
+    let msg1 = burn_liquid_token(deps.storage, &config, &contract_addr, amount)?;
     let amount_to_unstake = amount.multiply_ratio(supply.native, liquid_supply);
     supply.native = supply.native.checked_sub(amount_to_unstake).map_err(StdError::overflow)?;
     supply.unstakings += amount_to_unstake;
     TOTAL_SUPPLY.save(deps.storage, &supply)?;
-    linked_list_append(deps.storage, sender.clone(), amount_to_unstake, env.block.height)?;
+    let node = Node {
+        receiver: sender.clone(),
+        value: amount_to_unstake,
+        height: env.block.height,
+    };
+    let request_id = UNSTAKING_QUEUE.push_back(deps.storage, node)?;
     UNDER_UNSTAKING.update(
         deps.storage,
         &sender,
         |claimable: Option<Uint128>| -> StdResult<_> { Ok(claimable.unwrap_or_default() + amount_to_unstake) },
     )?;
-    let msg2 = to_binary(&ExecuteMsg::_PerformCheck {})?;
+    // liquid token can be bought elsewhere, so the staked amount only goes down to zero
+    STAKED.update(
+        deps.storage,
+        &sender,
+        |staked: Option<Uint128>| -> StdResult<_> { Ok(staked.unwrap_or_default().saturating_sub(amount_to_unstake)) },
+    )?;
+    let msg2 = to_json_binary(&ExecuteMsg::_PerformCheck { harvester: None })?;
     
-    let res = Response::new()
+    let mut res = Response::new()
         .add_message(msg1)
         .add_message(WasmMsg::Execute {
             contract_addr: contract_addr.to_string(),
             msg: msg2,
             funds: vec![],
-        })
+        });
+    let unstake_request = UnstakeRequest {
+        receiver: sender.clone(),
+        ticket: config.ticket_addr.clone(),
+        amount: amount_to_unstake,
+        height: env.block.height,
+        filled: Uint128::zero(),
+        cancelled: Uint128::zero(),
+        claimed: Uint128::zero(),
+    };
+    UNSTAKE_REQUESTS.save(deps.storage, request_id, &unstake_request)?;
+    // mint a transferable claim ticket for the request
+    if let Some(ticket_addr) = config.ticket_addr {
+        res = res.add_message(WasmMsg::Execute {
+            contract_addr: ticket_addr.to_string(),
+            msg: to_json_binary(&TicketExecuteMsg::Mint(TicketMintMsg {
+                token_id: request_id.to_string(),
+                owner: sender.to_string(),
+                token_uri: None,
+                extension: TicketMetadata {
+                    amount: amount_to_unstake,
+                    height: env.block.height,
+                    eta: env.block.time.plus_seconds(UNBONDING_PERIOD),
+                },
+            }))?,
+            funds: vec![],
+        });
+    }
+    let hooks = prepare_hooks(deps.storage, HookExecuteMsg::UnstakeHook {
+        address: sender.to_string(),
+        amount,
+        native_amount: amount_to_unstake,
+        request_id,
+    }, false)?;
+    res = res
+        .add_submessages(hooks)
         .add_attribute("action", "unstake")
         .add_attribute("from", sender)
-        .add_attribute("amount", amount);
+        .add_attribute("amount", amount)
+        .add_attribute("request_id", request_id.to_string());
+    Ok(res)
+}
+
+pub fn execute_cancel_unstake(
+    deps: DepsMut<ArchwayQuery>,
+    env: Env,
+    info: MessageInfo,
+    request_id: u64,
+    amount: Option<Uint128>,
+) -> Result<Response<ArchwayMsg>, ContractError> {
+    // cancelling stakes the amount again, so both operations must be open
+    ensure_not_paused(deps.storage, Operation::Unstake)?;
+    ensure_not_paused(deps.storage, Operation::Stake)?;
+    let config = CONFIG.load(deps.storage)?;
+
+    // only the part of the request still in the queue can be cancelled, by the ticket holder if any
+    let request = UNSTAKING_QUEUE
+        .may_load(deps.storage, request_id)?
+        .ok_or(ContractError::UnstakeRequestNotFound { id: request_id })?;
+    let unstake_request = UNSTAKE_REQUESTS.may_load(deps.storage, request_id)?;
+    let holder = match &unstake_request {
+        Some(unstake_request) => get_request_holder(&deps.querier, unstake_request, request_id)?,
+        None => request.receiver.to_string(),
+    };
+    if holder != info.sender {
+        return Err(ContractError::UnstakeRequestNotFound { id: request_id });
+    }
+    let amount = amount.unwrap_or(request.value);
+    if amount.is_zero() || amount > request.value {
+        return Err(ContractError::InvalidCancelAmount { available: request.value });
+    }
+    check_deposit_limits(deps.storage, &info.sender, amount)?;
+    if amount == request.value {
+        UNSTAKING_QUEUE.remove(deps.storage, request_id)?;
+    } else {
+        UNSTAKING_QUEUE.save(deps.storage, request_id, Node { value: request.value - amount, ..request.clone() })?;
+    }
+    if let Some(mut unstake_request) = unstake_request {
+        unstake_request.cancelled += amount;
+        UNSTAKE_REQUESTS.save(deps.storage, request_id, &unstake_request)?;
+    }
+
+    let mut supply = TOTAL_SUPPLY.load(deps.storage)?;
+    supply.unstakings = supply.unstakings.checked_sub(amount).map_err(StdError::overflow)?;
+
+    // undelegations the queue no longer needs are cancelled newest first, which delegates the
+    // tokens again. Those already completed are delegated by _PerformCheck once booked
+    let mut ledger = LEDGER.load(deps.storage)?;
+    let mut surplus = amount.min(ledger.unbonding.saturating_sub(supply.unstakings));
+    let mut cancels: Vec<CosmosMsg<ArchwayMsg>> = vec![];
+    while !surplus.is_zero() {
+        let (id, unbonding) = match UNBONDINGS.back(deps.storage)? {
+            Some(back) if back.1.completion > env.block.time => back,
+            _ => break,
+        };
+        let cancelled = unbonding.amount.min(surplus);
+        if cancelled == unbonding.amount {
+            UNBONDINGS.pop_back(deps.storage)?;
+        } else {
+            UNBONDINGS.save(deps.storage, id, Unbonding { amount: unbonding.amount - cancelled, ..unbonding.clone() })?;
+        }
+        surplus -= cancelled;
+        ledger.unbonding = ledger.unbonding.checked_sub(cancelled).map_err(StdError::overflow)?;
+        cancels.push(cancel_unbonding_delegation(
+            env.contract.address.as_str(),
+            &config.validator,
+            &coin(cancelled.u128(), &config.bond_denom),
+            unbonding.height,
+        ));
+    }
+    // cancelling withdraws the pending rewards before _PerformCheck can, they are booked first
+    // so the amount is minted back at the ratio including them
+    if !cancels.is_empty() {
+        book_withdrawn_rewards(deps.storage, &deps.querier, &env, &config, &mut ledger, &mut supply)?;
+    }
+    LEDGER.save(deps.storage, &ledger)?;
+
+    // mint back at the current ratio, then the amount is staked again
+    let liquid_supply = get_liquid_supply(deps.storage, &deps.querier, &config)?;
+    let to_mint = if !supply.native.is_zero() {
+        amount.multiply_ratio(liquid_supply, supply.native)
+    } else if liquid_supply.is_zero() {
+        FALLBACK_RATIO * amount
+    } else {
+        return Err(ContractError::DrainedPool { issued: liquid_supply });
+    };
+    if to_mint.is_zero() {
+        return Err(ContractError::ZeroShares {});
+    }
+    supply.native += amount;
+    TOTAL_SUPPLY.save(deps.storage, &supply)?;
+    UNDER_UNSTAKING.update(
+        deps.storage,
+        &request.receiver,
+        |unstaking: Option<Uint128>| -> StdResult<_> { Ok(unstaking.unwrap_or_default().checked_sub(amount)?) },
+    )?;
+    STAKED.update(
+        deps.storage,
+        &info.sender,
+        |staked: Option<Uint128>| -> StdResult<_> { Ok(staked.unwrap_or_default() + amount) },
+    )?;
+
+    // taking back an unstaking request is a stake of its native amount
+    let hooks = prepare_hooks(deps.storage, HookExecuteMsg::StakeHook {
+        address: info.sender.to_string(),
+        amount,
+        minted: to_mint,
+    }, false)?;
+    let msg = to_json_binary(&ExecuteMsg::_PerformCheck { harvester: None })?;
+    let res = Response::new()
+        .add_message(mint_liquid_token(deps.storage, &config, &env.contract.address, &info.sender, to_mint)?)
+        .add_messages(cancels)
+        .add_message(WasmMsg::Execute {
+            contract_addr: env.contract.address.to_string(),
+            msg,
+            funds: vec![],
+        })
+        .add_submessages(hooks)
+        .add_attribute("action", "cancelUnstake")
+        .add_attribute("from", info.sender)
+        .add_attribute("request_id", request_id.to_string())
+        .add_attribute("amount", amount)
+        .add_attribute("minted", to_mint);
     Ok(res)
 }
 
+pub fn execute_unstake_denom(
+    deps: DepsMut<ArchwayQuery>,
+    env: Env,
+    info: MessageInfo,
+) -> Result<Response<ArchwayMsg>, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    let denom = config.liquid_denom.ok_or(ContractError::NoLiquidDenom {})?;
+    // other coins sent along would be stuck in the contract
+    if info.funds.len() != 1 || info.funds[0].denom != denom {
+        return Err(ContractError::InvalidUnstakeFunds { denom });
+    }
+    let amount = info.funds[0].amount;
+
+    execute_unstake(deps, env, info.sender, amount)
+}
+
 pub fn execute_receive(
-    deps: DepsMut,
+    deps: DepsMut<ArchwayQuery>,
     env: Env,
     info: MessageInfo,
     wrapper: Cw20ReceiveMsg,
-) -> Result<Response, ContractError> {
+) -> Result<Response<ArchwayMsg>, ContractError> {
     // info.sender is the address of the cw20 contract (that re-sent this message).
     // wrapper.sender is the address of the user that requested the cw20 contract to send this.
     // This cannot be fully trusted (the cw20 contract can fake it), so only use it for actions
@@ -353,20 +1108,36 @@ pub fn execute_receive(
 }
 
 pub fn execute_claim(
-    deps: DepsMut,
+    deps: DepsMut<ArchwayQuery>,
     info: MessageInfo,
-) -> Result<Response, ContractError> {
+) -> Result<Response<ArchwayMsg>, ContractError> {
+    ensure_not_paused(deps.storage, Operation::Claim)?;
     let config = CONFIG.load(deps.storage)?;
 
-    let mut to_send:Uint128 = Uint128::zero();
-    CLAIMABLE.update(
-        deps.storage,
-        &info.sender,
-        |claimable: Option<Uint128>| -> StdResult<_> {
-            to_send = claimable.unwrap_or_default();
-            Ok(Uint128::zero()) 
-        },
-    )?;
+    let claimable = CLAIMABLE.may_load(deps.storage, &info.sender)?.unwrap_or_default();
+    if claimable.is_zero() {
+        return Err(ContractError::NothingToClaim {});
+    }
+    // mark the requests behind the claimable total as claimed, a batch at a time
+    let unclaimed: Vec<u64> = UNCLAIMED_REQUESTS
+        .prefix(&info.sender)
+        .keys(deps.storage, None, None, Order::Ascending)
+        .take(CLAIM_BATCH_SIZE + 1)
+        .collect::<StdResult<_>>()?;
+    let exhausted = unclaimed.len() <= CLAIM_BATCH_SIZE;
+    let mut batch_total = Uint128::zero();
+    for id in unclaimed.into_iter().take(CLAIM_BATCH_SIZE) {
+        UNSTAKE_REQUESTS.update(deps.storage, id, |unstake_request| -> StdResult<_> {
+            let mut unstake_request = unstake_request.ok_or_else(|| StdError::not_found("UnstakeRequest"))?;
+            batch_total += unstake_request.filled.checked_sub(unstake_request.claimed)?;
+            unstake_request.claimed = unstake_request.filled;
+            Ok(unstake_request)
+        })?;
+        UNCLAIMED_REQUESTS.remove(deps.storage, (&info.sender, id));
+    }
+    // the whole total is paid with the last batch, it also holds amounts filled before requests were tracked
+    let to_send = if exhausted { claimable } else { batch_total.min(claimable) };
+    CLAIMABLE.save(deps.storage, &info.sender, &(claimable - to_send))?;
     if to_send.is_zero() {
         return Err(ContractError::NothingToClaim {});
     }
@@ -375,57 +1146,963 @@ pub fn execute_claim(
         supply.claims = supply.claims.checked_sub(to_send)?;
         Ok(supply)
     })?;
+    LEDGER.update(deps.storage, |mut ledger| -> StdResult<_> {
+        ledger.balance = ledger.balance.checked_sub(to_send)?;
+        Ok(ledger)
+    })?;
     
     // transfer tokens to the sender
+    // a failing hook must not lock the claim
+    let hooks = prepare_hooks(deps.storage, HookExecuteMsg::ClaimHook {
+        address: info.sender.to_string(),
+        amount: to_send,
+    }, true)?;
     let res = Response::new()
         .add_message(BankMsg::Send {
             to_address: info.sender.to_string(),
             amount: coins(to_send.u128(), config.bond_denom),
         })
+        .add_submessages(hooks)
         .add_attribute("action", "claim")
         .add_attribute("from", info.sender)
-        .add_attribute("amount", to_send);
+        .add_attribute("amount", to_send)
+        .add_attribute("complete", exhausted.to_string());
     Ok(res)
 }
 
-pub fn execute_set_liquid_token(
-    deps: DepsMut,
-    info: MessageInfo,
-    address: Addr,
-) -> Result<Response, ContractError> {
-    let config = CONFIG.load(deps.storage)?;
-    // only allow owner to call 
-    if info.sender != config.owner {
-        return Err(ContractError::Unauthorized {});
+// claim_requests marks the filled part of the requests claimed by their holder and returns the total,
+// which the caller sends to the holder
+fn claim_requests(deps: DepsMut<ArchwayQuery>, holder: &Addr, ids: &[u64]) -> Result<Uint128, ContractError> {
+    let mut to_send = Uint128::zero();
+    for id in ids.iter().copied() {
+        let mut unstake_request = UNSTAKE_REQUESTS
+            .may_load(deps.storage, id)?
+            .ok_or(ContractError::UnstakeRequestNotFound { id })?;
+        if get_request_holder(&deps.querier, &unstake_request, id)? != *holder {
+            return Err(ContractError::Unauthorized {});
+        }
+        let claimable = unstake_request.filled.checked_sub(unstake_request.claimed).map_err(StdError::overflow)?;
+        unstake_request.claimed = unstake_request.filled;
+        UNSTAKE_REQUESTS.save(deps.storage, id, &unstake_request)?;
+        // requests without a ticket are also part of the receiver's claimable total
+        if unstake_request.ticket.is_none() {
+            CLAIMABLE.update(
+                deps.storage,
+                &unstake_request.receiver,
+                |total: Option<Uint128>| -> StdResult<_> { Ok(total.unwrap_or_default().checked_sub(claimable)?) },
+            )?;
+            UNCLAIMED_REQUESTS.remove(deps.storage, (&unstake_request.receiver, id));
+        }
+        to_send += claimable;
+    }
+    if to_send.is_zero() {
+        return Err(ContractError::NothingToClaim {});
     }
-    CONFIG.update(deps.storage, |mut config| -> StdResult<_> {
-        config.liquid_token_addr = address.clone();
-        Ok(config)
+    // update total supply (lower claim)
+    TOTAL_SUPPLY.update(deps.storage, |mut supply| -> StdResult<_> {
+        supply.claims = supply.claims.checked_sub(to_send)?;
+        Ok(supply)
+    })?;
+    LEDGER.update(deps.storage, |mut ledger| -> StdResult<_> {
+        ledger.balance = ledger.balance.checked_sub(to_send)?;
+        Ok(ledger)
     })?;
+    Ok(to_send)
+}
+
+pub fn execute_claim_requests(
+    mut deps: DepsMut<ArchwayQuery>,
+    info: MessageInfo,
+    ids: Vec<u64>,
+) -> Result<Response<ArchwayMsg>, ContractError> {
+    ensure_not_paused(deps.storage, Operation::Claim)?;
+    let config = CONFIG.load(deps.storage)?;
+    let to_send = claim_requests(deps.branch(), &info.sender, &ids)?;
 
+    // transfer tokens to the request holder
+    let ids: Vec<String> = ids.iter().map(|id| id.to_string()).collect();
+    // a failing hook must not lock the claim
+    let hooks = prepare_hooks(deps.storage, HookExecuteMsg::ClaimHook {
+        address: info.sender.to_string(),
+        amount: to_send,
+    }, true)?;
     let res = Response::new()
-        .add_attribute("action", "setLiquidToken")
+        .add_message(BankMsg::Send {
+            to_address: info.sender.to_string(),
+            amount: coins(to_send.u128(), config.bond_denom),
+        })
+        .add_submessages(hooks)
+        .add_attribute("action", "claimRequests")
         .add_attribute("from", info.sender)
-        .add_attribute("address", address);
+        .add_attribute("ids", ids.join(","))
+        .add_attribute("amount", to_send);
     Ok(res)
 }
 
-#[cfg_attr(not(feature = "library"), entry_point)]
-pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
-    match msg {
-        QueryMsg::ClaimableOf { address } => {
-            to_binary(&query_claimable_of(deps, address)?)
-        },
-        QueryMsg::ConfigInfo {} => to_binary(&query_config(deps)?),
-        QueryMsg::StatusInfo {} => to_binary(&query_status(deps, _env)?),
-        QueryMsg::UnstakingQueue {} => to_binary(&query_unstaking_queue(deps)?),
+pub fn execute_claim_ticket(
+    mut deps: DepsMut<ArchwayQuery>,
+    info: MessageInfo,
+    id: u64,
+) -> Result<Response<ArchwayMsg>, ContractError> {
+    ensure_not_paused(deps.storage, Operation::Claim)?;
+    let config = CONFIG.load(deps.storage)?;
+    let ticketed = UNSTAKE_REQUESTS
+        .may_load(deps.storage, id)?
+        .is_some_and(|unstake_request| unstake_request.ticket.is_some());
+    if !ticketed {
+        return Err(ContractError::TicketNotFound { id });
+    }
+    let to_send = claim_requests(deps.branch(), &info.sender, &[id])?;
+
+    // transfer tokens to the ticket holder
+    // a failing hook must not lock the claim
+    let hooks = prepare_hooks(deps.storage, HookExecuteMsg::ClaimHook {
+        address: info.sender.to_string(),
+        amount: to_send,
+    }, true)?;
+    let res = Response::new()
+        .add_message(BankMsg::Send {
+            to_address: info.sender.to_string(),
+            amount: coins(to_send.u128(), config.bond_denom),
+        })
+        .add_submessages(hooks)
+        .add_attribute("action", "claimTicket")
+        .add_attribute("from", info.sender)
+        .add_attribute("id", id.to_string())
+        .add_attribute("amount", to_send);
+    Ok(res)
+}
+
+pub fn execute_process_queue(
+    deps: DepsMut<ArchwayQuery>,
+    env: Env,
+    info: MessageInfo,
+    limit: Option<u32>,
+) -> Result<Response<ArchwayMsg>, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    let limit = limit.unwrap_or(config.queue_batch_size).min(MAX_QUEUE_BATCH_SIZE);
+    let actual_balance = deps
+        .querier
+        .query_balance(&env.contract.address, &config.bond_denom)?.amount;
+    // book matured unbondings, then pay the queue with what is not reserved for claims
+    let mut ledger = LEDGER.load(deps.storage)?;
+    sync_ledger(
+        deps.storage,
+        &mut ledger,
+        env.block.time,
+        actual_balance,
+        Uint128::zero(),
+    )?;
+    let mut supply = TOTAL_SUPPLY.load(deps.storage)?;
+    let available = ledger.balance.checked_sub(supply.claims).map_err(StdError::overflow)?;
+    let (remaining, processed) = pay_unstaking_queue(deps.storage, &mut supply, available, limit)?;
+    let mut res = Response::new();
+    let mut rewards = Uint128::zero();
+    // bond what is left once the queue is empty, like _ProcessToken does. Delegating withdraws
+    // the pending rewards, they are booked first
+    if supply.unstakings.is_zero() && !remaining.is_zero() {
+        rewards = book_withdrawn_rewards(deps.storage, &deps.querier, &env, &config, &mut ledger, &mut supply)?;
+        ledger.balance = ledger.balance.checked_sub(remaining).map_err(StdError::overflow)?;
+        res = res.add_message(StakingMsg::Delegate {
+            validator: config.validator,
+            amount: coin(remaining.u128(), &config.bond_denom),
+        });
+    }
+    LEDGER.save(deps.storage, &ledger)?;
+    TOTAL_SUPPLY.save(deps.storage, &supply)?;
+
+    res = res
+        .add_attribute("action", "processQueue")
+        .add_attribute("from", info.sender)
+        .add_attribute("processed", processed.to_string())
+        .add_attribute("paid", available - remaining)
+        .add_attribute("rewards", rewards);
+    Ok(res)
+}
+
+pub fn execute_harvest(deps: DepsMut<ArchwayQuery>, env: Env, info: MessageInfo) -> Result<Response<ArchwayMsg>, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    if let Some(last_harvest) = LAST_HARVEST.may_load(deps.storage)? {
+        let next = last_harvest.plus_seconds(config.harvest_interval);
+        if env.block.time < next {
+            return Err(ContractError::HarvestTooSoon { next: next.seconds() });
+        }
+    }
+    LAST_HARVEST.save(deps.storage, &env.block.time)?;
+
+    // claim rewards and process them, paying the bounty to the caller
+    let msg = to_json_binary(&ExecuteMsg::_PerformCheck { harvester: Some(info.sender.clone()) })?;
+    let res = Response::new()
+        .add_message(WasmMsg::Execute {
+            contract_addr: env.contract.address.to_string(),
+            msg,
+            funds: vec![],
+        })
+        .add_attribute("action", "harvest")
+        .add_attribute("from", info.sender);
+    Ok(res)
+}
+
+pub fn execute_deposit_rewards(
+    deps: DepsMut<ArchwayQuery>,
+    env: Env,
+    info: MessageInfo,
+) -> Result<Response<ArchwayMsg>, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    // only allow rewards collector to call
+    if Some(&info.sender) != config.rewards_collector.as_ref() {
+        return Err(ContractError::Unauthorized {});
+    }
+    if info.funds.len() != 1 || info.funds[0].denom != config.bond_denom {
+        return Err(ContractError::InvalidRewards { denom: config.bond_denom });
+    }
+    let mut rewards = info.funds[0].amount;
+    let mut res = Response::new();
+    // pay the bounty of the harvest that withdrew the rewards
+    if let Some(harvester) = PENDING_HARVESTER.may_load(deps.storage)? {
+        PENDING_HARVESTER.remove(deps.storage);
+        let bounty = rewards * config.harvest_bounty;
+        if !bounty.is_zero() {
+            rewards = rewards.checked_sub(bounty).map_err(StdError::overflow)?;
+            res = res
+                .add_message(BankMsg::Send {
+                    to_address: harvester.to_string(),
+                    amount: coins(bounty.u128(), &config.bond_denom),
+                })
+                .add_attribute("bounty", bounty);
+        }
+    }
+
+    // rewards are booked exactly as sent, not inferred from the balance
+    let mut supply = TOTAL_SUPPLY.load(deps.storage)?;
+    supply.native += rewards;
+    TOTAL_SUPPLY.save(deps.storage, &supply)?;
+    LEDGER.update(deps.storage, |mut ledger| -> StdResult<_> {
+        ledger.balance += rewards;
+        Ok(ledger)
+    })?;
+    if !rewards.is_zero() {
+        let liquid_supply = get_liquid_supply(deps.storage, &deps.querier, &config)?;
+        record_ratio_snapshot(deps.storage, &env, &supply, liquid_supply)?;
+    }
+
+    // process unstaking queue and bond the rewards, they are already booked
+    let msg = to_json_binary(&ExecuteMsg::_ProcessToken { rewards: Uint128::zero(), harvester: None })?;
+    res = res
+        .add_message(WasmMsg::Execute {
+            contract_addr: env.contract.address.to_string(),
+            msg,
+            funds: vec![],
+        })
+        .add_attribute("action", "depositRewards")
+        .add_attribute("from", info.sender)
+        .add_attribute("amount", rewards);
+    Ok(res)
+}
+
+pub fn execute_set_harvest_config(
+    deps: DepsMut<ArchwayQuery>,
+    env: Env,
+    info: MessageInfo,
+    bounty: Decimal,
+    interval: u64,
+) -> Result<Response<ArchwayMsg>, ContractError> {
+    ensure_has_role(deps.storage, &info.sender, Role::Owner)?;
+    let max_bounty = Decimal::percent(MAX_HARVEST_BOUNTY_PERCENT);
+    if bounty > max_bounty {
+        return Err(ContractError::InvalidHarvestBounty { max: max_bounty });
+    }
+    let config = CONFIG.load(deps.storage)?;
+    let change = AdminChange::SetHarvestConfig { bounty, interval };
+    schedule_change(deps, env, info.sender, change, config.timelock_delay)
+}
+
+pub fn execute_set_contract_metadata(
+    deps: DepsMut<ArchwayQuery>,
+    env: Env,
+    info: MessageInfo,
+    owner_address: Option<String>,
+    rewards_address: Option<String>,
+) -> Result<Response<ArchwayMsg>, ContractError> {
+    ensure_has_role(deps.storage, &info.sender, Role::Owner)?;
+    let config = CONFIG.load(deps.storage)?;
+    let change = AdminChange::SetContractMetadata { owner_address, rewards_address };
+    schedule_change(deps, env, info.sender, change, config.timelock_delay)
+}
+
+pub fn execute_set_rewards_collector(
+    deps: DepsMut<ArchwayQuery>,
+    env: Env,
+    info: MessageInfo,
+    address: Option<String>,
+) -> Result<Response<ArchwayMsg>, ContractError> {
+    ensure_has_role(deps.storage, &info.sender, Role::Owner)?;
+    let config = CONFIG.load(deps.storage)?;
+    let address = address.map(|addr| deps.api.addr_validate(&addr)).transpose()?;
+    let change = AdminChange::SetRewardsCollector { address };
+    schedule_change(deps, env, info.sender, change, config.timelock_delay)
+}
+
+pub fn execute_set_ticket_contract(
+    deps: DepsMut<ArchwayQuery>,
+    env: Env,
+    info: MessageInfo,
+    address: Option<String>,
+) -> Result<Response<ArchwayMsg>, ContractError> {
+    ensure_has_role(deps.storage, &info.sender, Role::Owner)?;
+    let config = CONFIG.load(deps.storage)?;
+    let address = address.map(|addr| deps.api.addr_validate(&addr)).transpose()?;
+    let change = AdminChange::SetTicketContract { address };
+    schedule_change(deps, env, info.sender, change, config.timelock_delay)
+}
+
+pub fn execute_sweep_unaccounted(
+    deps: DepsMut<ArchwayQuery>,
+    env: Env,
+    info: MessageInfo,
+    recipient: Option<String>,
+) -> Result<Response<ArchwayMsg>, ContractError> {
+    ensure_has_role(deps.storage, &info.sender, Role::Owner)?;
+    let config = CONFIG.load(deps.storage)?;
+    let recipient = match recipient {
+        Some(recipient) => deps.api.addr_validate(&recipient)?,
+        None => info.sender.clone(),
+    };
+    let actual_balance = deps
+        .querier
+        .query_balance(&env.contract.address, &config.bond_denom)?.amount;
+    // book matured unbondings first so they are not swept, and keep what may be
+    // pending unbondings back early
+    let mut ledger = LEDGER.load(deps.storage)?;
+    let (_, unaccounted) = sync_ledger(
+        deps.storage,
+        &mut ledger,
+        env.block.time,
+        actual_balance,
+        Uint128::zero(),
+    )?;
+    let unaccounted = unaccounted.saturating_sub(ledger.unbonding);
+    if unaccounted.is_zero() {
+        return Err(ContractError::NothingToSweep { denom: config.bond_denom });
+    }
+    LEDGER.save(deps.storage, &ledger)?;
+
+    let res = Response::new()
+        .add_message(BankMsg::Send {
+            to_address: recipient.to_string(),
+            amount: coins(unaccounted.u128(), config.bond_denom),
+        })
+        .add_attribute("action", "sweepUnaccounted")
+        .add_attribute("from", info.sender)
+        .add_attribute("to", recipient)
+        .add_attribute("amount", unaccounted);
+    Ok(res)
+}
+
+pub fn execute_set_liquid_token(
+    deps: DepsMut<ArchwayQuery>,
+    env: Env,
+    info: MessageInfo,
+    address: Addr,
+) -> Result<Response<ArchwayMsg>, ContractError> {
+    ensure_has_role(deps.storage, &info.sender, Role::Owner)?;
+    let config = CONFIG.load(deps.storage)?;
+    if config.liquid_denom.is_some() {
+        return Err(ContractError::LiquidTokenAlreadySet {});
+    }
+    // the first liquid token is set up at once, nothing has been minted yet
+    let delay = if config.liquid_token_addr == Addr::unchecked("none") {
+        0
+    } else {
+        config.timelock_delay
+    };
+    schedule_change(deps, env, info.sender, AdminChange::SetLiquidToken { address }, delay)
+}
+
+pub fn execute_create_liquid_denom(
+    deps: DepsMut<ArchwayQuery>,
+    env: Env,
+    info: MessageInfo,
+    subdenom: String,
+) -> Result<Response<ArchwayMsg>, ContractError> {
+    ensure_has_role(deps.storage, &info.sender, Role::Owner)?;
+    let mut config = CONFIG.load(deps.storage)?;
+    // the liquid token can't be switched once it may have been minted
+    if config.liquid_token_addr != Addr::unchecked("none") || config.liquid_denom.is_some() {
+        return Err(ContractError::LiquidTokenAlreadySet {});
+    }
+    let denom = format!("factory/{}/{}", env.contract.address, subdenom);
+    config.liquid_denom = Some(denom.clone());
+    CONFIG.save(deps.storage, &config)?;
+    LIQUID_SUPPLY.save(deps.storage, &Uint128::zero())?;
+
+    let res = Response::new()
+        .add_message(tokenfactory::create_denom(env.contract.address.as_str(), &subdenom))
+        .add_attribute("action", "createLiquidDenom")
+        .add_attribute("from", info.sender)
+        .add_attribute("denom", denom);
+    Ok(res)
+}
+
+// apply_change performs a queued admin change
+fn apply_change(
+    deps: DepsMut<ArchwayQuery>,
+    env: Env,
+    change: AdminChange,
+) -> Result<Response<ArchwayMsg>, ContractError> {
+    let mut config = CONFIG.load(deps.storage)?;
+    let res = match change {
+        AdminChange::SetLiquidToken { address } => {
+            config.liquid_token_addr = address.clone();
+            Response::new()
+                .add_attribute("action", "setLiquidToken")
+                .add_attribute("address", address)
+        },
+        AdminChange::SetTimelockDelay { delay } => {
+            config.timelock_delay = delay;
+            Response::new()
+                .add_attribute("action", "setTimelockDelay")
+                .add_attribute("delay", delay.to_string())
+        },
+        AdminChange::SetContractMetadata { owner_address, rewards_address } => {
+            Response::new()
+                .add_message(ArchwayMsg::UpdateContractMetadata {
+                    owner_address: owner_address.clone(),
+                    rewards_address: rewards_address.clone(),
+                })
+                .add_attribute("action", "setContractMetadata")
+                .add_attribute("owner_address", owner_address.unwrap_or_default())
+                .add_attribute("rewards_address", rewards_address.unwrap_or_default())
+        },
+        AdminChange::SetRewardsCollector { address } => {
+            // delegator rewards go to the collector, or back to this contract
+            let withdraw_address = address.clone().unwrap_or_else(|| env.contract.address.clone());
+            config.rewards_collector = address;
+            Response::new()
+                .add_message(DistributionMsg::SetWithdrawAddress {
+                    address: withdraw_address.to_string(),
+                })
+                .add_attribute("action", "setRewardsCollector")
+                .add_attribute("address", withdraw_address)
+        },
+        AdminChange::SetTicketContract { address } => {
+            // tickets already minted stay on the contract they were minted on
+            config.ticket_addr = address.clone();
+            Response::new()
+                .add_attribute("action", "setTicketContract")
+                .add_attribute("address", address.map(String::from).unwrap_or_else(|| "none".to_string()))
+        },
+        AdminChange::AddHook { address, revert_on_error } => {
+            // hooks may have been added while the change was queued
+            ensure_hook_can_be_added(deps.storage, &address)?;
+            HOOKS.save(deps.storage, &address, &HookConfig { revert_on_error })?;
+            Response::new()
+                .add_attribute("action", "addHook")
+                .add_attribute("address", address)
+                .add_attribute("revert_on_error", revert_on_error.to_string())
+        },
+        AdminChange::SetQueueBatchSize { size } => {
+            config.queue_batch_size = size;
+            Response::new()
+                .add_attribute("action", "setQueueBatchSize")
+                .add_attribute("size", size.to_string())
+        },
+        AdminChange::SetDepositLimits { limits } => {
+            DEPOSIT_LIMITS.save(deps.storage, &limits)?;
+            Response::new()
+                .add_attribute("action", "setDepositLimits")
+                .add_attribute("deposit_cap", limits.deposit_cap.map(|cap| cap.to_string()).unwrap_or_else(|| "none".to_string()))
+                .add_attribute("address_cap", limits.address_cap.map(|cap| cap.to_string()).unwrap_or_else(|| "none".to_string()))
+                .add_attribute("min_stake", limits.min_stake)
+        },
+        AdminChange::SetHarvestConfig { bounty, interval } => {
+            config.harvest_bounty = bounty;
+            config.harvest_interval = interval;
+            Response::new()
+                .add_attribute("action", "setHarvestConfig")
+                .add_attribute("bounty", bounty.to_string())
+                .add_attribute("interval", interval.to_string())
+        },
+    };
+    CONFIG.save(deps.storage, &config)?;
+    Ok(res)
+}
+
+// schedule_change queues an admin change behind the timelock, or applies it at once without delay
+fn schedule_change(
+    deps: DepsMut<ArchwayQuery>,
+    env: Env,
+    sender: Addr,
+    change: AdminChange,
+    delay: u64,
+) -> Result<Response<ArchwayMsg>, ContractError> {
+    if delay == 0 {
+        return Ok(apply_change(deps, env, change)?.add_attribute("from", sender));
+    }
+    let id = PENDING_CHANGES_NEXT_ID.may_load(deps.storage)?.unwrap_or_default();
+    let eta = env.block.time.plus_seconds(delay);
+    PENDING_CHANGES.save(deps.storage, id, &PendingChange { change, eta })?;
+    PENDING_CHANGES_NEXT_ID.save(deps.storage, &(id + 1))?;
+
+    let res = Response::new()
+        .add_attribute("action", "queueChange")
+        .add_attribute("from", sender)
+        .add_attribute("change_id", id.to_string())
+        .add_attribute("eta", eta.seconds().to_string());
+    Ok(res)
+}
+
+pub fn execute_set_timelock_delay(
+    deps: DepsMut<ArchwayQuery>,
+    env: Env,
+    info: MessageInfo,
+    delay: u64,
+) -> Result<Response<ArchwayMsg>, ContractError> {
+    ensure_has_role(deps.storage, &info.sender, Role::Owner)?;
+    let config = CONFIG.load(deps.storage)?;
+    schedule_change(deps, env, info.sender, AdminChange::SetTimelockDelay { delay }, config.timelock_delay)
+}
+
+pub fn execute_pending_change(
+    deps: DepsMut<ArchwayQuery>,
+    env: Env,
+    info: MessageInfo,
+    id: u64,
+) -> Result<Response<ArchwayMsg>, ContractError> {
+    ensure_has_role(deps.storage, &info.sender, Role::Owner)?;
+    let pending = PENDING_CHANGES
+        .may_load(deps.storage, id)?
+        .ok_or(ContractError::PendingChangeNotFound { id })?;
+    if env.block.time < pending.eta {
+        return Err(ContractError::TimelockNotExpired { id, eta: pending.eta.seconds() });
+    }
+    PENDING_CHANGES.remove(deps.storage, id);
+
+    let res = apply_change(deps, env, pending.change)?
+        .add_attribute("from", info.sender)
+        .add_attribute("change_id", id.to_string());
+    Ok(res)
+}
+
+pub fn execute_cancel_pending_change(
+    deps: DepsMut<ArchwayQuery>,
+    info: MessageInfo,
+    id: u64,
+) -> Result<Response<ArchwayMsg>, ContractError> {
+    ensure_has_role(deps.storage, &info.sender, Role::Guardian)?;
+    if PENDING_CHANGES.may_load(deps.storage, id)?.is_none() {
+        return Err(ContractError::PendingChangeNotFound { id });
+    }
+    PENDING_CHANGES.remove(deps.storage, id);
+
+    let res = Response::new()
+        .add_attribute("action", "cancelChange")
+        .add_attribute("from", info.sender)
+        .add_attribute("change_id", id.to_string());
+    Ok(res)
+}
+
+pub fn execute_propose_new_owner(
+    deps: DepsMut<ArchwayQuery>,
+    info: MessageInfo,
+    owner: String,
+    expiry: Option<u64>,
+) -> Result<Response<ArchwayMsg>, ContractError> {
+    ensure_has_role(deps.storage, &info.sender, Role::Owner)?;
+    let pending = PendingOwner {
+        owner: deps.api.addr_validate(&owner)?,
+        expiry: expiry.map(Timestamp::from_seconds),
+    };
+    PENDING_OWNER.save(deps.storage, &pending)?;
+
+    let res = Response::new()
+        .add_attribute("action", "proposeNewOwner")
+        .add_attribute("from", info.sender)
+        .add_attribute("owner", pending.owner)
+        .add_attribute("expiry", expiry.map(|e| e.to_string()).unwrap_or_else(|| "none".to_string()));
+    Ok(res)
+}
+
+pub fn execute_accept_ownership(
+    deps: DepsMut<ArchwayQuery>,
+    env: Env,
+    info: MessageInfo,
+) -> Result<Response<ArchwayMsg>, ContractError> {
+    let pending = PENDING_OWNER
+        .may_load(deps.storage)?
+        .ok_or(ContractError::NoPendingOwner {})?;
+    // only allow proposed owner to call
+    if info.sender != pending.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+    if let Some(expiry) = pending.expiry {
+        if env.block.time >= expiry {
+            return Err(ContractError::OwnershipProposalExpired {});
+        }
+    }
+    let mut config = CONFIG.load(deps.storage)?;
+    let previous_owner = config.owner.map(String::from).unwrap_or_else(|| "none".to_string());
+    config.owner = Some(pending.owner);
+    CONFIG.save(deps.storage, &config)?;
+    PENDING_OWNER.remove(deps.storage);
+
+    let res = Response::new()
+        .add_attribute("action", "acceptOwnership")
+        .add_attribute("from", info.sender)
+        .add_attribute("previous_owner", previous_owner);
+    Ok(res)
+}
+
+pub fn execute_cancel_ownership_proposal(
+    deps: DepsMut<ArchwayQuery>,
+    info: MessageInfo,
+) -> Result<Response<ArchwayMsg>, ContractError> {
+    ensure_has_role(deps.storage, &info.sender, Role::Owner)?;
+    if PENDING_OWNER.may_load(deps.storage)?.is_none() {
+        return Err(ContractError::NoPendingOwner {});
+    }
+    PENDING_OWNER.remove(deps.storage);
+
+    let res = Response::new()
+        .add_attribute("action", "cancelOwnershipProposal")
+        .add_attribute("from", info.sender);
+    Ok(res)
+}
+
+pub fn execute_renounce_ownership(
+    deps: DepsMut<ArchwayQuery>,
+    info: MessageInfo,
+) -> Result<Response<ArchwayMsg>, ContractError> {
+    ensure_has_role(deps.storage, &info.sender, Role::Owner)?;
+    let mut config = CONFIG.load(deps.storage)?;
+    config.owner = None;
+    CONFIG.save(deps.storage, &config)?;
+    PENDING_OWNER.remove(deps.storage);
+
+    let res = Response::new()
+        .add_attribute("action", "renounceOwnership")
+        .add_attribute("from", info.sender);
+    Ok(res)
+}
+
+pub fn execute_grant_role(
+    deps: DepsMut<ArchwayQuery>,
+    info: MessageInfo,
+    role: Role,
+    address: String,
+) -> Result<Response<ArchwayMsg>, ContractError> {
+    ensure_has_role(deps.storage, &info.sender, Role::Owner)?;
+    if role == Role::Owner {
+        return Err(ContractError::OwnerRoleNotGrantable {});
+    }
+    let address = deps.api.addr_validate(&address)?;
+    ROLES.save(deps.storage, (role.as_str(), &address), &Empty {})?;
+
+    let res = Response::new()
+        .add_attribute("action", "grantRole")
+        .add_attribute("from", info.sender)
+        .add_attribute("role", role.as_str())
+        .add_attribute("address", address);
+    Ok(res)
+}
+
+pub fn execute_revoke_role(
+    deps: DepsMut<ArchwayQuery>,
+    info: MessageInfo,
+    role: Role,
+    address: String,
+) -> Result<Response<ArchwayMsg>, ContractError> {
+    ensure_has_role(deps.storage, &info.sender, Role::Owner)?;
+    if role == Role::Owner {
+        return Err(ContractError::OwnerRoleNotGrantable {});
+    }
+    let address = deps.api.addr_validate(&address)?;
+    ROLES.remove(deps.storage, (role.as_str(), &address));
+
+    let res = Response::new()
+        .add_attribute("action", "revokeRole")
+        .add_attribute("from", info.sender)
+        .add_attribute("role", role.as_str())
+        .add_attribute("address", address);
+    Ok(res)
+}
+
+pub fn execute_vote(
+    deps: DepsMut<ArchwayQuery>,
+    env: Env,
+    info: MessageInfo,
+    proposal_id: u64,
+    vote: VoteOption,
+) -> Result<Response<ArchwayMsg>, ContractError> {
+    let mut proposal = GOV_PROPOSALS
+        .may_load(deps.storage, proposal_id)?
+        .ok_or(ContractError::ProposalNotFound { proposal_id })?;
+    if env.block.time >= proposal.voting_end {
+        return Err(ContractError::VotingEnded { proposal_id });
+    }
+    let config = CONFIG.load(deps.storage)?;
+    // TokenFactory balances have no snapshots to weigh votes with
+    if config.liquid_denom.is_some() {
+        return Err(ContractError::VotingUnsupported {});
+    }
+    let weight = get_snapshot_balance(&deps.querier, &config.liquid_token_addr, &info.sender, proposal.snapshot_height)?;
+    if weight.is_zero() {
+        return Err(ContractError::NoVotingPower { height: proposal.snapshot_height });
+    }
+    // a new vote replaces the previous one
+    if let Some(previous) = GOV_VOTES.may_load(deps.storage, (proposal_id, &info.sender))? {
+        let power = tally_of(&mut proposal.tally, &previous.option);
+        *power = power.checked_sub(previous.weight).map_err(StdError::overflow)?;
+    }
+    *tally_of(&mut proposal.tally, &vote) += weight;
+    // the tally is cast on every vote so the contract has voted before the period ends
+    let cast = cast_tally(&mut proposal, proposal_id, env.block.time);
+    GOV_PROPOSALS.save(deps.storage, proposal_id, &proposal)?;
+    GOV_VOTES.save(deps.storage, (proposal_id, &info.sender), &GovVote { option: vote, weight })?;
+
+    let res = Response::new()
+        .add_messages(cast)
+        .add_attribute("action", "vote")
+        .add_attribute("from", info.sender)
+        .add_attribute("proposal_id", proposal_id.to_string())
+        .add_attribute("weight", weight);
+    Ok(res)
+}
+
+pub fn execute_register_proposal(
+    deps: DepsMut<ArchwayQuery>,
+    env: Env,
+    info: MessageInfo,
+    proposal_id: u64,
+    voting_end: u64,
+) -> Result<Response<ArchwayMsg>, ContractError> {
+    ensure_has_role(deps.storage, &info.sender, Role::Operator)?;
+    if GOV_PROPOSALS.has(deps.storage, proposal_id) {
+        return Err(ContractError::ProposalAlreadyRegistered { proposal_id });
+    }
+    let voting_end = Timestamp::from_seconds(voting_end);
+    if env.block.time >= voting_end {
+        return Err(ContractError::VotingEnded { proposal_id });
+    }
+    let proposal = GovProposal {
+        snapshot_height: env.block.height,
+        voting_end,
+        tally: VoteTally::default(),
+        cast_at: None,
+    };
+    GOV_PROPOSALS.save(deps.storage, proposal_id, &proposal)?;
+
+    let res = Response::new()
+        .add_attribute("action", "registerProposal")
+        .add_attribute("from", info.sender)
+        .add_attribute("proposal_id", proposal_id.to_string())
+        .add_attribute("snapshot_height", proposal.snapshot_height.to_string());
+    Ok(res)
+}
+
+pub fn execute_cast_vote(
+    deps: DepsMut<ArchwayQuery>,
+    env: Env,
+    info: MessageInfo,
+    proposal_id: u64,
+) -> Result<Response<ArchwayMsg>, ContractError> {
+    ensure_has_role(deps.storage, &info.sender, Role::Operator)?;
+    let mut proposal = GOV_PROPOSALS
+        .may_load(deps.storage, proposal_id)?
+        .ok_or(ContractError::ProposalNotFound { proposal_id })?;
+    if env.block.time >= proposal.voting_end {
+        return Err(ContractError::VotingEnded { proposal_id });
+    }
+    let msg = cast_tally(&mut proposal, proposal_id, env.block.time)
+        .ok_or(ContractError::NoVotes { proposal_id })?;
+    GOV_PROPOSALS.save(deps.storage, proposal_id, &proposal)?;
+
+    let res = Response::new()
+        .add_message(msg)
+        .add_attribute("action", "castVote")
+        .add_attribute("from", info.sender)
+        .add_attribute("proposal_id", proposal_id.to_string());
+    Ok(res)
+}
+
+pub fn execute_add_hook(
+    deps: DepsMut<ArchwayQuery>,
+    env: Env,
+    info: MessageInfo,
+    address: String,
+    revert_on_error: bool,
+) -> Result<Response<ArchwayMsg>, ContractError> {
+    ensure_has_role(deps.storage, &info.sender, Role::Owner)?;
+    let address = deps.api.addr_validate(&address)?;
+    ensure_hook_can_be_added(deps.storage, &address)?;
+    let config = CONFIG.load(deps.storage)?;
+    // a hook reverting on error can block stakes and unstakes, users get the timelock to react
+    let change = AdminChange::AddHook { address, revert_on_error };
+    schedule_change(deps, env, info.sender, change, config.timelock_delay)
+}
+
+// ensure_hook_can_be_added checks that the hook is not registered yet and that there is room for it
+fn ensure_hook_can_be_added(storage: &dyn Storage, address: &Addr) -> Result<(), ContractError> {
+    if HOOKS.has(storage, address) {
+        return Err(ContractError::HookAlreadyRegistered { address: address.to_string() });
+    }
+    let count = HOOKS.keys(storage, None, None, Order::Ascending).count();
+    if count >= MAX_HOOKS as usize {
+        return Err(ContractError::TooManyHooks { max: MAX_HOOKS });
+    }
+    Ok(())
+}
+
+pub fn execute_remove_hook(
+    deps: DepsMut<ArchwayQuery>,
+    info: MessageInfo,
+    address: String,
+) -> Result<Response<ArchwayMsg>, ContractError> {
+    ensure_has_role(deps.storage, &info.sender, Role::Owner)?;
+    let address = deps.api.addr_validate(&address)?;
+    if !HOOKS.has(deps.storage, &address) {
+        return Err(ContractError::HookNotRegistered { address: address.into_string() });
+    }
+    HOOKS.remove(deps.storage, &address);
+
+    let res = Response::new()
+        .add_attribute("action", "removeHook")
+        .add_attribute("from", info.sender)
+        .add_attribute("address", address);
+    Ok(res)
+}
+
+pub fn execute_repair_queue(
+    deps: DepsMut<ArchwayQuery>,
+    info: MessageInfo,
+) -> Result<Response<ArchwayMsg>, ContractError> {
+    ensure_has_role(deps.storage, &info.sender, Role::Owner)?;
+    let state = UNSTAKING_QUEUE.repair(deps.storage)?;
+
+    let res = Response::new()
+        .add_attribute("action", "repairQueue")
+        .add_attribute("from", info.sender)
+        .add_attribute("length", state.length.to_string())
+        .add_attribute("head_id", state.head_id.to_string())
+        .add_attribute("tail_id", state.tail_id.to_string());
+    Ok(res)
+}
+
+pub fn execute_set_queue_batch_size(
+    deps: DepsMut<ArchwayQuery>,
+    env: Env,
+    info: MessageInfo,
+    size: u32,
+) -> Result<Response<ArchwayMsg>, ContractError> {
+    ensure_has_role(deps.storage, &info.sender, Role::Operator)?;
+    if size == 0 || size > MAX_QUEUE_BATCH_SIZE {
+        return Err(ContractError::InvalidQueueBatchSize { max: MAX_QUEUE_BATCH_SIZE });
+    }
+    let config = CONFIG.load(deps.storage)?;
+    let change = AdminChange::SetQueueBatchSize { size };
+    schedule_change(deps, env, info.sender, change, config.timelock_delay)
+}
+
+pub fn execute_set_deposit_limits(
+    deps: DepsMut<ArchwayQuery>,
+    env: Env,
+    info: MessageInfo,
+    deposit_cap: Option<Uint128>,
+    address_cap: Option<Uint128>,
+    min_stake: Uint128,
+) -> Result<Response<ArchwayMsg>, ContractError> {
+    ensure_has_role(deps.storage, &info.sender, Role::Owner)?;
+    let config = CONFIG.load(deps.storage)?;
+    let change = AdminChange::SetDepositLimits { limits: DepositLimits { deposit_cap, address_cap, min_stake } };
+    schedule_change(deps, env, info.sender, change, config.timelock_delay)
+}
+
+pub fn execute_pause(
+    deps: DepsMut<ArchwayQuery>,
+    info: MessageInfo,
+    operation: Option<Operation>,
+) -> Result<Response<ArchwayMsg>, ContractError> {
+    ensure_has_role(deps.storage, &info.sender, Role::Guardian)?;
+    let mut paused = PAUSED.load(deps.storage)?;
+    set_paused(&mut paused, operation, true);
+    PAUSED.save(deps.storage, &paused)?;
+
+    let res = Response::new()
+        .add_attribute("action", "pause")
+        .add_attribute("from", info.sender)
+        .add_attribute("operation", operation.map(|op| op.to_string()).unwrap_or_else(|| "all".to_string()));
+    Ok(res)
+}
+
+pub fn execute_unpause(
+    deps: DepsMut<ArchwayQuery>,
+    info: MessageInfo,
+    operation: Option<Operation>,
+) -> Result<Response<ArchwayMsg>, ContractError> {
+    // once ownership is renounced the guardian unpauses, so a pause can't last for good
+    let config = CONFIG.load(deps.storage)?;
+    let role = if config.owner.is_some() { Role::Owner } else { Role::Guardian };
+    ensure_has_role(deps.storage, &info.sender, role)?;
+    let mut paused = PAUSED.load(deps.storage)?;
+    set_paused(&mut paused, operation, false);
+    PAUSED.save(deps.storage, &paused)?;
+
+    let res = Response::new()
+        .add_attribute("action", "unpause")
+        .add_attribute("from", info.sender)
+        .add_attribute("operation", operation.map(|op| op.to_string()).unwrap_or_else(|| "all".to_string()));
+    Ok(res)
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn reply(_deps: DepsMut<ArchwayQuery>, _env: Env, msg: Reply) -> Result<Response<ArchwayMsg>, ContractError> {
+    match (msg.id, msg.result) {
+        // a failing hook that doesn't revert the action is only recorded
+        (HOOK_REPLY_ID, SubMsgResult::Err(err)) => Ok(Response::new()
+            .add_attribute("action", "hookFailed")
+            .add_attribute("error", err)),
+        (id, _) => Err(StdError::generic_err(format!("Unexpected reply id {}", id)).into()),
+    }
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn query(deps: Deps<ArchwayQuery>, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
+    match msg {
+        QueryMsg::ClaimableOf { address } => {
+            to_json_binary(&query_claimable_of(deps, address)?)
+        },
+        QueryMsg::ConfigInfo {} => to_json_binary(&query_config(deps)?),
+        QueryMsg::PendingOwner {} => to_json_binary(&query_pending_owner(deps)?),
+        QueryMsg::RoleMembers { role, start_after, limit } => {
+            to_json_binary(&query_role_members(deps, role, start_after, limit)?)
+        },
+        QueryMsg::HasRole { role, address } => to_json_binary(&query_has_role(deps, role, address)?),
+        QueryMsg::PendingChanges { start_after, limit } => {
+            to_json_binary(&query_pending_changes(deps, start_after, limit)?)
+        },
+        QueryMsg::StatusInfo {} => to_json_binary(&query_status(deps, _env)?),
+        QueryMsg::UnstakingQueue {} => to_json_binary(&query_unstaking_queue(deps)?),
         QueryMsg::UnderUnstakingOf { address } => {
-            to_binary(&query_under_unstaking_of(deps, address)?)
+            to_json_binary(&query_under_unstaking_of(deps, address)?)
         },
+        QueryMsg::RatioHistory { start_after, limit } => {
+            to_json_binary(&query_ratio_history(deps, start_after, limit)?)
+        },
+        QueryMsg::Apr { window_seconds } => to_json_binary(&query_apr(deps, _env, window_seconds)?),
+        QueryMsg::ArchwayRewards {} => to_json_binary(&query_archway_rewards(deps, _env)?),
+        QueryMsg::VerifyQueue { limit } => to_json_binary(&query_verify_queue(deps, limit)?),
+        QueryMsg::Solvency {} => to_json_binary(&query_solvency(deps, _env)?),
+        QueryMsg::Proposal { proposal_id } => to_json_binary(&query_proposal(deps, proposal_id)?),
+        QueryMsg::VoteOf { proposal_id, address } => {
+            to_json_binary(&query_vote_of(deps, proposal_id, address)?)
+        },
+        QueryMsg::Hooks {} => to_json_binary(&query_hooks(deps)?),
+        QueryMsg::RequestStatus { id } => to_json_binary(&query_request_status(deps, id)?),
+        QueryMsg::Ticket { id } => to_json_binary(&query_ticket(deps, id)?),
     }
 }
 
-pub fn query_claimable_of(deps: Deps, address: String) -> StdResult<BalanceResponse> {
+pub fn query_claimable_of(deps: Deps<ArchwayQuery>, address: String) -> StdResult<BalanceResponse> {
     let address = deps.api.addr_validate(&address)?;
     let claimable = CLAIMABLE
         .may_load(deps.storage, &address)?
@@ -433,47 +2110,126 @@ pub fn query_claimable_of(deps: Deps, address: String) -> StdResult<BalanceRespo
     Ok(BalanceResponse { balance: claimable })
 }
 
-pub fn query_config(deps: Deps) -> StdResult<ConfigResponse> {
+pub fn query_config(deps: Deps<ArchwayQuery>) -> StdResult<ConfigResponse> {
     let config = CONFIG.load(deps.storage)?;
 
     let res = ConfigResponse {
-        owner: config.owner.to_string(),
+        owner: config.owner.map(String::from),
         bond_denom: config.bond_denom,
         liquid_token_addr: config.liquid_token_addr.to_string(),
+        liquid_denom: config.liquid_denom,
         validator: config.validator,
+        paused: PAUSED.load(deps.storage)?,
+        timelock_delay: config.timelock_delay,
+        harvest_bounty: config.harvest_bounty,
+        harvest_interval: config.harvest_interval,
+        last_harvest: LAST_HARVEST.may_load(deps.storage)?.map(|t| t.seconds()),
+        rewards_collector: config.rewards_collector.map(String::from),
+        ticket_addr: config.ticket_addr.map(String::from),
+        queue_batch_size: config.queue_batch_size,
+        deposit_limits: DEPOSIT_LIMITS.load(deps.storage)?,
     };
     Ok(res)
 }
 
-pub fn query_status(deps: Deps, _env: Env) -> StdResult<StatusResponse> {
+pub fn query_status(deps: Deps<ArchwayQuery>, _env: Env) -> StdResult<StatusResponse> {
     let config = CONFIG.load(deps.storage)?;
     let supply = TOTAL_SUPPLY.load(deps.storage)?;
 
-    let bonded = get_bonded(&deps.querier, &_env.contract.address).unwrap();
+    let bonded = get_bonded(&deps.querier, &_env.contract.address)
+        .map_err(|err| StdError::generic_err(err.to_string()))?;
     let balance = deps
         .querier
         .query_balance(&_env.contract.address, &config.bond_denom)?;
-    let liquid_supply = get_token_supply(&deps.querier, config.liquid_token_addr)?;
+    let ledger = LEDGER.load(deps.storage)?;
+    let limits = DEPOSIT_LIMITS.load(deps.storage)?;
+    let liquid_supply = get_liquid_supply(deps.storage, &deps.querier, &config)?;
 
     let res = StatusResponse {
         issued: liquid_supply,
         native: coin(supply.native.u128(), &config.bond_denom),
         unstakings: supply.unstakings,
         claims: supply.claims,
-        bonded: bonded,
+        bonded,
         balance: balance.amount,
-        ratio: if liquid_supply.is_zero() {
-            FALLBACK_RATIO
-        } else {
-            Decimal::from_ratio(supply.native, liquid_supply)
-        },
+        accounted_balance: ledger.balance,
+        unbonding: ledger.unbonding,
+        remaining_capacity: limits.deposit_cap.map(|cap| cap.saturating_sub(supply.native)),
+        ratio: get_ratio(supply.native, liquid_supply),
+    };
+    Ok(res)
+}
+
+pub fn query_solvency(deps: Deps<ArchwayQuery>, env: Env) -> StdResult<SolvencyResponse> {
+    let config = CONFIG.load(deps.storage)?;
+    let supply = TOTAL_SUPPLY.load(deps.storage)?;
+    let mut ledger = LEDGER.load(deps.storage)?;
+
+    let bonded = get_bonded(&deps.querier, &env.contract.address)
+        .map_err(|err| StdError::generic_err(err.to_string()))?;
+    let balance = deps
+        .querier
+        .query_balance(&env.contract.address, &config.bond_denom)?
+        .amount;
+    // unbondings which already landed in the balance must not be counted twice
+    let matured = get_matured_unbonding(deps.storage, env.block.time)?;
+    reconcile_ledger(&mut ledger, balance, Uint128::zero(), matured);
+    let assets = bonded
+        .checked_add(ledger.unbonding)?
+        .checked_add(balance)?;
+    let liabilities = supply.native
+        .checked_add(supply.unstakings)?
+        .checked_add(supply.claims)?;
+
+    let res = SolvencyResponse {
+        bonded,
+        unbonding: ledger.unbonding,
+        balance,
+        assets,
+        liabilities,
+        surplus: assets.saturating_sub(liabilities),
+        deficit: liabilities.saturating_sub(assets),
+        solvent: assets >= liabilities,
     };
     Ok(res)
 }
 
-pub fn query_unstaking_queue(deps: Deps) -> StdResult<UnstakingQueueResponse> {
-    let state = linked_list_read(deps.storage).load()?;
-    let unstaking_requests: Vec<NodeWithId> = linked_list_get_list(deps.storage, 50)?;
+pub fn query_proposal(deps: Deps<ArchwayQuery>, proposal_id: u64) -> StdResult<ProposalResponse> {
+    let proposal = GOV_PROPOSALS.load(deps.storage, proposal_id)?;
+
+    Ok(ProposalResponse {
+        proposal_id,
+        snapshot_height: proposal.snapshot_height,
+        voting_end: proposal.voting_end.seconds(),
+        tally: proposal.tally,
+        cast_at: proposal.cast_at.map(|time| time.seconds()),
+    })
+}
+
+pub fn query_vote_of(deps: Deps<ArchwayQuery>, proposal_id: u64, address: String) -> StdResult<VoteOfResponse> {
+    let address = deps.api.addr_validate(&address)?;
+    let vote = GOV_VOTES.may_load(deps.storage, (proposal_id, &address))?;
+    Ok(VoteOfResponse { vote })
+}
+
+pub fn query_hooks(deps: Deps<ArchwayQuery>) -> StdResult<HooksResponse> {
+    let hooks = HOOKS
+        .range(deps.storage, None, None, Order::Ascending)
+        .map(|item| {
+            let (address, hook) = item?;
+            Ok(HookInfo { address: address.into_string(), revert_on_error: hook.revert_on_error })
+        })
+        .collect::<StdResult<Vec<_>>>()?;
+    Ok(HooksResponse { hooks })
+}
+
+pub fn query_unstaking_queue(deps: Deps<ArchwayQuery>) -> StdResult<UnstakingQueueResponse> {
+    let state = UNSTAKING_QUEUE.state(deps.storage)?;
+    let unstaking_requests: Vec<NodeWithId> = UNSTAKING_QUEUE
+        .range(deps.storage, None, 50)?
+        .into_iter()
+        .map(|(id, info)| NodeWithId { id, info })
+        .collect();
 
     let res = UnstakingQueueResponse {
         state,
@@ -482,10 +2238,216 @@ pub fn query_unstaking_queue(deps: Deps) -> StdResult<UnstakingQueueResponse> {
     Ok(res)
 }
 
-pub fn query_under_unstaking_of(deps: Deps, address: String) -> StdResult<BalanceResponse> {
+pub fn query_under_unstaking_of(deps: Deps<ArchwayQuery>, address: String) -> StdResult<BalanceResponse> {
     let address = deps.api.addr_validate(&address)?;
     let unstaking = UNDER_UNSTAKING
         .may_load(deps.storage, &address)?
         .unwrap_or_default();
     Ok(BalanceResponse { balance: unstaking })
 }
+
+pub fn query_ratio_history(
+    deps: Deps<ArchwayQuery>,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+) -> StdResult<RatioHistoryResponse> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after.map(Bound::exclusive);
+    let snapshots = RATIO_HISTORY
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|item| item.map(|(id, info)| RatioSnapshotWithId { id, info }))
+        .collect::<StdResult<Vec<_>>>()?;
+    Ok(RatioHistoryResponse { snapshots })
+}
+
+pub fn query_apr(deps: Deps<ArchwayQuery>, env: Env, window_seconds: u64) -> StdResult<AprResponse> {
+    let config = CONFIG.load(deps.storage)?;
+    let supply = TOTAL_SUPPLY.load(deps.storage)?;
+    let liquid_supply = get_liquid_supply(deps.storage, &deps.querier, &config)?;
+    let ratio = get_ratio(supply.native, liquid_supply);
+
+    // start from the latest snapshot taken before the window begins,
+    // or from the oldest one we still have
+    let window_start = env.block.time.seconds().saturating_sub(window_seconds);
+    let mut start: Option<RatioSnapshotWithId> = None;
+    for item in RATIO_HISTORY.range(deps.storage, None, None, Order::Descending) {
+        let (id, info) = item?;
+        let before_window = info.time.seconds() <= window_start;
+        start = Some(RatioSnapshotWithId { id, info });
+        if before_window {
+            break;
+        }
+    }
+
+    let mut apr = Decimal::zero();
+    let mut elapsed_seconds = 0;
+    if let Some(start) = &start {
+        elapsed_seconds = env.block.time.seconds().saturating_sub(start.info.time.seconds());
+        if elapsed_seconds > 0 && ratio > start.info.ratio && !start.info.ratio.is_zero() {
+            let growth = (ratio - start.info.ratio) / start.info.ratio;
+            apr = growth
+                .checked_mul(Decimal::from_ratio(SECONDS_PER_YEAR, elapsed_seconds))
+                .map_err(StdError::overflow)?;
+        }
+    }
+
+    Ok(AprResponse {
+        apr,
+        start,
+        ratio,
+        elapsed_seconds,
+    })
+}
+
+pub fn query_pending_owner(deps: Deps<ArchwayQuery>) -> StdResult<PendingOwnerResponse> {
+    let pending = PENDING_OWNER.may_load(deps.storage)?;
+    Ok(PendingOwnerResponse {
+        owner: pending.as_ref().map(|p| p.owner.to_string()),
+        expiry: pending.and_then(|p| p.expiry).map(|e| e.seconds()),
+    })
+}
+
+pub fn query_pending_changes(
+    deps: Deps<ArchwayQuery>,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+) -> StdResult<PendingChangesResponse> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after.map(Bound::exclusive);
+    let changes = PENDING_CHANGES
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|item| item.map(|(id, pending)| PendingChangeWithId {
+            id,
+            change: pending.change,
+            eta: pending.eta.seconds(),
+        }))
+        .collect::<StdResult<Vec<_>>>()?;
+    Ok(PendingChangesResponse { changes })
+}
+
+pub fn query_role_members(
+    deps: Deps<ArchwayQuery>,
+    role: Role,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<RoleMembersResponse> {
+    if role == Role::Owner {
+        let config = CONFIG.load(deps.storage)?;
+        return Ok(RoleMembersResponse { members: config.owner.into_iter().map(String::from).collect() });
+    }
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start_after = start_after.map(|addr| deps.api.addr_validate(&addr)).transpose()?;
+    let start = start_after.as_ref().map(Bound::exclusive);
+    let members = ROLES
+        .prefix(role.as_str())
+        .keys(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|item| item.map(String::from))
+        .collect::<StdResult<Vec<_>>>()?;
+    Ok(RoleMembersResponse { members })
+}
+
+pub fn query_has_role(deps: Deps<ArchwayQuery>, role: Role, address: String) -> StdResult<HasRoleResponse> {
+    let address = deps.api.addr_validate(&address)?;
+    Ok(HasRoleResponse { has_role: has_role(deps.storage, &address, role)? })
+}
+
+pub fn query_request_status(deps: Deps<ArchwayQuery>, id: u64) -> StdResult<RequestStatusResponse> {
+    let unstake_request = UNSTAKE_REQUESTS.load(deps.storage, id)?;
+    let holder = get_request_holder(&deps.querier, &unstake_request, id)?;
+    let unfilled = UNSTAKING_QUEUE
+        .may_load(deps.storage, id)?
+        .map(|request| request.value)
+        .unwrap_or_default();
+
+    Ok(RequestStatusResponse {
+        receiver: unstake_request.receiver.to_string(),
+        holder,
+        ticket: unstake_request.ticket.map(String::from),
+        amount: unstake_request.amount,
+        height: unstake_request.height,
+        unfilled,
+        filled: unstake_request.filled,
+        cancelled: unstake_request.cancelled,
+        claimed: unstake_request.claimed,
+        claimable: unstake_request.filled.checked_sub(unstake_request.claimed)?,
+    })
+}
+
+pub fn query_ticket(deps: Deps<ArchwayQuery>, id: u64) -> StdResult<TicketResponse> {
+    let unstake_request = UNSTAKE_REQUESTS.load(deps.storage, id)?;
+    let contract = unstake_request
+        .ticket
+        .clone()
+        .ok_or_else(|| StdError::generic_err(ContractError::TicketNotFound { id }.to_string()))?;
+    let owner = get_request_holder(&deps.querier, &unstake_request, id)?;
+    let unfilled = UNSTAKING_QUEUE
+        .may_load(deps.storage, id)?
+        .map(|request| request.value)
+        .unwrap_or_default();
+
+    Ok(TicketResponse {
+        contract: contract.to_string(),
+        owner,
+        amount: unstake_request.amount,
+        claimable: unstake_request.filled.checked_sub(unstake_request.claimed)?,
+        unfilled,
+    })
+}
+
+pub fn query_verify_queue(deps: Deps<ArchwayQuery>, limit: Option<u32>) -> StdResult<VerifyQueueResponse> {
+    let limit = limit.unwrap_or(DEFAULT_VERIFY_LIMIT) as usize;
+    let report = UNSTAKING_QUEUE.verify(deps.storage, limit)?;
+    let mut problems = report.problems;
+    let mut complete = report.complete;
+
+    // totals only add up over the whole queue
+    if report.complete {
+        let mut by_receiver: BTreeMap<Addr, Uint128> = BTreeMap::new();
+        for (_, node) in report.entries.iter() {
+            *by_receiver.entry(node.receiver.clone()).or_default() += node.value;
+        }
+        let total = by_receiver.values().fold(Uint128::zero(), |acc, value| acc + *value);
+        let supply = TOTAL_SUPPLY.load(deps.storage)?;
+        if total != supply.unstakings {
+            problems.push(format!("queue holds {} but supply unstakings is {}", total, supply.unstakings));
+        }
+        // paid out addresses keep a zero entry, they don't count against the limit
+        let mut checked = 0;
+        for item in UNDER_UNSTAKING.range(deps.storage, None, None, Order::Ascending) {
+            let (address, unstaking) = item?;
+            if unstaking.is_zero() {
+                continue;
+            }
+            if checked == limit {
+                complete = false;
+                break;
+            }
+            checked += 1;
+            let queued = by_receiver.remove(&address).unwrap_or_default();
+            if queued != unstaking {
+                problems.push(format!("{} has {} under unstaking but {} in the queue", address, unstaking, queued));
+            }
+        }
+        // receivers left over are only missing once every address under unstaking was checked
+        if complete {
+            for (address, queued) in by_receiver {
+                problems.push(format!("{} has {} in the queue but nothing under unstaking", address, queued));
+            }
+        }
+    }
+
+    Ok(VerifyQueueResponse {
+        walked: report.entries.len() as u64,
+        complete,
+        problems,
+    })
+}
+
+pub fn query_archway_rewards(deps: Deps<ArchwayQuery>, env: Env) -> StdResult<ArchwayRewardsResponse> {
+    let config = CONFIG.load(deps.storage)?;
+    let (records, rewards) = get_archway_rewards(&deps.querier, &env.contract.address, &config.bond_denom)?;
+    Ok(ArchwayRewardsResponse { records, rewards })
+}
@@ -1,4 +1,4 @@
-use cosmwasm_std::StdError;
+use cosmwasm_std::{Decimal, StdError, Uint128};
 use thiserror::Error;
 
 #[derive(Error, Debug)]
@@ -14,12 +14,108 @@ pub enum ContractError {
     #[error("No {denom} tokens sent")]
     EmptyBalance { denom: String },
 
+    #[error("Stake must be at least {min}")]
+    StakeBelowMinimum { min: Uint128 },
+
+    #[error("Deposit cap of {cap} reached, only {remaining} can still be staked")]
+    DepositCapExceeded { cap: Uint128, remaining: Uint128 },
+
+    #[error("Address cap of {cap} reached, only {remaining} can still be staked by this address")]
+    AddressCapExceeded { cap: Uint128, remaining: Uint128 },
+
+    #[error("First stake must be at least {min}")]
+    InsufficientInitialDeposit { min: Uint128 },
+
+    #[error("Deposit is too small to mint any shares")]
+    ZeroShares {},
+
+    #[error("Pool holds no native tokens for its {issued} shares")]
+    DrainedPool { issued: Uint128 },
+
+    #[error("Liquid token is already set")]
+    LiquidTokenAlreadySet {},
+
+    #[error("Liquid token is a cw20 contract, unstake by sending it")]
+    NoLiquidDenom {},
+
+    #[error("Unstake takes a single {denom} coin")]
+    InvalidUnstakeFunds { denom: String },
+
+    #[error("Voting is not supported with a TokenFactory liquid denom")]
+    VotingUnsupported {},
+
     #[error("Different denominations in bonds: '{denom1}' vs. '{denom2}'")]
     DifferentBondDenom { denom1: String, denom2: String },
 
+    #[error("No pending unstaking request {id} for this address")]
+    UnstakeRequestNotFound { id: u64 },
+
+    #[error("No ticket {id}")]
+    TicketNotFound { id: u64 },
+
+    #[error("Cancel amount must be between 1 and {available}")]
+    InvalidCancelAmount { available: Uint128 },
+
     #[error("No claims that can be released currently")]
     NothingToClaim {},
 
+    #[error("Owner role can only be transferred with ProposeNewOwner")]
+    OwnerRoleNotGrantable {},
+
+    #[error("No ownership proposal to accept or cancel")]
+    NoPendingOwner {},
+
+    #[error("Ownership proposal has expired")]
+    OwnershipProposalExpired {},
+
+    #[error("No pending change with id {id}")]
+    PendingChangeNotFound { id: u64 },
+
+    #[error("Change {id} can't be executed before {eta}")]
+    TimelockNotExpired { id: u64, eta: u64 },
+
+    #[error("Rewards must be sent in a single {denom} coin")]
+    InvalidRewards { denom: String },
+
+    #[error("No unaccounted {denom} tokens to sweep")]
+    NothingToSweep { denom: String },
+
+    #[error("Queue batch size must be between 1 and {max}")]
+    InvalidQueueBatchSize { max: u32 },
+
+    #[error("Harvest bounty can't be more than {max}")]
+    InvalidHarvestBounty { max: Decimal },
+
+    #[error("Harvest is not available before {next}")]
+    HarvestTooSoon { next: u64 },
+
+    #[error("Proposal {proposal_id} is already registered")]
+    ProposalAlreadyRegistered { proposal_id: u64 },
+
+    #[error("Proposal {proposal_id} is not registered")]
+    ProposalNotFound { proposal_id: u64 },
+
+    #[error("Voting period of proposal {proposal_id} has ended")]
+    VotingEnded { proposal_id: u64 },
+
+    #[error("No liquid tokens held at height {height}")]
+    NoVotingPower { height: u64 },
+
+    #[error("No votes registered on proposal {proposal_id}")]
+    NoVotes { proposal_id: u64 },
+
+    #[error("Hook {address} is already registered")]
+    HookAlreadyRegistered { address: String },
+
+    #[error("Hook {address} is not registered")]
+    HookNotRegistered { address: String },
+
+    #[error("No more than {max} hooks can be registered")]
+    TooManyHooks { max: u32 },
+
+    #[error("Operation {operation} is paused")]
+    Paused { operation: String },
+
     // #[error("Balance should be zero but: '{balance}'")]
     // BalanceShouldBeZero { balance: String },
 }
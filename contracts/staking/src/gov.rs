@@ -0,0 +1,11 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// SnapshotQueryMsg is the balance snapshot query of the liquid token, used to weight votes
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum SnapshotQueryMsg {
+    /// BalanceAt shows the balance of the address at the start of the block `height`,
+    /// returns cw20::BalanceResponse
+    BalanceAt { address: String, height: u64 },
+}
@@ -0,0 +1,28 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use cosmwasm_std::{to_json_binary, CosmosMsg, CustomMsg, StdResult, Uint128, WasmMsg};
+
+/// HookExecuteMsg is the execute message sent to the registered hook contracts
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum HookExecuteMsg {
+    /// StakeHook is sent when `address` stakes `amount` native tokens and receives `minted` liquid tokens
+    StakeHook { address: String, amount: Uint128, minted: Uint128 },
+    /// UnstakeHook is sent when `address` unstakes `amount` liquid tokens worth `native_amount`
+    /// native tokens, queued as request `request_id`
+    UnstakeHook { address: String, amount: Uint128, native_amount: Uint128, request_id: u64 },
+    /// ClaimHook is sent when `address` claims `amount` native tokens
+    ClaimHook { address: String, amount: Uint128 },
+}
+
+impl HookExecuteMsg {
+    pub fn into_cosmos_msg<T: Into<String>, C: CustomMsg>(self, contract_addr: T) -> StdResult<CosmosMsg<C>> {
+        Ok(WasmMsg::Execute {
+            contract_addr: contract_addr.into(),
+            msg: to_json_binary(&self)?,
+            funds: vec![],
+        }
+        .into())
+    }
+}
@@ -1,7 +1,16 @@
+pub mod archway;
+pub mod collector;
 pub mod contract;
-pub mod linked_list;
 mod error;
+pub mod gov;
+pub mod hooks;
 pub mod msg;
+pub mod proto;
 pub mod state;
+pub mod ticket;
+pub mod tokenfactory;
+
+#[cfg(test)]
+mod testing;
 
 pub use crate::error::ContractError;
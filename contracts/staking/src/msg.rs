@@ -1,10 +1,14 @@
+use std::fmt;
+
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
-use cosmwasm_std::{Addr, Uint128, Decimal, Coin};
+use cosmwasm_std::{Addr, Uint128, Decimal, Coin, VoteOption};
 use cw20::{Cw20ReceiveMsg};
 
-use crate::linked_list::{NodeWithId, LinkedList};
+use deque::DequeState;
+
+use crate::state::{AdminChange, DepositLimits, GovVote, Node, PausedInfo, RatioSnapshot, VoteTally};
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct InstantiateMsg {
@@ -19,19 +23,144 @@ pub struct InstantiateMsg {
 pub enum ExecuteMsg {
     /// Stake will stake and delegate all native tokens sent with the message and give back stkTokens
     Stake {},
-    /// Claim is used to claim the amount of available native tokens that you previously "unstaked" 
+    /// Unstake is called along with a single coin of the TokenFactory liquid denom to unstake it, the cw20
+    /// liquid token is unstaked by sending it instead
+    Unstake {},
+    /// Claim is used to claim the amount of available native tokens that you previously "unstaked",
+    /// the requests behind it are marked claimed a batch at a time and the rest is paid with the last batch
     Claim {},
-    /// Admin call this method to set up liquid token address 
+    /// CancelUnstake takes back all (or `amount`) of an unstaking request that is not filled yet
+    /// and gives back stkTokens at the current ratio. It counts as a stake for pausing and deposit
+    /// limits, undelegations the queue no longer needs are cancelled
+    CancelUnstake { request_id: u64, amount: Option<Uint128> },
+    /// ClaimRequests claims the filled part of the given unstaking requests, ticketed requests
+    /// are paid to the ticket holder and the ticket stays as a receipt
+    ClaimRequests { ids: Vec<u64> },
+    /// ClaimTicket pays the filled part of a ticketed unstaking request to the ticket holder,
+    /// the ticket stays as a receipt once the request is fully paid
+    ClaimTicket { id: u64 },
+    /// Harvest compounds staking rewards, anyone can call it and receives a share of the rewards
+    Harvest {},
+    /// ProcessQueue pays up to `limit` unstaking requests with the available native tokens,
+    /// resuming where the last call stopped, anyone can call it
+    ProcessQueue { limit: Option<u32> },
+    /// Vote registers the vote of the sender on a registered proposal, weighted by its liquid token
+    /// balance at the proposal snapshot height, a new vote replaces the previous one. The tally is
+    /// cast to the gov module on every vote. Only the cw20 liquid token keeps balance snapshots, voting
+    /// fails with a TokenFactory liquid denom
+    Vote { proposal_id: u64, vote: VoteOption },
+    /// Operator call this method to open voting on a chain governance proposal, the voting power is
+    /// read at the current height. `voting_end` is the end of the chain voting period (block time in seconds)
+    RegisterProposal { proposal_id: u64, voting_end: u64 },
+    /// Operator call this method to send the tally of a proposal to the gov module as a weighted vote,
+    /// votes already cast it, it can be sent again until the voting period ends
+    CastVote { proposal_id: u64 },
+    /// DepositRewards is called by the rewards collector with the delegator rewards it received,
+    /// the harvest bounty is paid out of them when they were withdrawn by a Harvest
+    DepositRewards {},
+    /// Admin call this method to queue a new liquid token address (applied at once the first time)
     SetLiquidToken { address: Addr },
+    /// Admin call this method to use the TokenFactory denom `factory/{contract}/{subdenom}` as liquid
+    /// token instead of a cw20 contract, only before any liquid token is set
+    CreateLiquidDenom { subdenom: String },
+    /// Admin call this method to queue a new timelock delay (in seconds)
+    SetTimelockDelay { delay: u64 },
+    /// Admin call this method to queue a new harvest bounty and minimum interval (in seconds)
+    SetHarvestConfig { bounty: Decimal, interval: u64 },
+    /// Admin call this method to queue an update of the Archway contract metadata
+    SetContractMetadata { owner_address: Option<String>, rewards_address: Option<String> },
+    /// Admin call this method to queue a new rewards collector, none withdraws rewards to this contract
+    SetRewardsCollector { address: Option<String> },
+    /// Admin call this method to queue a new cw721 contract for claim tickets, none stops minting tickets
+    SetTicketContract { address: Option<String> },
+    /// Admin call this method to queue a contract notified on stake, unstake and claim, a failing hook
+    /// reverts a stake or unstake if `revert_on_error` is set and is ignored otherwise. Failing hooks
+    /// never revert a claim, and ignored hooks run with a gas limit
+    AddHook { address: String, revert_on_error: bool },
+    /// Admin call this method to stop notifying a contract
+    RemoveHook { address: String },
+    /// Admin call this method to relink the unstaking queue from the stored requests, in id order
+    RepairQueue {},
+    /// Operator call this method to queue how many unstaking requests are paid in a single call,
+    /// the owner executes the change once the timelock has passed
+    SetQueueBatchSize { size: u32 },
+    /// Admin call this method to set the total and per-address deposit caps and the minimum stake
+    /// behind the timelock, a cap of none means no limit
+    SetDepositLimits { deposit_cap: Option<Uint128>, address_cap: Option<Uint128>, min_stake: Uint128 },
+    /// Admin call this method to send native tokens the ledger doesn't account for (sent directly
+    /// to the contract) to the recipient, or to the sender if none is given. Delegator rewards
+    /// withdrawn by a delegation change are booked when it is sent, so they are never swept
+    SweepUnaccounted { recipient: Option<String> },
+    /// Admin call this method to apply a queued change once its timelock has passed
+    ExecutePendingChange { id: u64 },
+    /// Admin or guardian call this method to drop a queued change
+    CancelPendingChange { id: u64 },
+    /// Admin call this method to propose a new admin, who has to accept before `expiry` (block time in seconds)
+    ProposeNewOwner { owner: String, expiry: Option<u64> },
+    /// Proposed admin call this method to take over the admin role
+    AcceptOwnership {},
+    /// Admin call this method to drop the pending admin proposal
+    CancelOwnershipProposal {},
+    /// Admin call this method to give up the admin role for good, the guardian can still unpause
+    RenounceOwnership {},
+    /// Admin call this method to give an address the operator or guardian role
+    GrantRole { role: Role, address: String },
+    /// Admin call this method to take the operator or guardian role from an address
+    RevokeRole { role: Role, address: String },
+    /// Guardian call this method to pause one operation, or all of them if none is given
+    Pause { operation: Option<Operation> },
+    /// Admin call this method to unpause one operation, or all of them if none is given,
+    /// guardian call it once ownership is renounced
+    Unpause { operation: Option<Operation> },
 
     /// This accepts a properly-encoded ReceiveMsg from a cw20 contract (to process unstake request)
     Receive(Cw20ReceiveMsg),
 
-    _ProcessToken { balance_before: Uint128 },
-    _PerformCheck {},
+    _ProcessToken { rewards: Uint128, harvester: Option<Addr> },
+    _PerformCheck { harvester: Option<Addr> },
     _MintLiquidToken { receiver: Addr, native_amount: Uint128 },
 }
 
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum Operation {
+    Stake,
+    Unstake,
+    Claim,
+}
+
+impl fmt::Display for Operation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Operation::Stake => write!(f, "stake"),
+            Operation::Unstake => write!(f, "unstake"),
+            Operation::Claim => write!(f, "claim"),
+        }
+    }
+}
+
+/// Role is a set of privileges, the owner holds every role
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum Role {
+    /// Owner manages economics and roles
+    Owner,
+    /// Operator runs keeper tasks
+    Operator,
+    /// Guardian can pause operations in an emergency
+    Guardian,
+}
+
+impl Role {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Role::Owner => "owner",
+            Role::Operator => "operator",
+            Role::Guardian => "guardian",
+        }
+    }
+}
+
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum QueryMsg {
@@ -39,25 +168,73 @@ pub enum QueryMsg {
     ClaimableOf { address: String },
     /// ConfigInfo shows the config of the contract
     ConfigInfo {},
+    /// PendingOwner shows the admin proposed by the current admin, if any
+    PendingOwner {},
+    /// RoleMembers shows the addresses holding a role
+    RoleMembers { role: Role, start_after: Option<String>, limit: Option<u32> },
+    /// HasRole shows whether the address holds a role
+    HasRole { role: Role, address: String },
+    /// PendingChanges shows the queued admin changes waiting for their timelock
+    PendingChanges { start_after: Option<u64>, limit: Option<u32> },
     /// StatusInfo shows staking info of the contract
     StatusInfo {},
     /// UnstakingQueue shows first 50 nodes in the unstaking queue of the contract
     UnstakingQueue {},
     /// UnderUnstaking shows the total number of native tokens this address is waiting to be unstaked
     UnderUnstakingOf { address: String },
+    /// RatioHistory shows the ratio snapshots recorded on each reward harvest, oldest first
+    RatioHistory { start_after: Option<u64>, limit: Option<u32> },
+    /// Apr shows the annualized ratio growth over the last `window_seconds`
+    Apr { window_seconds: u64 },
+    /// ArchwayRewards shows the Archway contract rewards waiting to be compounded
+    ArchwayRewards {},
+    /// RequestStatus shows the claim state of an unstaking request
+    RequestStatus { id: u64 },
+    /// Ticket shows the claim ticket of an unstaking request
+    Ticket { id: u64 },
+    /// VerifyQueue walks up to `limit` requests of the unstaking queue and reports inconsistencies
+    VerifyQueue { limit: Option<u32> },
+    /// Solvency compares the native tokens the contract holds with the native tokens it owes
+    Solvency {},
+    /// Proposal shows the tally of a registered governance proposal
+    Proposal { proposal_id: u64 },
+    /// VoteOf shows the vote of the address on a registered governance proposal
+    VoteOf { proposal_id: u64, address: String },
+    /// Hooks shows the contracts notified on stake, unstake and claim
+    Hooks {},
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct ConfigResponse {
-    /// Admin to change config
-    pub owner: String,
+    /// Admin to change config, none once ownership is renounced
+    pub owner: Option<String>,
     /// This is the denomination we can stake (and only one we accept for payments)
     pub bond_denom: String,
     /// Liquid token address
     pub liquid_token_addr: String,
+    /// TokenFactory denom used as liquid token, if any
+    pub liquid_denom: Option<String>,
     /// All tokens are bonded to this validator
     /// FIXME: address validation doesn't work for validator addresses
     pub validator: String,
+    /// Operations currently paused
+    pub paused: PausedInfo,
+    /// Delay in seconds before a queued admin change can be executed
+    pub timelock_delay: u64,
+    /// Share of the harvested rewards paid to whoever calls Harvest
+    pub harvest_bounty: Decimal,
+    /// Minimum number of seconds between two Harvest calls
+    pub harvest_interval: u64,
+    /// Block time in seconds of the last Harvest, none if it was never called
+    pub last_harvest: Option<u64>,
+    /// Contract receiving the delegator rewards, none if rewards are withdrawn to this contract
+    pub rewards_collector: Option<String>,
+    /// cw721 contract minting claim tickets, none if requests are claimed by the unstaker
+    pub ticket_addr: Option<String>,
+    /// Maximum number of unstaking requests paid in a single call
+    pub queue_batch_size: u32,
+    /// Deposit caps and minimum stake
+    pub deposit_limits: DepositLimits,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -74,12 +251,186 @@ pub struct StatusResponse {
     pub bonded: Uint128,
     /// available native token balance of this contract
     pub balance: Uint128,
+    /// native token balance of this contract accounted for in the ledger
+    pub accounted_balance: Uint128,
+    /// native tokens undelegated and not yet returned to this contract
+    pub unbonding: Uint128,
+    /// native tokens that can still be staked before the deposit cap, none if there is no cap
+    pub remaining_capacity: Option<Uint128>,
     /// ratio of native / issued (or how many native tokens that one derivative token is nominally worth)
     pub ratio: Decimal,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct UnstakingQueueResponse {
-    pub state: LinkedList,
+    pub state: DequeState,
     pub queue: Vec<NodeWithId>,
-}
\ No newline at end of file
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct NodeWithId {
+    pub id: u64,
+    pub info: Node,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct RatioSnapshotWithId {
+    pub id: u64,
+    pub info: RatioSnapshot,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct RatioHistoryResponse {
+    pub snapshots: Vec<RatioSnapshotWithId>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct AprResponse {
+    /// annualized growth of the ratio over the window (zero if the ratio did not grow)
+    pub apr: Decimal,
+    /// snapshot the window starts from, none if no harvest was recorded yet
+    pub start: Option<RatioSnapshotWithId>,
+    /// current ratio of native / issued
+    pub ratio: Decimal,
+    /// number of seconds actually covered, may be shorter than requested if history is short
+    pub elapsed_seconds: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PendingOwnerResponse {
+    /// proposed admin, none if there is no pending proposal
+    pub owner: Option<String>,
+    /// block time in seconds after which the proposal can no longer be accepted
+    pub expiry: Option<u64>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PendingChangeWithId {
+    pub id: u64,
+    pub change: AdminChange,
+    /// block time in seconds from which the change can be executed
+    pub eta: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PendingChangesResponse {
+    pub changes: Vec<PendingChangeWithId>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct RoleMembersResponse {
+    pub members: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct HasRoleResponse {
+    pub has_role: bool,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ArchwayRewardsResponse {
+    /// number of rewards records waiting to be withdrawn
+    pub records: u64,
+    /// native tokens held by those records
+    pub rewards: Uint128,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct RequestStatusResponse {
+    /// address that unstaked
+    pub receiver: String,
+    /// address the request is paid to, the ticket holder if any
+    pub holder: String,
+    /// cw721 contract of the claim ticket, if any
+    pub ticket: Option<String>,
+    /// native tokens requested
+    pub amount: Uint128,
+    /// block height of the unstake
+    pub height: u64,
+    /// native tokens still waiting in the unstaking queue
+    pub unfilled: Uint128,
+    /// native tokens paid out of the queue into the request
+    pub filled: Uint128,
+    /// native tokens taken back with CancelUnstake
+    pub cancelled: Uint128,
+    /// native tokens claimed
+    pub claimed: Uint128,
+    /// native tokens filled and not claimed yet
+    pub claimable: Uint128,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct VerifyQueueResponse {
+    /// number of queue nodes walked
+    pub walked: u64,
+    /// whether the whole queue and every address under unstaking were walked within the limit,
+    /// totals are only checked over the whole queue
+    pub complete: bool,
+    /// problems found, empty if the queue is consistent
+    pub problems: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct SolvencyResponse {
+    /// native tokens bonded to validators
+    pub bonded: Uint128,
+    /// native tokens undelegated and not yet returned to this contract
+    pub unbonding: Uint128,
+    /// native token balance of this contract
+    pub balance: Uint128,
+    /// assets is bonded + unbonding + balance
+    pub assets: Uint128,
+    /// liabilities is native + unstakings + claims
+    pub liabilities: Uint128,
+    /// assets over liabilities, zero if there is a deficit
+    pub surplus: Uint128,
+    /// liabilities over assets, zero if there is a surplus
+    pub deficit: Uint128,
+    /// whether assets cover liabilities
+    pub solvent: bool,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ProposalResponse {
+    pub proposal_id: u64,
+    /// block height the voting power is read at
+    pub snapshot_height: u64,
+    /// block time in seconds the chain voting period ends
+    pub voting_end: u64,
+    pub tally: VoteTally,
+    /// block time in seconds of the last vote sent to the gov module, none if it was never sent
+    pub cast_at: Option<u64>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct VoteOfResponse {
+    /// vote of the address, none if it didn't vote
+    pub vote: Option<GovVote>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct HookInfo {
+    pub address: String,
+    /// whether a failing hook reverts a stake or unstake, or is ignored. Claims ignore failing hooks
+    /// whatever this is set to
+    pub revert_on_error: bool,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct HooksResponse {
+    pub hooks: Vec<HookInfo>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct TicketResponse {
+    /// cw721 contract the ticket was minted on
+    pub contract: String,
+    /// current holder of the ticket
+    pub owner: String,
+    /// native tokens requested
+    pub amount: Uint128,
+    /// native tokens filled and not claimed yet
+    pub claimable: Uint128,
+    /// native tokens still waiting in the unstaking queue
+    pub unfilled: Uint128,
+}
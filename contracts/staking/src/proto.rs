@@ -0,0 +1,87 @@
+use cosmwasm_std::{Binary, Coin, CosmosMsg};
+
+/// Type url of the staking module message cancelling an unbonding delegation
+pub const CANCEL_UNBONDING_DELEGATION_TYPE_URL: &str = "/cosmos.staking.v1beta1.MsgCancelUnbondingDelegation";
+
+const WIRE_VARINT: u64 = 0;
+const WIRE_LEN: u64 = 2;
+
+/// ProtoWriter encodes protobuf messages field by field, fields at their default value are skipped
+#[derive(Default)]
+pub struct ProtoWriter {
+    buf: Vec<u8>,
+}
+
+impl ProtoWriter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn varint(&mut self, mut value: u64) {
+        while value >= 0x80 {
+            self.buf.push((value as u8 & 0x7f) | 0x80);
+            value >>= 7;
+        }
+        self.buf.push(value as u8);
+    }
+
+    fn key(&mut self, field: u64, wire_type: u64) {
+        self.varint(field << 3 | wire_type);
+    }
+
+    pub fn uint(mut self, field: u64, value: u64) -> Self {
+        if value != 0 {
+            self.key(field, WIRE_VARINT);
+            self.varint(value);
+        }
+        self
+    }
+
+    pub fn bytes(mut self, field: u64, value: &[u8]) -> Self {
+        if !value.is_empty() {
+            self.key(field, WIRE_LEN);
+            self.varint(value.len() as u64);
+            self.buf.extend_from_slice(value);
+        }
+        self
+    }
+
+    pub fn string(self, field: u64, value: &str) -> Self {
+        self.bytes(field, value.as_bytes())
+    }
+
+    /// message encodes an embedded message, it is always written
+    pub fn message(mut self, field: u64, value: ProtoWriter) -> Self {
+        self.key(field, WIRE_LEN);
+        self.varint(value.buf.len() as u64);
+        self.buf.extend_from_slice(&value.buf);
+        self
+    }
+
+    /// coin encodes a cosmos.base.v1beta1.Coin
+    pub fn coin(self, field: u64, coin: &Coin) -> Self {
+        let value = ProtoWriter::new().string(1, &coin.denom).string(2, &coin.amount.to_string());
+        self.message(field, value)
+    }
+
+    pub fn into_binary(self) -> Binary {
+        Binary(self.buf)
+    }
+}
+
+/// cancel_unbonding_delegation returns the message delegating again the tokens of the unbonding
+/// delegation created at `creation_height`
+pub fn cancel_unbonding_delegation<T>(
+    delegator: &str,
+    validator: &str,
+    amount: &Coin,
+    creation_height: u64,
+) -> CosmosMsg<T> {
+    let value = ProtoWriter::new()
+        .string(1, delegator)
+        .string(2, validator)
+        .coin(3, amount)
+        .uint(4, creation_height)
+        .into_binary();
+    CosmosMsg::Stargate { type_url: CANCEL_UNBONDING_DELEGATION_TYPE_URL.to_string(), value }
+}
@@ -1,20 +1,58 @@
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
-use cosmwasm_std::{Addr, Uint128};
+use cosmwasm_std::{Addr, Decimal, Timestamp, Uint128, VoteOption};
+use cosmwasm_std::Empty;
 use cw_storage_plus::{Item, Map};
+use deque::Deque;
+
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct ConfigInfo {
-    /// Admin to change config
-    pub owner: Addr,
+    /// Admin to change config, none once ownership is renounced
+    pub owner: Option<Addr>,
     /// This is the denomination we can stake (and only one we accept for payments)
     pub bond_denom: String,
     /// Liquid token address
     pub liquid_token_addr: Addr,
+    /// TokenFactory denom used as liquid token instead of the cw20 contract, if any
+    pub liquid_denom: Option<String>,
     /// All tokens are bonded to this validator
     /// FIXME: address validation doesn't work for validator addresses
     pub validator: String,
+    /// Delay in seconds before a queued admin change can be executed
+    pub timelock_delay: u64,
+    /// Share of the harvested rewards paid to whoever calls Harvest
+    pub harvest_bounty: Decimal,
+    /// Minimum number of seconds between two Harvest calls
+    pub harvest_interval: u64,
+    /// Contract receiving the delegator rewards and forwarding them with DepositRewards,
+    /// none if rewards are withdrawn to this contract
+    pub rewards_collector: Option<Addr>,
+    /// cw721 contract minting a claim ticket for each unstaking request, none if requests
+    /// are claimed by the unstaker
+    pub ticket_addr: Option<Addr>,
+    /// Maximum number of unstaking requests paid in a single call
+    pub queue_batch_size: u32,
+}
+
+/// PausedInfo tracks which user operations are halted
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema, Default)]
+pub struct PausedInfo {
+    pub stake: bool,
+    pub unstake: bool,
+    pub claim: bool,
+}
+
+/// DepositLimits bounds how much can be staked, none means no limit
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema, Default)]
+pub struct DepositLimits {
+    /// highest total supply of native tokens
+    pub deposit_cap: Option<Uint128>,
+    /// highest native amount an address can have staked
+    pub address_cap: Option<Uint128>,
+    /// smallest native amount accepted by a single Stake
+    pub min_stake: Uint128,
 }
 
 /// Supply is dynamic and tracks the current supply of staked and ERC20 tokens.
@@ -28,7 +66,196 @@ pub struct Supply {
     pub claims: Uint128,
 }
 
+/// RatioSnapshot records the exchange ratio right after staking rewards are harvested
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct RatioSnapshot {
+    /// block height of the harvest
+    pub height: u64,
+    /// block time of the harvest
+    pub time: Timestamp,
+    /// ratio of native / issued after the harvest
+    pub ratio: Decimal,
+    /// total supply of native tokens liquid token holders can withdraw
+    pub native: Uint128,
+    /// total supply of liquid tokens
+    pub issued: Uint128,
+}
+
+/// Node is an unstaking request waiting in the queue to be paid
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Node {
+    /// address that unstaked
+    pub receiver: Addr,
+    /// native tokens not paid yet
+    pub value: Uint128,
+    /// block height of the unstake
+    pub height: u64,
+}
+
+/// UnstakeRequest keeps the claim state of an unstaking request, keyed by its node id
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct UnstakeRequest {
+    /// address that unstaked
+    pub receiver: Addr,
+    /// cw721 contract of the claim ticket, the request is paid to the ticket holder if any
+    pub ticket: Option<Addr>,
+    /// native tokens requested
+    pub amount: Uint128,
+    /// block height of the unstake
+    pub height: u64,
+    /// native tokens paid out of the queue into the request
+    pub filled: Uint128,
+    /// native tokens taken back with CancelUnstake
+    pub cancelled: Uint128,
+    /// native tokens claimed
+    pub claimed: Uint128,
+}
+
+/// Estimated unbonding period in seconds, used for the ticket ETA and to tell when undelegated
+/// tokens are back. Tokens coming back later are simply booked later
+pub const UNBONDING_PERIOD: u64 = 21 * 24 * 60 * 60;
+
+/// Maximum number of ratio snapshots kept, older ones are dropped
+pub const RATIO_HISTORY_CAPACITY: u64 = 1000;
+
+/// PendingOwner is the address proposed to take over the admin role
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PendingOwner {
+    pub owner: Addr,
+    /// block time after which the proposal can no longer be accepted
+    pub expiry: Option<Timestamp>,
+}
+
+/// AdminChange is an admin action that only takes effect after the timelock delay
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum AdminChange {
+    SetLiquidToken { address: Addr },
+    SetTimelockDelay { delay: u64 },
+    SetHarvestConfig { bounty: Decimal, interval: u64 },
+    SetContractMetadata { owner_address: Option<String>, rewards_address: Option<String> },
+    SetRewardsCollector { address: Option<Addr> },
+    SetTicketContract { address: Option<Addr> },
+    SetDepositLimits { limits: DepositLimits },
+    SetQueueBatchSize { size: u32 },
+    AddHook { address: Addr, revert_on_error: bool },
+}
+
+/// VoteTally is the liquid token voting power behind each vote option
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema, Default)]
+pub struct VoteTally {
+    pub yes: Uint128,
+    pub abstain: Uint128,
+    pub no: Uint128,
+    pub no_with_veto: Uint128,
+}
+
+/// GovProposal is a chain governance proposal liquid token holders vote on,
+/// this contract votes with its delegation following the tally
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct GovProposal {
+    /// block height the voting power is read at
+    pub snapshot_height: u64,
+    /// block time the chain voting period ends
+    pub voting_end: Timestamp,
+    pub tally: VoteTally,
+    /// block time of the last vote sent to the gov module, none if it was never sent
+    pub cast_at: Option<Timestamp>,
+}
+
+/// GovVote is the vote of a liquid token holder on a proposal
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct GovVote {
+    pub option: VoteOption,
+    /// liquid token balance at the proposal snapshot height
+    pub weight: Uint128,
+}
+
+/// HookConfig is the setting of a registered hook contract
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct HookConfig {
+    /// whether a failing hook reverts a stake or unstake, or is ignored. Claims ignore it
+    pub revert_on_error: bool,
+}
+
+/// PendingChange is an admin change waiting for the timelock to pass
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PendingChange {
+    pub change: AdminChange,
+    /// block time from which the change can be executed
+    pub eta: Timestamp,
+}
+
+/// Default delay in seconds before a queued admin change can be executed
+pub const DEFAULT_TIMELOCK_DELAY: u64 = 24 * 60 * 60;
+/// Default minimum number of seconds between two Harvest calls
+pub const DEFAULT_HARVEST_INTERVAL: u64 = 60 * 60;
+/// Default maximum number of unstaking requests paid in a single call
+pub const DEFAULT_QUEUE_BATCH_SIZE: u32 = 50;
+/// Highest number of unstaking requests that can be paid in a single call
+pub const MAX_QUEUE_BATCH_SIZE: u32 = 200;
+/// Highest number of registered hook contracts
+pub const MAX_HOOKS: u32 = 10;
+/// Gas a hook whose failure is ignored can use, so it can't exhaust the gas of the action
+pub const HOOK_GAS_LIMIT: u64 = 300_000;
+/// Highest share (in percent) of the harvested rewards that can be paid as bounty
+pub const MAX_HARVEST_BOUNTY_PERCENT: u64 = 10;
+/// Smallest native amount accepted for the first stake, when no liquid token exists yet
+pub const MINIMUM_INITIAL_STAKE: u128 = 1_000_000;
+/// Liquid tokens minted to this contract on the first stake and locked for good,
+/// so the share price can't be inflated by a tiny first stake
+pub const LOCKED_LIQUID_TOKENS: u128 = 1_000;
+
+/// NativeLedger tracks the native tokens the contract knows it holds,
+/// so tokens sent directly to the contract don't change the accounting
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema, Default)]
+pub struct NativeLedger {
+    /// native tokens held by the contract and accounted for
+    pub balance: Uint128,
+    /// native tokens undelegated and not yet returned to the contract
+    pub unbonding: Uint128,
+}
+
+/// Unbonding is an undelegation whose tokens are not back in the contract yet
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Unbonding {
+    /// native tokens undelegated and not booked back yet
+    pub amount: Uint128,
+    /// block time the tokens are expected back
+    pub completion: Timestamp,
+    /// block height of the undelegation
+    pub height: u64,
+}
+
 pub const CONFIG: Item<ConfigInfo> = Item::new("config");
+pub const PENDING_OWNER: Item<PendingOwner> = Item::new("pending_owner");
+pub const PENDING_CHANGES: Map<u64, PendingChange> = Map::new("pending_changes");
+pub const PENDING_CHANGES_NEXT_ID: Item<u64> = Item::new("pending_changes_next_id");
+/// ROLES holds the operator and guardian members, keyed by role name then address
+pub const ROLES: Map<(&str, &Addr), Empty> = Map::new("roles");
+pub const PAUSED: Item<PausedInfo> = Item::new("paused");
+pub const DEPOSIT_LIMITS: Item<DepositLimits> = Item::new("deposit_limits");
+pub const LAST_HARVEST: Item<Timestamp> = Item::new("last_harvest");
+/// caller of the harvest whose delegator rewards are on their way from the rewards collector,
+/// paid the bounty by DepositRewards
+pub const PENDING_HARVESTER: Item<Addr> = Item::new("pending_harvester");
+pub const LEDGER: Item<NativeLedger> = Item::new("native_ledger");
+/// UNBONDINGS holds the pending undelegations oldest first, they add up to the ledger unbonding
+pub const UNBONDINGS: Deque<Unbonding> = Deque::new("unbondings", "unbondings_nodes");
 pub const TOTAL_SUPPLY: Item<Supply> = Item::new("total_supply");
+/// supply of the TokenFactory liquid denom, only this contract mints and burns it
+pub const LIQUID_SUPPLY: Item<Uint128> = Item::new("liquid_supply");
 pub const CLAIMABLE: Map<&Addr, Uint128> = Map::new("claimable");
-pub const UNDER_UNSTAKING: Map<&Addr, Uint128> = Map::new("under_unstaking");
\ No newline at end of file
+pub const UNDER_UNSTAKING: Map<&Addr, Uint128> = Map::new("under_unstaking");
+pub const UNSTAKING_QUEUE: Deque<Node> = Deque::new("unstaking_queue", "unstaking_queue_nodes");
+pub const UNSTAKE_REQUESTS: Map<u64, UnstakeRequest> = Map::new("unstake_requests");
+/// requests without a ticket holding filled tokens not claimed yet, by receiver
+pub const UNCLAIMED_REQUESTS: Map<(&Addr, u64), Empty> = Map::new("unclaimed_requests");
+/// native amount staked by an address and not unstaked yet, checked against the address cap
+pub const STAKED: Map<&Addr, Uint128> = Map::new("staked");
+/// HOOKS holds the contracts notified on stake, unstake and claim
+pub const HOOKS: Map<&Addr, HookConfig> = Map::new("hooks");
+pub const GOV_PROPOSALS: Map<u64, GovProposal> = Map::new("gov_proposals");
+pub const GOV_VOTES: Map<(u64, &Addr), GovVote> = Map::new("gov_votes");
+pub const RATIO_HISTORY: Map<u64, RatioSnapshot> = Map::new("ratio_history");
+pub const RATIO_HISTORY_NEXT_ID: Item<u64> = Item::new("ratio_history_next_id");
@@ -0,0 +1,76 @@
+use cosmwasm_std::testing::{mock_env, mock_info, MOCK_CONTRACT_ADDR};
+use cosmwasm_std::{
+    coin, from_json, to_json_binary, ContractResult, CosmosMsg, SystemError, SystemResult, Uint128, WasmMsg,
+};
+
+use crate::archway::{ArchwayMsg, ArchwayQuery, RewardsRecord, RewardsRecordsResponse};
+use crate::contract::{execute, query};
+use crate::msg::{ArchwayRewardsResponse, ExecuteMsg, QueryMsg};
+use crate::testing::mock_querier::{mock_dependencies_with_custom_handler, setup, MockDeps, BOND_DENOM};
+
+fn record(id: u64, amount: u128, denom: &str) -> RewardsRecord {
+    RewardsRecord {
+        id,
+        rewards_address: MOCK_CONTRACT_ADDR.to_string(),
+        rewards: vec![coin(amount, denom)],
+        calculated_height: 100 + id,
+    }
+}
+
+// perform_check runs _PerformCheck and returns its messages and the rewards passed to _ProcessToken
+fn perform_check(deps: &mut MockDeps) -> (Vec<CosmosMsg<ArchwayMsg>>, Uint128) {
+    let msg = ExecuteMsg::_PerformCheck { harvester: None };
+    let res = execute(deps.as_mut(), mock_env(), mock_info(MOCK_CONTRACT_ADDR, &[]), msg).unwrap();
+    let msgs: Vec<CosmosMsg<ArchwayMsg>> = res.messages.into_iter().map(|sub| sub.msg).collect();
+    let rewards = match msgs.last() {
+        Some(CosmosMsg::Wasm(WasmMsg::Execute { msg, .. })) => match from_json(msg).unwrap() {
+            ExecuteMsg::_ProcessToken { rewards, .. } => rewards,
+            other => panic!("unexpected message {:?}", other),
+        },
+        other => panic!("unexpected message {:?}", other),
+    };
+    (msgs, rewards)
+}
+
+#[test]
+fn perform_check_withdraws_archway_rewards() {
+    let mut deps = mock_dependencies_with_custom_handler(|query| match query {
+        ArchwayQuery::RewardsRecords { .. } => {
+            let response = RewardsRecordsResponse {
+                records: vec![record(1, 300, BOND_DENOM), record(2, 200, BOND_DENOM), record(3, 50, "uother")],
+                pagination: None,
+            };
+            SystemResult::Ok(ContractResult::Ok(to_json_binary(&response).unwrap()))
+        },
+        _ => SystemResult::Err(SystemError::UnsupportedRequest { kind: "archway".to_string() }),
+    });
+    setup(&mut deps);
+
+    let (msgs, rewards) = perform_check(&mut deps);
+    assert_eq!(rewards.u128(), 500);
+    assert_eq!(msgs.len(), 2);
+    assert_eq!(msgs[0], CosmosMsg::Custom(ArchwayMsg::WithdrawRewards { records_limit: Some(50), record_ids: vec![] }));
+
+    let res: ArchwayRewardsResponse =
+        from_json(query(deps.as_ref(), mock_env(), QueryMsg::ArchwayRewards {}).unwrap()).unwrap();
+    assert_eq!(res.records, 3);
+    assert_eq!(res.rewards.u128(), 500);
+}
+
+#[test]
+fn perform_check_ignores_failing_rewards_query() {
+    let mut deps = mock_dependencies_with_custom_handler(|_| {
+        SystemResult::Err(SystemError::UnsupportedRequest { kind: "archway".to_string() })
+    });
+    setup(&mut deps);
+
+    let (msgs, rewards) = perform_check(&mut deps);
+    assert!(rewards.is_zero());
+    assert_eq!(msgs.len(), 1);
+
+    let mut deps = mock_dependencies_with_custom_handler(|_| SystemResult::Ok(ContractResult::Err("no module".to_string())));
+    setup(&mut deps);
+    let (msgs, rewards) = perform_check(&mut deps);
+    assert!(rewards.is_zero());
+    assert_eq!(msgs.len(), 1);
+}
@@ -0,0 +1,58 @@
+use cosmwasm_std::testing::{mock_env, mock_info};
+use cosmwasm_std::{Addr, Empty, Uint128};
+
+use crate::contract::execute;
+use crate::msg::ExecuteMsg;
+use crate::state::{
+    NativeLedger, Supply, UnstakeRequest, CLAIMABLE, LEDGER, TOTAL_SUPPLY, UNCLAIMED_REQUESTS, UNSTAKE_REQUESTS,
+};
+use crate::testing::mock_querier::{mock_dependencies, setup, MockDeps};
+use crate::ContractError;
+
+// fill_requests stores `count` requests of alice filled with 10 each, plus `legacy` claimable
+// filled before requests were tracked
+fn fill_requests(deps: &mut MockDeps, count: u64, legacy: u128) {
+    let alice = Addr::unchecked("alice");
+    for id in 1..=count {
+        let unstake_request = UnstakeRequest {
+            receiver: alice.clone(),
+            ticket: None,
+            amount: Uint128::new(10),
+            height: 1,
+            filled: Uint128::new(10),
+            cancelled: Uint128::zero(),
+            claimed: Uint128::zero(),
+        };
+        UNSTAKE_REQUESTS.save(deps.as_mut().storage, id, &unstake_request).unwrap();
+        UNCLAIMED_REQUESTS.save(deps.as_mut().storage, (&alice, id), &Empty {}).unwrap();
+    }
+    let total = Uint128::new(count as u128 * 10 + legacy);
+    CLAIMABLE.save(deps.as_mut().storage, &alice, &total).unwrap();
+    TOTAL_SUPPLY.save(deps.as_mut().storage, &Supply { claims: total, ..Supply::default() }).unwrap();
+    LEDGER.save(deps.as_mut().storage, &NativeLedger { balance: total, ..NativeLedger::default() }).unwrap();
+}
+
+fn claim(deps: &mut MockDeps) -> Result<(String, String), ContractError> {
+    let res = execute(deps.as_mut(), mock_env(), mock_info("alice", &[]), ExecuteMsg::Claim {})?;
+    let attr = |key: &str| res.attributes.iter().find(|attr| attr.key == key).unwrap().value.clone();
+    Ok((attr("amount"), attr("complete")))
+}
+
+#[test]
+fn claim_marks_requests_a_batch_at_a_time() {
+    let mut deps = mock_dependencies();
+    setup(&mut deps);
+    fill_requests(&mut deps, 31, 5);
+
+    assert_eq!(claim(&mut deps).unwrap(), ("300".to_string(), "false".to_string()));
+    assert_eq!(CLAIMABLE.load(&deps.storage, &Addr::unchecked("alice")).unwrap().u128(), 15);
+    assert_eq!(UNSTAKE_REQUESTS.load(&deps.storage, 30).unwrap().claimed.u128(), 10);
+    assert!(UNSTAKE_REQUESTS.load(&deps.storage, 31).unwrap().claimed.is_zero());
+
+    // the last batch pays the rest of the total
+    assert_eq!(claim(&mut deps).unwrap(), ("15".to_string(), "true".to_string()));
+    assert!(CLAIMABLE.load(&deps.storage, &Addr::unchecked("alice")).unwrap().is_zero());
+    assert!(TOTAL_SUPPLY.load(&deps.storage).unwrap().claims.is_zero());
+    assert!(LEDGER.load(&deps.storage).unwrap().balance.is_zero());
+    assert!(matches!(claim(&mut deps).unwrap_err(), ContractError::NothingToClaim {}));
+}
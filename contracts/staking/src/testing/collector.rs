@@ -0,0 +1,86 @@
+use cosmwasm_std::testing::{mock_env, mock_info, MOCK_CONTRACT_ADDR};
+use cosmwasm_std::{
+    coins, from_json, to_json_binary, Addr, BankMsg, CosmosMsg, DistributionMsg, SubMsg, Uint128, WasmMsg,
+};
+
+use crate::archway::ArchwayMsg;
+use crate::collector::CollectorExecuteMsg;
+use crate::contract::execute;
+use crate::msg::ExecuteMsg;
+use crate::state::{Supply, LEDGER, PENDING_HARVESTER, TOTAL_SUPPLY};
+use crate::testing::mock_querier::{
+    execute_admin, mock_dependencies, set_delegation, setup, MockDeps, BOND_DENOM, VALIDATOR,
+};
+use crate::ContractError;
+
+const COLLECTOR: &str = "collector";
+const KEEPER: &str = "keeper";
+
+fn collector_setup() -> MockDeps {
+    let mut deps = mock_dependencies();
+    setup(&mut deps);
+    execute_admin(&mut deps, ExecuteMsg::SetRewardsCollector { address: Some(COLLECTOR.to_string()) });
+    set_delegation(&mut deps, 1_000_000, 10_000);
+    deps
+}
+
+fn self_call(msg: &ExecuteMsg) -> SubMsg<ArchwayMsg> {
+    SubMsg::new(WasmMsg::Execute {
+        contract_addr: MOCK_CONTRACT_ADDR.to_string(),
+        msg: to_json_binary(msg).unwrap(),
+        funds: vec![],
+    })
+}
+
+#[test]
+fn perform_check_forwards_rewards_through_the_collector() {
+    let mut deps = collector_setup();
+    let msg = ExecuteMsg::_PerformCheck { harvester: Some(Addr::unchecked(KEEPER)) };
+    let res = execute(deps.as_mut(), mock_env(), mock_info(MOCK_CONTRACT_ADDR, &[]), msg).unwrap();
+    assert_eq!(res.messages, vec![
+        SubMsg::new(DistributionMsg::WithdrawDelegatorReward { validator: VALIDATOR.to_string() }),
+        SubMsg::new(WasmMsg::Execute {
+            contract_addr: COLLECTOR.to_string(),
+            msg: to_json_binary(&CollectorExecuteMsg::Forward {}).unwrap(),
+            funds: vec![],
+        }),
+        // the staking rewards are booked by DepositRewards, not here
+        self_call(&ExecuteMsg::_ProcessToken { rewards: Uint128::zero(), harvester: Some(Addr::unchecked(KEEPER)) }),
+    ]);
+    assert_eq!(PENDING_HARVESTER.load(&deps.storage).unwrap(), KEEPER);
+}
+
+#[test]
+fn deposit_rewards_pays_the_harvest_bounty() {
+    let mut deps = collector_setup();
+    TOTAL_SUPPLY.save(deps.as_mut().storage, &Supply { native: Uint128::new(1_000_000), ..Supply::default() }).unwrap();
+    execute(deps.as_mut(), mock_env(), mock_info(KEEPER, &[]), ExecuteMsg::Harvest {}).unwrap();
+    let msg = ExecuteMsg::_PerformCheck { harvester: Some(Addr::unchecked(KEEPER)) };
+    execute(deps.as_mut(), mock_env(), mock_info(MOCK_CONTRACT_ADDR, &[]), msg).unwrap();
+
+    let res = execute(deps.as_mut(), mock_env(), mock_info(COLLECTOR, &coins(10_000, BOND_DENOM)), ExecuteMsg::DepositRewards {}).unwrap();
+    // the default bounty is 1%
+    assert_eq!(res.messages, vec![
+        SubMsg::new(BankMsg::Send { to_address: KEEPER.to_string(), amount: coins(100, BOND_DENOM) }),
+        self_call(&ExecuteMsg::_ProcessToken { rewards: Uint128::zero(), harvester: None }),
+    ]);
+    assert_eq!(TOTAL_SUPPLY.load(&deps.storage).unwrap().native.u128(), 1_009_900);
+    assert_eq!(LEDGER.load(&deps.storage).unwrap().balance.u128(), 9_900);
+    assert!(PENDING_HARVESTER.may_load(&deps.storage).unwrap().is_none());
+
+    // rewards forwarded outside of a harvest pay no bounty
+    let res = execute(deps.as_mut(), mock_env(), mock_info(COLLECTOR, &coins(5_000, BOND_DENOM)), ExecuteMsg::DepositRewards {}).unwrap();
+    assert_eq!(res.messages.len(), 1);
+    assert!(matches!(&res.messages[0].msg, CosmosMsg::Wasm(WasmMsg::Execute { msg, .. })
+        if matches!(from_json(msg).unwrap(), ExecuteMsg::_ProcessToken { .. })));
+    assert_eq!(TOTAL_SUPPLY.load(&deps.storage).unwrap().native.u128(), 1_014_900);
+}
+
+#[test]
+fn deposit_rewards_only_from_the_collector() {
+    let mut deps = collector_setup();
+    let err = execute(deps.as_mut(), mock_env(), mock_info(KEEPER, &coins(10, BOND_DENOM)), ExecuteMsg::DepositRewards {}).unwrap_err();
+    assert!(matches!(err, ContractError::Unauthorized {}));
+    let err = execute(deps.as_mut(), mock_env(), mock_info(COLLECTOR, &coins(10, "uother")), ExecuteMsg::DepositRewards {}).unwrap_err();
+    assert!(matches!(err, ContractError::InvalidRewards { .. }));
+}
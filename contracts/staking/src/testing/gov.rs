@@ -0,0 +1,125 @@
+use cosmwasm_std::testing::{mock_env, mock_info};
+use cosmwasm_std::{from_json, CosmosMsg, Decimal, GovMsg, VoteOption, WeightedVoteOption};
+
+use crate::archway::ArchwayMsg;
+use crate::contract::{execute, query};
+use crate::msg::{ExecuteMsg, ProposalResponse, QueryMsg, Role};
+use crate::testing::mock_querier::{mock_dependencies, mock_liquid_token, setup, MockDeps, OWNER};
+use crate::ContractError;
+
+const OPERATOR: &str = "operator";
+const PROPOSAL_ID: u64 = 7;
+
+fn register(deps: &mut MockDeps) {
+    let msg = ExecuteMsg::GrantRole { role: Role::Operator, address: OPERATOR.to_string() };
+    execute(deps.as_mut(), mock_env(), mock_info(OWNER, &[]), msg).unwrap();
+    let voting_end = mock_env().block.time.plus_seconds(3600).seconds();
+    let msg = ExecuteMsg::RegisterProposal { proposal_id: PROPOSAL_ID, voting_end };
+    execute(deps.as_mut(), mock_env(), mock_info(OPERATOR, &[]), msg).unwrap();
+}
+
+fn vote(deps: &mut MockDeps, voter: &str, vote: VoteOption) -> Result<Vec<CosmosMsg<ArchwayMsg>>, ContractError> {
+    let msg = ExecuteMsg::Vote { proposal_id: PROPOSAL_ID, vote };
+    let res = execute(deps.as_mut(), mock_env(), mock_info(voter, &[]), msg)?;
+    Ok(res.messages.into_iter().map(|sub| sub.msg).collect())
+}
+
+fn weighted(options: &[(VoteOption, u64)]) -> CosmosMsg<ArchwayMsg> {
+    GovMsg::VoteWeighted {
+        proposal_id: PROPOSAL_ID,
+        options: options
+            .iter()
+            .map(|(option, percent)| WeightedVoteOption { option: option.clone(), weight: Decimal::percent(*percent) })
+            .collect(),
+    }.into()
+}
+
+#[test]
+fn register_proposal_needs_operator() {
+    let mut deps = mock_dependencies();
+    setup(&mut deps);
+    let voting_end = mock_env().block.time.plus_seconds(3600).seconds();
+    let msg = ExecuteMsg::RegisterProposal { proposal_id: PROPOSAL_ID, voting_end };
+    let err = execute(deps.as_mut(), mock_env(), mock_info(OPERATOR, &[]), msg).unwrap_err();
+    assert!(matches!(err, ContractError::Unauthorized {}));
+
+    register(&mut deps);
+    let proposal: ProposalResponse =
+        from_json(query(deps.as_ref(), mock_env(), QueryMsg::Proposal { proposal_id: PROPOSAL_ID }).unwrap()).unwrap();
+    assert_eq!(proposal.snapshot_height, mock_env().block.height);
+    assert_eq!(proposal.cast_at, None);
+
+    let msg = ExecuteMsg::RegisterProposal { proposal_id: PROPOSAL_ID, voting_end };
+    let err = execute(deps.as_mut(), mock_env(), mock_info(OPERATOR, &[]), msg).unwrap_err();
+    assert!(matches!(err, ContractError::ProposalAlreadyRegistered { .. }));
+}
+
+#[test]
+fn vote_casts_the_tally() {
+    let mut deps = mock_dependencies();
+    setup(&mut deps);
+    mock_liquid_token(&mut deps, 1_000, &[("alice", 300), ("bob", 100)]);
+    register(&mut deps);
+
+    let msgs = vote(&mut deps, "alice", VoteOption::Yes).unwrap();
+    assert_eq!(msgs, vec![weighted(&[(VoteOption::Yes, 100)])]);
+    let msgs = vote(&mut deps, "bob", VoteOption::No).unwrap();
+    assert_eq!(msgs, vec![weighted(&[(VoteOption::Yes, 75), (VoteOption::No, 25)])]);
+    // a new vote replaces the previous one
+    let msgs = vote(&mut deps, "alice", VoteOption::No).unwrap();
+    assert_eq!(msgs, vec![weighted(&[(VoteOption::No, 100)])]);
+
+    let proposal: ProposalResponse =
+        from_json(query(deps.as_ref(), mock_env(), QueryMsg::Proposal { proposal_id: PROPOSAL_ID }).unwrap()).unwrap();
+    assert_eq!(proposal.tally.yes.u128(), 0);
+    assert_eq!(proposal.tally.no.u128(), 400);
+    assert_eq!(proposal.cast_at, Some(mock_env().block.time.seconds()));
+}
+
+#[test]
+fn vote_needs_power_and_open_proposal() {
+    let mut deps = mock_dependencies();
+    setup(&mut deps);
+    mock_liquid_token(&mut deps, 1_000, &[("alice", 300)]);
+
+    let err = vote(&mut deps, "alice", VoteOption::Yes).unwrap_err();
+    assert!(matches!(err, ContractError::ProposalNotFound { .. }));
+    register(&mut deps);
+    let err = vote(&mut deps, "carol", VoteOption::Yes).unwrap_err();
+    assert!(matches!(err, ContractError::NoVotingPower { .. }));
+
+    let mut env = mock_env();
+    env.block.time = env.block.time.plus_seconds(3600);
+    let msg = ExecuteMsg::Vote { proposal_id: PROPOSAL_ID, vote: VoteOption::Yes };
+    let err = execute(deps.as_mut(), env, mock_info("alice", &[]), msg).unwrap_err();
+    assert!(matches!(err, ContractError::VotingEnded { .. }));
+}
+
+#[test]
+fn cast_vote_splits_weights_to_one() {
+    let mut deps = mock_dependencies();
+    setup(&mut deps);
+    mock_liquid_token(&mut deps, 1_000, &[("alice", 1), ("bob", 1), ("carol", 1)]);
+    register(&mut deps);
+
+    let msg = ExecuteMsg::CastVote { proposal_id: PROPOSAL_ID };
+    let err = execute(deps.as_mut(), mock_env(), mock_info(OPERATOR, &[]), msg.clone()).unwrap_err();
+    assert!(matches!(err, ContractError::NoVotes { .. }));
+
+    vote(&mut deps, "alice", VoteOption::Yes).unwrap();
+    vote(&mut deps, "bob", VoteOption::No).unwrap();
+    vote(&mut deps, "carol", VoteOption::Abstain).unwrap();
+    let err = execute(deps.as_mut(), mock_env(), mock_info("alice", &[]), msg.clone()).unwrap_err();
+    assert!(matches!(err, ContractError::Unauthorized {}));
+    let res = execute(deps.as_mut(), mock_env(), mock_info(OPERATOR, &[]), msg).unwrap();
+    let options = match &res.messages[0].msg {
+        CosmosMsg::Gov(GovMsg::VoteWeighted { proposal_id, options }) => {
+            assert_eq!(*proposal_id, PROPOSAL_ID);
+            options.clone()
+        },
+        other => panic!("unexpected message {:?}", other),
+    };
+    assert_eq!(options.len(), 3);
+    let total = options.iter().fold(Decimal::zero(), |acc, option| acc + option.weight);
+    assert_eq!(total, Decimal::one());
+}
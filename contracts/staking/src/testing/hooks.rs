@@ -0,0 +1,93 @@
+use cosmwasm_std::testing::{mock_env, mock_info, MOCK_CONTRACT_ADDR};
+use cosmwasm_std::{Addr, CosmosMsg, Reply, ReplyOn, SubMsg, SubMsgResult, Uint128, WasmMsg};
+
+use crate::archway::ArchwayMsg;
+use crate::contract::{execute, reply};
+use crate::msg::ExecuteMsg;
+use crate::state::{NativeLedger, Supply, CLAIMABLE, HOOKS, HOOK_GAS_LIMIT, LEDGER, TOTAL_SUPPLY};
+use crate::testing::mock_querier::{execute_admin, mock_dependencies, setup, MockDeps, OWNER};
+
+const STRICT_HOOK: &str = "strict_hook";
+const LENIENT_HOOK: &str = "lenient_hook";
+
+// hooks_setup registers a hook reverting on error and one whose failure is ignored
+fn hooks_setup() -> MockDeps {
+    let mut deps = mock_dependencies();
+    setup(&mut deps);
+    for (address, revert_on_error) in [(STRICT_HOOK, true), (LENIENT_HOOK, false)] {
+        execute_admin(&mut deps, ExecuteMsg::AddHook { address: address.to_string(), revert_on_error });
+    }
+    deps
+}
+
+// hook_messages returns the hook each submessage goes to, with its reply mode and gas limit
+fn hook_messages(messages: &[SubMsg<ArchwayMsg>]) -> Vec<(String, ReplyOn, Option<u64>)> {
+    messages
+        .iter()
+        .filter_map(|sub| match &sub.msg {
+            CosmosMsg::Wasm(WasmMsg::Execute { contract_addr, .. })
+                if contract_addr == STRICT_HOOK || contract_addr == LENIENT_HOOK =>
+            {
+                Some((contract_addr.clone(), sub.reply_on.clone(), sub.gas_limit))
+            },
+            _ => None,
+        })
+        .collect()
+}
+
+#[test]
+fn add_hook_waits_for_the_timelock() {
+    let mut deps = mock_dependencies();
+    setup(&mut deps);
+    let msg = ExecuteMsg::AddHook { address: STRICT_HOOK.to_string(), revert_on_error: true };
+    let res = execute(deps.as_mut(), mock_env(), mock_info(OWNER, &[]), msg.clone()).unwrap();
+    assert!(res.attributes.iter().any(|attr| attr.key == "change_id"));
+    assert!(!HOOKS.has(&deps.storage, &Addr::unchecked(STRICT_HOOK)));
+
+    execute_admin(&mut deps, msg);
+    assert!(HOOKS.load(&deps.storage, &Addr::unchecked(STRICT_HOOK)).unwrap().revert_on_error);
+}
+
+#[test]
+fn ignored_hooks_are_gas_limited() {
+    let mut deps = hooks_setup();
+    let msg = ExecuteMsg::_MintLiquidToken { receiver: Addr::unchecked("alice"), native_amount: Uint128::new(1_000_000) };
+    let res = execute(deps.as_mut(), mock_env(), mock_info(MOCK_CONTRACT_ADDR, &[]), msg).unwrap();
+    assert_eq!(
+        hook_messages(&res.messages),
+        vec![
+            (LENIENT_HOOK.to_string(), ReplyOn::Error, Some(HOOK_GAS_LIMIT)),
+            (STRICT_HOOK.to_string(), ReplyOn::Never, None),
+        ]
+    );
+}
+
+#[test]
+fn claim_ignores_failing_hooks() {
+    let mut deps = hooks_setup();
+    let total = Uint128::new(100);
+    CLAIMABLE.save(deps.as_mut().storage, &Addr::unchecked("alice"), &total).unwrap();
+    TOTAL_SUPPLY.save(deps.as_mut().storage, &Supply { claims: total, ..Supply::default() }).unwrap();
+    LEDGER.save(deps.as_mut().storage, &NativeLedger { balance: total, ..NativeLedger::default() }).unwrap();
+
+    let res = execute(deps.as_mut(), mock_env(), mock_info("alice", &[]), ExecuteMsg::Claim {}).unwrap();
+    assert_eq!(
+        hook_messages(&res.messages),
+        vec![
+            (LENIENT_HOOK.to_string(), ReplyOn::Error, Some(HOOK_GAS_LIMIT)),
+            (STRICT_HOOK.to_string(), ReplyOn::Error, Some(HOOK_GAS_LIMIT)),
+        ]
+    );
+
+    // the strict hook failing is only recorded, the claim goes through
+    let strict = res
+        .messages
+        .iter()
+        .find(|sub| matches!(&sub.msg, CosmosMsg::Wasm(WasmMsg::Execute { contract_addr, .. }) if contract_addr == STRICT_HOOK))
+        .unwrap();
+    let msg = Reply { id: strict.id, result: SubMsgResult::Err("hook failed".to_string()) };
+    let res = reply(deps.as_mut(), mock_env(), msg).unwrap();
+    assert_eq!(res.attributes[0].value, "hookFailed");
+    assert!(res.messages.is_empty());
+    assert_eq!(CLAIMABLE.load(&deps.storage, &Addr::unchecked("alice")).unwrap(), Uint128::zero());
+}
@@ -0,0 +1,37 @@
+use cosmwasm_std::testing::{mock_env, mock_info};
+use cosmwasm_std::{coins, Uint128};
+
+use crate::contract::execute;
+use crate::msg::ExecuteMsg;
+use crate::state::{DepositLimits, DEPOSIT_LIMITS};
+use crate::testing::mock_querier::{execute_admin, mock_dependencies, setup, BOND_DENOM, OWNER};
+use crate::ContractError;
+
+fn set_limits() -> ExecuteMsg {
+    ExecuteMsg::SetDepositLimits {
+        deposit_cap: None,
+        address_cap: Some(Uint128::new(2_000_000)),
+        min_stake: Uint128::new(1_000),
+    }
+}
+
+#[test]
+fn deposit_limits_wait_for_the_timelock() {
+    let mut deps = mock_dependencies();
+    setup(&mut deps);
+    let res = execute(deps.as_mut(), mock_env(), mock_info(OWNER, &[]), set_limits()).unwrap();
+    assert!(res.attributes.iter().any(|attr| attr.key == "change_id"));
+    assert_eq!(DEPOSIT_LIMITS.load(&deps.storage).unwrap(), DepositLimits::default());
+
+    execute_admin(&mut deps, set_limits());
+    let limits = DEPOSIT_LIMITS.load(&deps.storage).unwrap();
+    assert_eq!(limits.address_cap, Some(Uint128::new(2_000_000)));
+    assert_eq!(limits.min_stake.u128(), 1_000);
+
+    let info = mock_info("alice", &coins(999, BOND_DENOM));
+    let err = execute(deps.as_mut(), mock_env(), info, ExecuteMsg::Stake {}).unwrap_err();
+    assert!(matches!(err, ContractError::StakeBelowMinimum { .. }));
+    let info = mock_info("alice", &coins(2_000_001, BOND_DENOM));
+    let err = execute(deps.as_mut(), mock_env(), info, ExecuteMsg::Stake {}).unwrap_err();
+    assert!(matches!(err, ContractError::AddressCapExceeded { .. }));
+}
@@ -0,0 +1,159 @@
+use std::collections::HashMap;
+use std::marker::PhantomData;
+
+use cosmwasm_std::testing::{mock_env, mock_info, MockApi, MockQuerier, MockStorage, MOCK_CONTRACT_ADDR};
+use cosmwasm_std::{
+    from_json, to_json_binary, Addr, Coin, ContractResult, Decimal, Env, FullDelegation, OwnedDeps, Response,
+    SystemError, SystemResult, Uint128, Validator, WasmQuery,
+};
+use cw20::{BalanceResponse, Cw20QueryMsg, TokenInfoResponse};
+
+use crate::archway::{ArchwayMsg, ArchwayQuery, RewardsRecordsResponse};
+use crate::contract::{execute, instantiate};
+use crate::gov::SnapshotQueryMsg;
+use crate::msg::{ExecuteMsg, InstantiateMsg};
+use crate::state::CONFIG;
+use crate::ticket::{OwnerOfResponse, TicketQueryMsg};
+use crate::ContractError;
+
+pub const OWNER: &str = "owner";
+pub const VALIDATOR: &str = "validator";
+pub const BOND_DENOM: &str = "uarch";
+pub const LIQUID_TOKEN: &str = "liquid_token";
+pub const TICKET: &str = "ticket";
+
+pub type MockDeps = OwnedDeps<MockStorage, MockApi, MockQuerier<ArchwayQuery>, ArchwayQuery>;
+
+// mock_dependencies returns dependencies with a chain holding one validator and no Archway rewards
+pub fn mock_dependencies() -> MockDeps {
+    mock_dependencies_with_custom_handler(|_| {
+        let response = RewardsRecordsResponse { records: vec![], pagination: None };
+        SystemResult::Ok(ContractResult::Ok(to_json_binary(&response).unwrap()))
+    })
+}
+
+// mock_dependencies_with_custom_handler returns dependencies answering Archway queries with the handler
+pub fn mock_dependencies_with_custom_handler<H>(handler: H) -> MockDeps
+where
+    H: Fn(&ArchwayQuery) -> SystemResult<ContractResult<cosmwasm_std::Binary>> + 'static,
+{
+    let mut querier = MockQuerier::<ArchwayQuery>::new(&[]).with_custom_handler(handler);
+    querier.update_staking(BOND_DENOM, &[validator()], &[]);
+    OwnedDeps {
+        storage: MockStorage::default(),
+        api: MockApi::default(),
+        querier,
+        custom_query_type: PhantomData,
+    }
+}
+
+fn validator() -> Validator {
+    Validator {
+        address: VALIDATOR.to_string(),
+        commission: Decimal::percent(5),
+        max_commission: Decimal::percent(10),
+        max_change_rate: Decimal::percent(1),
+    }
+}
+
+// set_delegation sets the delegation of the contract to the validator and its pending rewards
+pub fn set_delegation(deps: &mut MockDeps, amount: u128, rewards: u128) {
+    let delegations = if amount == 0 {
+        vec![]
+    } else {
+        vec![FullDelegation {
+            delegator: Addr::unchecked(MOCK_CONTRACT_ADDR),
+            validator: VALIDATOR.to_string(),
+            amount: Coin::new(amount, BOND_DENOM),
+            can_redelegate: Coin::new(amount, BOND_DENOM),
+            accumulated_rewards: vec![Coin::new(rewards, BOND_DENOM)],
+        }]
+    };
+    deps.querier.update_staking(BOND_DENOM, &[validator()], &delegations);
+}
+
+// set_balance sets the native balance of the contract
+pub fn set_balance(deps: &mut MockDeps, amount: u128) {
+    deps.querier.update_balance(MOCK_CONTRACT_ADDR, vec![Coin::new(amount, BOND_DENOM)]);
+}
+
+// mock_liquid_token answers the cw20 and snapshot queries of the liquid token,
+// snapshot balances are the same at every height
+pub fn mock_liquid_token(deps: &mut MockDeps, total_supply: u128, balances: &[(&str, u128)]) {
+    mock_contracts(deps, total_supply, balances, &[]);
+}
+
+// mock_contracts answers the liquid token queries like mock_liquid_token,
+// and the OwnerOf queries of the claim ticket contract with the given ticket owners
+pub fn mock_contracts(deps: &mut MockDeps, total_supply: u128, balances: &[(&str, u128)], tickets: &[(u64, &str)]) {
+    let balances: HashMap<String, Uint128> = balances
+        .iter()
+        .map(|(address, balance)| (address.to_string(), Uint128::new(*balance)))
+        .collect();
+    let tickets: HashMap<String, String> = tickets
+        .iter()
+        .map(|(id, owner)| (id.to_string(), owner.to_string()))
+        .collect();
+    deps.querier.update_wasm(move |query| match query {
+        WasmQuery::Smart { contract_addr, msg } if contract_addr == TICKET => {
+            let response = match from_json(msg).unwrap() {
+                TicketQueryMsg::OwnerOf { token_id, .. } => match tickets.get(&token_id) {
+                    Some(owner) => to_json_binary(&OwnerOfResponse { owner: owner.clone(), approvals: vec![] }),
+                    None => return SystemResult::Ok(ContractResult::Err(format!("No ticket {}", token_id))),
+                },
+            };
+            SystemResult::Ok(ContractResult::Ok(response.unwrap()))
+        },
+        WasmQuery::Smart { contract_addr, msg } if contract_addr == LIQUID_TOKEN => {
+            let response = if let Ok(SnapshotQueryMsg::BalanceAt { address, .. }) = from_json(msg) {
+                let balance = balances.get(&address).copied().unwrap_or_default();
+                to_json_binary(&BalanceResponse { balance })
+            } else {
+                match from_json(msg).unwrap() {
+                    Cw20QueryMsg::TokenInfo {} => to_json_binary(&TokenInfoResponse {
+                        name: "Liquid".to_string(),
+                        symbol: "LQD".to_string(),
+                        decimals: 6,
+                        total_supply: Uint128::new(total_supply),
+                    }),
+                    other => panic!("unexpected liquid token query {:?}", other),
+                }
+            };
+            SystemResult::Ok(ContractResult::Ok(response.unwrap()))
+        },
+        _ => SystemResult::Err(SystemError::UnsupportedRequest { kind: format!("{:?}", query) }),
+    });
+}
+
+// setup instantiates the contract and sets the liquid token, without a timelock delay
+pub fn setup(deps: &mut MockDeps) {
+    let msg = InstantiateMsg { validator: VALIDATOR.to_string() };
+    instantiate(deps.as_mut(), mock_env(), mock_info(OWNER, &[]), msg).unwrap();
+    let msg = ExecuteMsg::SetLiquidToken { address: Addr::unchecked(LIQUID_TOKEN) };
+    execute(deps.as_mut(), mock_env(), mock_info(OWNER, &[]), msg).unwrap();
+    mock_liquid_token(deps, 0, &[]);
+}
+
+// execute_admin runs an owner message and applies the change it queued once the timelock has passed
+pub fn execute_admin(deps: &mut MockDeps, msg: ExecuteMsg) -> Response<ArchwayMsg> {
+    let res = execute(deps.as_mut(), mock_env(), mock_info(OWNER, &[]), msg).unwrap();
+    let id = match res.attributes.iter().find(|attr| attr.key == "change_id") {
+        Some(attr) => attr.value.parse().unwrap(),
+        None => return res,
+    };
+    let env = env_at(CONFIG.load(&deps.storage).unwrap().timelock_delay);
+    execute(deps.as_mut(), env, mock_info(OWNER, &[]), ExecuteMsg::ExecutePendingChange { id }).unwrap()
+}
+
+// run executes a message from the sender, dropping the response
+pub fn run(deps: &mut MockDeps, sender: &str, msg: ExecuteMsg) -> Result<(), ContractError> {
+    execute(deps.as_mut(), mock_env(), mock_info(sender, &[]), msg).map(|_| ())
+}
+
+// env_at returns the mock env `seconds` later, with a block every 5 seconds
+pub fn env_at(seconds: u64) -> Env {
+    let mut env = mock_env();
+    env.block.time = env.block.time.plus_seconds(seconds);
+    env.block.height += seconds / 5;
+    env
+}
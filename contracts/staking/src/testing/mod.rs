@@ -0,0 +1,16 @@
+mod mock_querier;
+
+mod archway;
+mod claim;
+mod collector;
+mod gov;
+mod hooks;
+mod limits;
+mod ownership;
+mod proto;
+mod queue;
+mod ratio;
+mod stake;
+mod ticket;
+mod tokenfactory;
+mod unbonding;
@@ -0,0 +1,47 @@
+use cosmwasm_std::testing::mock_env;
+use cosmwasm_std::from_json;
+
+use crate::contract::query;
+use crate::msg::{ConfigResponse, ExecuteMsg, QueryMsg, Role, RoleMembersResponse};
+use crate::testing::mock_querier::{mock_dependencies, run, setup, OWNER};
+use crate::ContractError;
+
+const GUARDIAN: &str = "guardian";
+
+#[test]
+fn renounce_clears_the_owner() {
+    let mut deps = mock_dependencies();
+    setup(&mut deps);
+    run(&mut deps, OWNER, ExecuteMsg::ProposeNewOwner { owner: "next".to_string(), expiry: None }).unwrap();
+    run(&mut deps, OWNER, ExecuteMsg::RenounceOwnership {}).unwrap();
+
+    let config: ConfigResponse = from_json(query(deps.as_ref(), mock_env(), QueryMsg::ConfigInfo {}).unwrap()).unwrap();
+    assert_eq!(config.owner, None);
+    let msg = QueryMsg::RoleMembers { role: Role::Owner, start_after: None, limit: None };
+    let members: RoleMembersResponse = from_json(query(deps.as_ref(), mock_env(), msg).unwrap()).unwrap();
+    assert!(members.members.is_empty());
+
+    // the proposal is dropped and nobody holds the owner role anymore
+    let err = run(&mut deps, "next", ExecuteMsg::AcceptOwnership {}).unwrap_err();
+    assert!(matches!(err, ContractError::NoPendingOwner {}));
+    let err = run(&mut deps, OWNER, ExecuteMsg::SetTimelockDelay { delay: 0 }).unwrap_err();
+    assert!(matches!(err, ContractError::Unauthorized {}));
+}
+
+#[test]
+fn guardian_unpauses_once_ownership_is_renounced() {
+    let mut deps = mock_dependencies();
+    setup(&mut deps);
+    run(&mut deps, OWNER, ExecuteMsg::GrantRole { role: Role::Guardian, address: GUARDIAN.to_string() }).unwrap();
+    run(&mut deps, GUARDIAN, ExecuteMsg::Pause { operation: None }).unwrap();
+    // the owner unpauses while there is one
+    let err = run(&mut deps, GUARDIAN, ExecuteMsg::Unpause { operation: None }).unwrap_err();
+    assert!(matches!(err, ContractError::Unauthorized {}));
+
+    run(&mut deps, OWNER, ExecuteMsg::RenounceOwnership {}).unwrap();
+    let err = run(&mut deps, "anyone", ExecuteMsg::Unpause { operation: None }).unwrap_err();
+    assert!(matches!(err, ContractError::Unauthorized {}));
+    run(&mut deps, GUARDIAN, ExecuteMsg::Unpause { operation: None }).unwrap();
+    let config: ConfigResponse = from_json(query(deps.as_ref(), mock_env(), QueryMsg::ConfigInfo {}).unwrap()).unwrap();
+    assert!(!config.paused.stake && !config.paused.unstake && !config.paused.claim);
+}
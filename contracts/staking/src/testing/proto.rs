@@ -0,0 +1,21 @@
+use cosmwasm_std::{coin, CosmosMsg, Empty};
+
+use crate::proto::{cancel_unbonding_delegation, CANCEL_UNBONDING_DELEGATION_TYPE_URL};
+
+#[test]
+fn cancel_unbonding_delegation_wire_format() {
+    let msg: CosmosMsg<Empty> = cancel_unbonding_delegation("d", "v", &coin(5, "u"), 300);
+    let expected = vec![
+        0x0a, 1, b'd', // delegator_address
+        0x12, 1, b'v', // validator_address
+        0x1a, 6, 0x0a, 1, b'u', 0x12, 1, b'5', // amount
+        0x20, 0xac, 0x02, // creation_height
+    ];
+    match msg {
+        CosmosMsg::Stargate { type_url, value } => {
+            assert_eq!(type_url, CANCEL_UNBONDING_DELEGATION_TYPE_URL);
+            assert_eq!(value.to_vec(), expected);
+        },
+        other => panic!("unexpected message {:?}", other),
+    }
+}
@@ -0,0 +1,115 @@
+use cosmwasm_std::testing::{mock_env, mock_info};
+use cosmwasm_std::{coin, from_json, Addr, StakingMsg, Uint128};
+
+use crate::contract::{execute, query};
+use crate::msg::{ExecuteMsg, QueryMsg, VerifyQueueResponse};
+use crate::state::{
+    NativeLedger, Node, Supply, CONFIG, DEFAULT_QUEUE_BATCH_SIZE, LEDGER, TOTAL_SUPPLY, UNDER_UNSTAKING,
+    UNSTAKING_QUEUE,
+};
+use crate::testing::mock_querier::{
+    execute_admin, mock_dependencies, set_balance, set_delegation, setup, MockDeps, BOND_DENOM, OWNER, VALIDATOR,
+};
+use crate::ContractError;
+
+fn set_under_unstaking(deps: &mut MockDeps, address: &str, amount: u128) {
+    UNDER_UNSTAKING.save(deps.as_mut().storage, &Addr::unchecked(address), &Uint128::new(amount)).unwrap();
+}
+
+fn verify_queue(deps: &MockDeps, limit: u32) -> VerifyQueueResponse {
+    let msg = QueryMsg::VerifyQueue { limit: Some(limit) };
+    from_json(query(deps.as_ref(), mock_env(), msg).unwrap()).unwrap()
+}
+
+#[test]
+fn queue_batch_size_waits_for_the_timelock() {
+    let mut deps = mock_dependencies();
+    setup(&mut deps);
+    let msg = ExecuteMsg::SetQueueBatchSize { size: 5 };
+    let res = execute(deps.as_mut(), mock_env(), mock_info(OWNER, &[]), msg.clone()).unwrap();
+    assert!(res.attributes.iter().any(|attr| attr.key == "change_id"));
+    assert_eq!(CONFIG.load(&deps.storage).unwrap().queue_batch_size, DEFAULT_QUEUE_BATCH_SIZE);
+
+    execute_admin(&mut deps, msg);
+    assert_eq!(CONFIG.load(&deps.storage).unwrap().queue_batch_size, 5);
+}
+
+#[test]
+fn process_queue_bonds_the_rest_once_the_queue_is_empty() {
+    let mut deps = mock_dependencies();
+    setup(&mut deps);
+    LEDGER.save(deps.as_mut().storage, &NativeLedger { balance: Uint128::new(50_000), ..NativeLedger::default() }).unwrap();
+    set_balance(&mut deps, 50_000);
+
+    let res = execute(deps.as_mut(), mock_env(), mock_info("keeper", &[]), ExecuteMsg::ProcessQueue { limit: None }).unwrap();
+    assert_eq!(res.messages[0].msg, StakingMsg::Delegate {
+        validator: VALIDATOR.to_string(),
+        amount: coin(50_000, BOND_DENOM),
+    }.into());
+    assert!(LEDGER.load(&deps.storage).unwrap().balance.is_zero());
+}
+
+#[test]
+fn process_queue_books_the_rewards_its_delegation_withdraws() {
+    let mut deps = mock_dependencies();
+    setup(&mut deps);
+    set_delegation(&mut deps, 1_000_000, 300);
+    TOTAL_SUPPLY.save(deps.as_mut().storage, &Supply { native: Uint128::new(1_000_000), ..Supply::default() }).unwrap();
+    LEDGER.save(deps.as_mut().storage, &NativeLedger { balance: Uint128::new(50_000), ..NativeLedger::default() }).unwrap();
+    set_balance(&mut deps, 50_000);
+
+    let res = execute(deps.as_mut(), mock_env(), mock_info("keeper", &[]), ExecuteMsg::ProcessQueue { limit: None }).unwrap();
+    assert_eq!(res.attributes.iter().find(|attr| attr.key == "rewards").unwrap().value, "300");
+    assert_eq!(TOTAL_SUPPLY.load(&deps.storage).unwrap().native.u128(), 1_000_300);
+    // the withdrawn rewards are expected in the balance, not left unaccounted
+    assert_eq!(LEDGER.load(&deps.storage).unwrap().balance.u128(), 300);
+}
+
+#[test]
+fn sweep_after_process_queue_leaves_withdrawn_rewards() {
+    let mut deps = mock_dependencies();
+    setup(&mut deps);
+    set_delegation(&mut deps, 1_000_000, 300);
+    TOTAL_SUPPLY.save(deps.as_mut().storage, &Supply { native: Uint128::new(1_000_000), ..Supply::default() }).unwrap();
+    LEDGER.save(deps.as_mut().storage, &NativeLedger { balance: Uint128::new(50_000), ..NativeLedger::default() }).unwrap();
+    set_balance(&mut deps, 50_000);
+    execute(deps.as_mut(), mock_env(), mock_info("keeper", &[]), ExecuteMsg::ProcessQueue { limit: None }).unwrap();
+
+    // the delegation withdrew the pending rewards to the contract
+    set_balance(&mut deps, 300);
+    let msg = ExecuteMsg::SweepUnaccounted { recipient: None };
+    let err = execute(deps.as_mut(), mock_env(), mock_info(OWNER, &[]), msg).unwrap_err();
+    assert!(matches!(err, ContractError::NothingToSweep { .. }));
+}
+
+#[test]
+fn verify_queue_skips_paid_out_addresses() {
+    let mut deps = mock_dependencies();
+    setup(&mut deps);
+    for (receiver, value) in [("alice", 10u128), ("bob", 20)] {
+        let node = Node { receiver: Addr::unchecked(receiver), value: Uint128::new(value), height: 1 };
+        UNSTAKING_QUEUE.push_back(deps.as_mut().storage, node).unwrap();
+        set_under_unstaking(&mut deps, receiver, value);
+    }
+    TOTAL_SUPPLY.save(deps.as_mut().storage, &Supply { unstakings: Uint128::new(30), ..Supply::default() }).unwrap();
+    set_under_unstaking(&mut deps, "aaron", 0);
+    set_under_unstaking(&mut deps, "abel", 0);
+    let report = verify_queue(&deps, 2);
+    assert!(report.complete);
+    assert!(report.problems.is_empty());
+
+    // bob's request has no matching total under unstaking
+    set_under_unstaking(&mut deps, "bob", 0);
+    let report = verify_queue(&deps, 2);
+    assert_eq!(report.problems, vec!["bob has 20 in the queue but nothing under unstaking".to_string()]);
+
+    // leftover receivers are not reported when the addresses are cut by the limit
+    set_under_unstaking(&mut deps, "bob", 20);
+    set_under_unstaking(&mut deps, "carol", 5);
+    let report = verify_queue(&deps, 2);
+    assert!(!report.complete);
+    assert!(report.problems.is_empty());
+    let report = verify_queue(&deps, 3);
+    assert!(report.complete);
+    assert_eq!(report.problems, vec!["carol has 5 under unstaking but 0 in the queue".to_string()]);
+}
@@ -0,0 +1,83 @@
+use cosmwasm_std::testing::{mock_env, mock_info, MOCK_CONTRACT_ADDR};
+use cosmwasm_std::{from_json, Decimal, Env, Uint128};
+
+use crate::contract::{execute, query};
+use crate::msg::{AprResponse, ExecuteMsg, QueryMsg, RatioHistoryResponse};
+use crate::state::{Supply, TOTAL_SUPPLY};
+use crate::testing::mock_querier::{env_at, mock_dependencies, mock_liquid_token, set_balance, setup, MockDeps};
+
+const ISSUED: u128 = 1_000_000;
+
+// harvest books `rewards` arrived on top of the contract balance through _ProcessToken
+fn harvest(deps: &mut MockDeps, env: Env, rewards: u128) {
+    set_balance(deps, rewards);
+    let msg = ExecuteMsg::_ProcessToken { rewards: Uint128::new(rewards), harvester: None };
+    execute(deps.as_mut(), env, mock_info(MOCK_CONTRACT_ADDR, &[]), msg).unwrap();
+}
+
+fn staked_setup() -> MockDeps {
+    let mut deps = mock_dependencies();
+    setup(&mut deps);
+    mock_liquid_token(&mut deps, ISSUED, &[]);
+    let supply = Supply { native: Uint128::new(ISSUED), ..Supply::default() };
+    TOTAL_SUPPLY.save(deps.as_mut().storage, &supply).unwrap();
+    deps
+}
+
+fn query_apr(deps: &MockDeps, env: Env, window_seconds: u64) -> AprResponse {
+    from_json(query(deps.as_ref(), env, QueryMsg::Apr { window_seconds }).unwrap()).unwrap()
+}
+
+#[test]
+fn harvest_records_ratio_history() {
+    let mut deps = staked_setup();
+    harvest(&mut deps, env_at(0), 10_000);
+    harvest(&mut deps, env_at(100), 0);
+    harvest(&mut deps, env_at(200), 10_100);
+
+    let res: RatioHistoryResponse =
+        from_json(query(deps.as_ref(), mock_env(), QueryMsg::RatioHistory { start_after: None, limit: None }).unwrap()).unwrap();
+    // a harvest without rewards doesn't record the ratio
+    assert_eq!(res.snapshots.len(), 2);
+    assert_eq!(res.snapshots[0].id, 0);
+    assert_eq!(res.snapshots[0].info.ratio, Decimal::percent(101));
+    assert_eq!(res.snapshots[0].info.native.u128(), 1_010_000);
+    assert_eq!(res.snapshots[0].info.issued.u128(), ISSUED);
+    assert_eq!(res.snapshots[1].info.ratio, Decimal::from_ratio(10_201u128, 10_000u128));
+    assert_eq!(res.snapshots[1].info.time, env_at(200).block.time);
+
+    let msg = QueryMsg::RatioHistory { start_after: Some(0), limit: Some(1) };
+    let res: RatioHistoryResponse = from_json(query(deps.as_ref(), mock_env(), msg).unwrap()).unwrap();
+    assert_eq!(res.snapshots.len(), 1);
+    assert_eq!(res.snapshots[0].id, 1);
+}
+
+#[test]
+fn apr_annualizes_ratio_growth() {
+    let mut deps = staked_setup();
+    // no history yet
+    let apr = query_apr(&deps, env_at(0), 3600);
+    assert_eq!(apr.apr, Decimal::zero());
+    assert_eq!(apr.start, None);
+    assert_eq!(apr.ratio, Decimal::one());
+
+    // a tenth of a year apart the ratio grows by 1%, that's 10% a year
+    let tenth_year = 365 * 24 * 60 * 60 / 10;
+    harvest(&mut deps, env_at(0), 10_000);
+    harvest(&mut deps, env_at(tenth_year), 10_100);
+    let apr = query_apr(&deps, env_at(tenth_year), tenth_year);
+    assert_eq!(apr.start.unwrap().id, 0);
+    assert_eq!(apr.elapsed_seconds, tenth_year);
+    assert_eq!(apr.apr, Decimal::percent(10));
+
+    // a window shorter than the history starts from the latest snapshot before it
+    let apr = query_apr(&deps, env_at(tenth_year + 60), 30);
+    assert_eq!(apr.start.unwrap().id, 1);
+    assert_eq!(apr.elapsed_seconds, 60);
+    assert_eq!(apr.apr, Decimal::zero());
+
+    // a window longer than the history covers what there is
+    let apr = query_apr(&deps, env_at(tenth_year), 10 * tenth_year);
+    assert_eq!(apr.start.unwrap().id, 0);
+    assert_eq!(apr.elapsed_seconds, tenth_year);
+}
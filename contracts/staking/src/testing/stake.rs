@@ -0,0 +1,74 @@
+use cosmwasm_std::testing::{mock_env, mock_info, MOCK_CONTRACT_ADDR};
+use cosmwasm_std::{Addr, Response, Uint128};
+
+use crate::archway::ArchwayMsg;
+use crate::contract::execute;
+use crate::msg::ExecuteMsg;
+use crate::state::{Supply, LOCKED_LIQUID_TOKENS, TOTAL_SUPPLY};
+use crate::testing::mock_querier::{mock_dependencies, mock_liquid_token, set_balance, setup, MockDeps};
+use crate::ContractError;
+
+fn mint(deps: &mut MockDeps, receiver: &str, native_amount: u128) -> Result<Response<ArchwayMsg>, ContractError> {
+    let msg = ExecuteMsg::_MintLiquidToken {
+        receiver: Addr::unchecked(receiver),
+        native_amount: Uint128::new(native_amount),
+    };
+    execute(deps.as_mut(), mock_env(), mock_info(MOCK_CONTRACT_ADDR, &[]), msg)
+}
+
+fn minted(res: &Response<ArchwayMsg>) -> u128 {
+    res.attributes.iter().find(|attr| attr.key == "minted").unwrap().value.parse().unwrap()
+}
+
+fn set_supply(deps: &mut MockDeps, native: u128, liquid: u128) {
+    let supply = Supply { native: Uint128::new(native), ..Supply::default() };
+    TOTAL_SUPPLY.save(deps.as_mut().storage, &supply).unwrap();
+    mock_liquid_token(deps, liquid, &[]);
+}
+
+#[test]
+fn first_stake_locks_liquid_tokens() {
+    let mut deps = mock_dependencies();
+    setup(&mut deps);
+    let err = mint(&mut deps, "alice", 999_999).unwrap_err();
+    assert!(matches!(err, ContractError::InsufficientInitialDeposit { .. }));
+
+    let res = mint(&mut deps, "alice", 1_000_000).unwrap();
+    assert_eq!(minted(&res), 1_000_000 - LOCKED_LIQUID_TOKENS);
+    assert_eq!(res.attributes.iter().find(|attr| attr.key == "locked").unwrap().value, "1000");
+    assert_eq!(TOTAL_SUPPLY.load(&deps.storage).unwrap().native.u128(), 1_000_000);
+}
+
+#[test]
+fn donation_does_not_dilute_next_staker() {
+    let mut deps = mock_dependencies();
+    setup(&mut deps);
+    set_supply(&mut deps, 1_000_000, 1_000_000);
+    // tokens sent directly to the contract are not booked in the ratio
+    set_balance(&mut deps, 100_000_000);
+    let res = mint(&mut deps, "victim", 500_000).unwrap();
+    assert_eq!(minted(&res), 500_000);
+}
+
+#[test]
+fn inflated_ratio_costs_the_next_staker_little() {
+    let mut deps = mock_dependencies();
+    setup(&mut deps);
+    // everyone but the locked tokens unstaked, then 1_000_000 was booked as rewards
+    set_supply(&mut deps, 1_000 + 1_000_000, LOCKED_LIQUID_TOKENS);
+    let res = mint(&mut deps, "victim", 500_000).unwrap();
+    let shares = minted(&res);
+    assert_eq!(shares, 499);
+    // the rounding loss goes to the locked tokens, not to whoever inflated the ratio
+    let value = Uint128::new(shares).multiply_ratio(1_501_000u128, LOCKED_LIQUID_TOKENS + shares);
+    assert!(value.u128() > 500_000 * 999 / 1000);
+}
+
+#[test]
+fn drained_pool_rejects_stakes() {
+    let mut deps = mock_dependencies();
+    setup(&mut deps);
+    set_supply(&mut deps, 0, LOCKED_LIQUID_TOKENS);
+    let err = mint(&mut deps, "victim", 1_000_000).unwrap_err();
+    assert!(matches!(err, ContractError::DrainedPool { issued } if issued.u128() == LOCKED_LIQUID_TOKENS));
+}
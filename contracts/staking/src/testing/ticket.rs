@@ -0,0 +1,101 @@
+use cosmwasm_std::testing::{mock_env, mock_info};
+use cosmwasm_std::{coins, from_json, Addr, BankMsg, CosmosMsg, Uint128, WasmMsg};
+use cw20::Cw20ReceiveMsg;
+
+use crate::contract::{execute, query};
+use crate::msg::{ExecuteMsg, QueryMsg, TicketResponse};
+use crate::state::{NativeLedger, Supply, CLAIMABLE, LEDGER, TOTAL_SUPPLY, UNBONDING_PERIOD, UNSTAKING_QUEUE};
+use crate::testing::mock_querier::{
+    execute_admin, mock_contracts, mock_dependencies, set_balance, setup, MockDeps, BOND_DENOM, LIQUID_TOKEN, TICKET,
+};
+use crate::ticket::{TicketExecuteMsg, TicketMetadata};
+use crate::ContractError;
+
+// ticketed_setup has alice unstake 100_000 with tickets on, the request is filled at once
+fn ticketed_setup() -> (MockDeps, u64) {
+    let mut deps = mock_dependencies();
+    setup(&mut deps);
+    execute_admin(&mut deps, ExecuteMsg::SetTicketContract { address: Some(TICKET.to_string()) });
+    TOTAL_SUPPLY.save(deps.as_mut().storage, &Supply { native: Uint128::new(1_000_000), ..Supply::default() }).unwrap();
+    mock_contracts(&mut deps, 1_000_000, &[], &[]);
+
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: "alice".to_string(),
+        amount: Uint128::new(100_000),
+        msg: Default::default(),
+    });
+    let res = execute(deps.as_mut(), mock_env(), mock_info(LIQUID_TOKEN, &[]), msg).unwrap();
+    let (id, _) = UNSTAKING_QUEUE.front(&deps.storage).unwrap().unwrap();
+    let mint = res
+        .messages
+        .iter()
+        .find_map(|sub| match &sub.msg {
+            CosmosMsg::Wasm(WasmMsg::Execute { contract_addr, msg, .. }) if contract_addr == TICKET => {
+                Some(from_json::<TicketExecuteMsg>(msg).unwrap())
+            },
+            _ => None,
+        })
+        .unwrap();
+    let TicketExecuteMsg::Mint(mint) = mint;
+    assert_eq!(mint.token_id, id.to_string());
+    assert_eq!(mint.owner, "alice");
+    assert_eq!(mint.extension, TicketMetadata {
+        amount: Uint128::new(100_000),
+        height: mock_env().block.height,
+        eta: mock_env().block.time.plus_seconds(UNBONDING_PERIOD),
+    });
+
+    // the unstaked tokens are back in the contract
+    LEDGER.save(deps.as_mut().storage, &NativeLedger { balance: Uint128::new(100_000), ..NativeLedger::default() }).unwrap();
+    set_balance(&mut deps, 100_000);
+    execute(deps.as_mut(), mock_env(), mock_info("keeper", &[]), ExecuteMsg::ProcessQueue { limit: None }).unwrap();
+    (deps, id)
+}
+
+#[test]
+fn ticket_holder_claims_the_request() {
+    let (mut deps, id) = ticketed_setup();
+    // a ticketed request is not part of the receiver's claimable total
+    assert!(CLAIMABLE.may_load(&deps.storage, &Addr::unchecked("alice")).unwrap().unwrap_or_default().is_zero());
+
+    // alice sold the ticket to bob
+    mock_contracts(&mut deps, 900_000, &[], &[(id, "bob")]);
+    let msg = ExecuteMsg::ClaimRequests { ids: vec![id] };
+    let err = execute(deps.as_mut(), mock_env(), mock_info("alice", &[]), msg.clone()).unwrap_err();
+    assert!(matches!(err, ContractError::Unauthorized {}));
+
+    let res = execute(deps.as_mut(), mock_env(), mock_info("bob", &[]), msg.clone()).unwrap();
+    assert_eq!(res.messages[0].msg, CosmosMsg::Bank(BankMsg::Send {
+        to_address: "bob".to_string(),
+        amount: coins(100_000, BOND_DENOM),
+    }));
+    let err = execute(deps.as_mut(), mock_env(), mock_info("bob", &[]), msg).unwrap_err();
+    assert!(matches!(err, ContractError::NothingToClaim {}));
+    assert!(TOTAL_SUPPLY.load(&deps.storage).unwrap().claims.is_zero());
+}
+
+#[test]
+fn ticket_holder_claims_the_ticket() {
+    let (mut deps, id) = ticketed_setup();
+    mock_contracts(&mut deps, 900_000, &[], &[(id, "bob")]);
+    let ticket: TicketResponse = from_json(query(deps.as_ref(), mock_env(), QueryMsg::Ticket { id }).unwrap()).unwrap();
+    assert_eq!(ticket, TicketResponse {
+        contract: TICKET.to_string(),
+        owner: "bob".to_string(),
+        amount: Uint128::new(100_000),
+        claimable: Uint128::new(100_000),
+        unfilled: Uint128::zero(),
+    });
+
+    let err = execute(deps.as_mut(), mock_env(), mock_info("bob", &[]), ExecuteMsg::ClaimTicket { id: id + 1 }).unwrap_err();
+    assert!(matches!(err, ContractError::TicketNotFound { .. }));
+    let err = execute(deps.as_mut(), mock_env(), mock_info("alice", &[]), ExecuteMsg::ClaimTicket { id }).unwrap_err();
+    assert!(matches!(err, ContractError::Unauthorized {}));
+    let res = execute(deps.as_mut(), mock_env(), mock_info("bob", &[]), ExecuteMsg::ClaimTicket { id }).unwrap();
+    assert_eq!(res.messages[0].msg, CosmosMsg::Bank(BankMsg::Send {
+        to_address: "bob".to_string(),
+        amount: coins(100_000, BOND_DENOM),
+    }));
+    let ticket: TicketResponse = from_json(query(deps.as_ref(), mock_env(), QueryMsg::Ticket { id }).unwrap()).unwrap();
+    assert!(ticket.claimable.is_zero());
+}
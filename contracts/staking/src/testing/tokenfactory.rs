@@ -0,0 +1,153 @@
+use cosmwasm_std::testing::{mock_env, mock_info, MOCK_CONTRACT_ADDR};
+use cosmwasm_std::{coin, coins, Addr, CosmosMsg, Empty, Uint128, VoteOption};
+use prost::Message;
+
+use crate::archway::ArchwayMsg;
+use crate::contract::{execute, instantiate};
+use crate::msg::{ExecuteMsg, InstantiateMsg};
+use crate::state::{LIQUID_SUPPLY, LOCKED_LIQUID_TOKENS};
+use crate::testing::mock_querier::{mock_dependencies, MockDeps, BOND_DENOM, OWNER, VALIDATOR};
+use crate::tokenfactory::{burn, create_denom, mint, BURN_TYPE_URL, CREATE_DENOM_TYPE_URL, MINT_TYPE_URL};
+use crate::ContractError;
+
+fn denom() -> String {
+    format!("factory/{}/sarch", MOCK_CONTRACT_ADDR)
+}
+
+fn stargate(msg: CosmosMsg<Empty>) -> (String, Vec<u8>) {
+    match msg {
+        CosmosMsg::Stargate { type_url, value } => (type_url, value.to_vec()),
+        other => panic!("unexpected message {:?}", other),
+    }
+}
+
+#[test]
+fn create_denom_wire_format() {
+    let expected = vec![
+        0x0a, 1, b'c', // sender
+        0x12, 2, b's', b'a', // subdenom
+    ];
+    assert_eq!(stargate(create_denom("c", "sa")), (CREATE_DENOM_TYPE_URL.to_string(), expected));
+}
+
+// The messages below are osmosis/tokenfactory/v1beta1/tx.proto and cosmos/base/v1beta1/coin.proto,
+// decoded with prost to check the hand written encoding against the canonical field numbers and types
+#[derive(Clone, PartialEq, prost::Message)]
+struct ProtoCoin {
+    #[prost(string, tag = "1")]
+    denom: String,
+    #[prost(string, tag = "2")]
+    amount: String,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+struct MsgCreateDenom {
+    #[prost(string, tag = "1")]
+    sender: String,
+    #[prost(string, tag = "2")]
+    subdenom: String,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+struct MsgMint {
+    #[prost(string, tag = "1")]
+    sender: String,
+    #[prost(message, optional, tag = "2")]
+    amount: Option<ProtoCoin>,
+    #[prost(string, tag = "3")]
+    mint_to_address: String,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+struct MsgBurn {
+    #[prost(string, tag = "1")]
+    sender: String,
+    #[prost(message, optional, tag = "2")]
+    amount: Option<ProtoCoin>,
+    #[prost(string, tag = "3")]
+    burn_from_address: String,
+}
+
+#[test]
+fn messages_decode_with_the_tokenfactory_proto() {
+    let sender = MOCK_CONTRACT_ADDR.to_string();
+    let amount = Some(ProtoCoin { denom: denom(), amount: "1234567890123".to_string() });
+
+    let (_, value) = stargate(create_denom(&sender, "sarch"));
+    let decoded = MsgCreateDenom::decode(value.as_slice()).unwrap();
+    assert_eq!(decoded, MsgCreateDenom { sender: sender.clone(), subdenom: "sarch".to_string() });
+
+    let (_, value) = stargate(mint(&sender, &coin(1_234_567_890_123, denom()), "alice"));
+    let decoded = MsgMint::decode(value.as_slice()).unwrap();
+    assert_eq!(decoded, MsgMint { sender: sender.clone(), amount: amount.clone(), mint_to_address: "alice".to_string() });
+    // prost encodes the same bytes, so the chain reads back exactly what was written
+    assert_eq!(decoded.encode_to_vec(), value);
+
+    let (_, value) = stargate(burn(&sender, &coin(1_234_567_890_123, denom()), MOCK_CONTRACT_ADDR));
+    let decoded = MsgBurn::decode(value.as_slice()).unwrap();
+    assert_eq!(decoded, MsgBurn { sender: sender.clone(), amount, burn_from_address: sender });
+    assert_eq!(decoded.encode_to_vec(), value);
+}
+
+#[test]
+fn mint_and_burn_wire_format() {
+    let expected = vec![
+        0x0a, 1, b'c', // sender
+        0x12, 7, 0x0a, 1, b'u', 0x12, 2, b'4', b'2', // amount
+        0x1a, 1, b'r', // mint_to_address or burn_from_address
+    ];
+    assert_eq!(stargate(mint("c", &coin(42, "u"), "r")), (MINT_TYPE_URL.to_string(), expected.clone()));
+    assert_eq!(stargate(burn("c", &coin(42, "u"), "r")), (BURN_TYPE_URL.to_string(), expected));
+}
+
+// denom_setup instantiates the contract with a TokenFactory liquid denom and has alice stake 1_000_000
+fn denom_setup() -> MockDeps {
+    let mut deps = mock_dependencies();
+    let msg = InstantiateMsg { validator: VALIDATOR.to_string() };
+    instantiate(deps.as_mut(), mock_env(), mock_info(OWNER, &[]), msg).unwrap();
+    let msg = ExecuteMsg::CreateLiquidDenom { subdenom: "sarch".to_string() };
+    let res = execute(deps.as_mut(), mock_env(), mock_info(OWNER, &[]), msg).unwrap();
+    assert_eq!(res.messages[0].msg, create_denom(MOCK_CONTRACT_ADDR, "sarch"));
+
+    let msg = ExecuteMsg::_MintLiquidToken { receiver: Addr::unchecked("alice"), native_amount: Uint128::new(1_000_000) };
+    let res = execute(deps.as_mut(), mock_env(), mock_info(MOCK_CONTRACT_ADDR, &[]), msg).unwrap();
+    let msgs: Vec<CosmosMsg<ArchwayMsg>> = res.messages.into_iter().map(|sub| sub.msg).collect();
+    assert_eq!(
+        msgs,
+        vec![
+            mint(MOCK_CONTRACT_ADDR, &coin(LOCKED_LIQUID_TOKENS, denom()), MOCK_CONTRACT_ADDR),
+            mint(MOCK_CONTRACT_ADDR, &coin(1_000_000 - LOCKED_LIQUID_TOKENS, denom()), "alice"),
+        ]
+    );
+    assert_eq!(LIQUID_SUPPLY.load(&deps.storage).unwrap().u128(), 1_000_000);
+    deps
+}
+
+#[test]
+fn unstake_burns_the_denom() {
+    let mut deps = denom_setup();
+    let info = mock_info("alice", &coins(100_000, denom()));
+    let res = execute(deps.as_mut(), mock_env(), info, ExecuteMsg::Unstake {}).unwrap();
+    assert_eq!(res.messages[0].msg, burn(MOCK_CONTRACT_ADDR, &coin(100_000, denom()), MOCK_CONTRACT_ADDR));
+    assert_eq!(LIQUID_SUPPLY.load(&deps.storage).unwrap().u128(), 900_000);
+}
+
+#[test]
+fn unstake_takes_exactly_one_coin() {
+    let mut deps = denom_setup();
+    for funds in [vec![], coins(100, BOND_DENOM), vec![coin(100, denom()), coin(100, BOND_DENOM)]] {
+        let err = execute(deps.as_mut(), mock_env(), mock_info("alice", &funds), ExecuteMsg::Unstake {}).unwrap_err();
+        assert!(matches!(err, ContractError::InvalidUnstakeFunds { .. }));
+    }
+}
+
+#[test]
+fn vote_is_unsupported_with_a_denom() {
+    let mut deps = denom_setup();
+    let voting_end = mock_env().block.time.plus_seconds(60).seconds();
+    let msg = ExecuteMsg::RegisterProposal { proposal_id: 1, voting_end };
+    execute(deps.as_mut(), mock_env(), mock_info(OWNER, &[]), msg).unwrap();
+    let msg = ExecuteMsg::Vote { proposal_id: 1, vote: VoteOption::Yes };
+    let err = execute(deps.as_mut(), mock_env(), mock_info("alice", &[]), msg).unwrap_err();
+    assert!(matches!(err, ContractError::VotingUnsupported {}));
+}
@@ -0,0 +1,277 @@
+use cosmwasm_std::testing::{mock_info, MOCK_CONTRACT_ADDR};
+use cosmwasm_std::{coin, from_json, Addr, CosmosMsg, Env, Order, StakingMsg, StdResult, Uint128};
+use cw20::Cw20ReceiveMsg;
+
+use crate::archway::ArchwayMsg;
+use crate::contract::{execute, query};
+use crate::hooks::HookExecuteMsg;
+use crate::msg::{ExecuteMsg, Operation, QueryMsg, SolvencyResponse};
+use crate::proto::cancel_unbonding_delegation;
+use crate::state::{Supply, CLAIMABLE, LEDGER, RATIO_HISTORY, TOTAL_SUPPLY, UNBONDINGS, UNBONDING_PERIOD, UNSTAKING_QUEUE};
+use crate::testing::mock_querier::{
+    env_at, execute_admin, mock_dependencies, mock_liquid_token, set_balance, set_delegation, setup, MockDeps, BOND_DENOM,
+    LIQUID_TOKEN, OWNER, VALIDATOR,
+};
+use crate::ContractError;
+
+const DAY: u64 = 24 * 60 * 60;
+
+fn process_token(deps: &mut MockDeps, env: Env) -> Vec<CosmosMsg<ArchwayMsg>> {
+    let msg = ExecuteMsg::_ProcessToken {
+        rewards: Uint128::zero(),
+        harvester: None,
+    };
+    let res = execute(deps.as_mut(), env, mock_info(MOCK_CONTRACT_ADDR, &[]), msg).unwrap();
+    res.messages.into_iter().map(|sub| sub.msg).collect()
+}
+
+fn process_queue(deps: &mut MockDeps, env: Env) -> String {
+    let res = execute(
+        deps.as_mut(),
+        env,
+        mock_info("keeper", &[]),
+        ExecuteMsg::ProcessQueue { limit: None },
+    )
+    .unwrap();
+    res.attributes
+        .iter()
+        .find(|attr| attr.key == "paid")
+        .unwrap()
+        .value
+        .clone()
+}
+
+// unbonding_setup has alice unstake 100_000 of 1_000_000 delegated, which is undelegated at once
+fn unbonding_setup() -> MockDeps {
+    let mut deps = mock_dependencies();
+    setup(&mut deps);
+    mock_liquid_token(&mut deps, 1_000_000, &[]);
+    TOTAL_SUPPLY
+        .save(
+            deps.as_mut().storage,
+            &Supply {
+                native: Uint128::new(1_000_000),
+                ..Supply::default()
+            },
+        )
+        .unwrap();
+    set_delegation(&mut deps, 1_000_000, 0);
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: "alice".to_string(),
+        amount: Uint128::new(100_000),
+        msg: Default::default(),
+    });
+    execute(deps.as_mut(), env_at(0), mock_info(LIQUID_TOKEN, &[]), msg).unwrap();
+
+    let msgs = process_token(&mut deps, env_at(0));
+    assert_eq!(
+        msgs,
+        vec![StakingMsg::Undelegate {
+            validator: VALIDATOR.to_string(),
+            amount: coin(100_000, BOND_DENOM)
+        }
+        .into()]
+    );
+    deps
+}
+
+#[test]
+fn undelegation_is_queued_until_completion() {
+    let deps = unbonding_setup();
+    assert_eq!(
+        LEDGER.load(&deps.storage).unwrap().unbonding.u128(),
+        100_000
+    );
+    let (_, unbonding) = UNBONDINGS.front(&deps.storage).unwrap().unwrap();
+    assert_eq!(unbonding.amount.u128(), 100_000);
+    assert_eq!(unbonding.completion, env_at(UNBONDING_PERIOD).block.time);
+    assert_eq!(unbonding.height, env_at(0).block.height);
+}
+
+#[test]
+fn inflow_before_completion_is_not_booked_as_unbonding() {
+    let mut deps = unbonding_setup();
+    // tokens sent to the contract while the undelegation is pending don't pay the queue
+    set_balance(&mut deps, 100_000);
+    assert_eq!(process_queue(&mut deps, env_at(DAY)), "0");
+    assert_eq!(LEDGER.load(&deps.storage).unwrap().balance.u128(), 0);
+    assert_eq!(UNBONDINGS.len(&deps.storage).unwrap(), 1);
+
+    // once the undelegation completes they are booked and paid out
+    assert_eq!(process_queue(&mut deps, env_at(UNBONDING_PERIOD)), "100000");
+    let ledger = LEDGER.load(&deps.storage).unwrap();
+    assert_eq!(ledger.balance.u128(), 100_000);
+    assert!(ledger.unbonding.is_zero());
+    assert!(UNBONDINGS.is_empty(&deps.storage).unwrap());
+    assert_eq!(
+        CLAIMABLE
+            .load(&deps.storage, &Addr::unchecked("alice"))
+            .unwrap()
+            .u128(),
+        100_000
+    );
+}
+
+#[test]
+fn partly_returned_unbonding_stays_pending() {
+    let mut deps = unbonding_setup();
+    set_balance(&mut deps, 40_000);
+    assert_eq!(process_queue(&mut deps, env_at(UNBONDING_PERIOD)), "40000");
+    let ledger = LEDGER.load(&deps.storage).unwrap();
+    assert_eq!(ledger.unbonding.u128(), 60_000);
+    let (_, unbonding) = UNBONDINGS.front(&deps.storage).unwrap().unwrap();
+    assert_eq!(unbonding.amount.u128(), 60_000);
+}
+
+#[test]
+fn solvency_does_not_count_returned_unbonding_twice() {
+    let mut deps = unbonding_setup();
+    set_delegation(&mut deps, 900_000, 0);
+    let solvency = |deps: &MockDeps, env: Env| -> SolvencyResponse {
+        from_json(query(deps.as_ref(), env, QueryMsg::Solvency {}).unwrap()).unwrap()
+    };
+    let before = solvency(&deps, env_at(DAY));
+    assert_eq!(before.unbonding.u128(), 100_000);
+    assert_eq!(before.assets.u128(), 1_000_000);
+
+    // the undelegation completed but the queue was not processed yet
+    set_balance(&mut deps, 100_000);
+    let res = solvency(&deps, env_at(UNBONDING_PERIOD));
+    assert!(res.unbonding.is_zero());
+    assert_eq!(res.balance.u128(), 100_000);
+    assert_eq!(res.assets.u128(), 1_000_000);
+    assert_eq!(res.surplus, before.surplus);
+    assert_eq!(LEDGER.load(&deps.storage).unwrap().unbonding.u128(), 100_000);
+}
+
+#[test]
+fn sweep_keeps_pending_unbonding() {
+    let mut deps = unbonding_setup();
+    let sweep = || ExecuteMsg::SweepUnaccounted { recipient: None };
+    // a donation can't be told apart from an undelegation back early
+    set_balance(&mut deps, 5_000);
+    let err = execute(deps.as_mut(), env_at(DAY), mock_info(OWNER, &[]), sweep()).unwrap_err();
+    assert!(matches!(err, ContractError::NothingToSweep { .. }));
+
+    set_balance(&mut deps, 105_000);
+    let res = execute(
+        deps.as_mut(),
+        env_at(UNBONDING_PERIOD),
+        mock_info(OWNER, &[]),
+        sweep(),
+    )
+    .unwrap();
+    assert_eq!(
+        res.attributes
+            .iter()
+            .find(|attr| attr.key == "amount")
+            .unwrap()
+            .value,
+        "5000"
+    );
+    let ledger = LEDGER.load(&deps.storage).unwrap();
+    assert_eq!(ledger.balance.u128(), 100_000);
+    assert!(ledger.unbonding.is_zero());
+}
+
+fn cancel(deps: &mut MockDeps, env: Env, amount: u128) -> Vec<CosmosMsg<ArchwayMsg>> {
+    let (request_id, _) = UNSTAKING_QUEUE.front(&deps.storage).unwrap().unwrap();
+    let msg = ExecuteMsg::CancelUnstake { request_id, amount: Some(Uint128::new(amount)) };
+    let res = execute(deps.as_mut(), env, mock_info("alice", &[]), msg).unwrap();
+    res.messages.into_iter().map(|sub| sub.msg).collect()
+}
+
+#[test]
+fn cancel_delegates_the_undelegated_amount_again() {
+    let mut deps = unbonding_setup();
+    let msgs = cancel(&mut deps, env_at(DAY), 30_000);
+    let expected = cancel_unbonding_delegation(
+        MOCK_CONTRACT_ADDR,
+        VALIDATOR,
+        &coin(30_000, BOND_DENOM),
+        env_at(0).block.height,
+    );
+    assert!(msgs.contains(&expected));
+    assert_eq!(LEDGER.load(&deps.storage).unwrap().unbonding.u128(), 70_000);
+    let (_, unbonding) = UNBONDINGS.back(&deps.storage).unwrap().unwrap();
+    assert_eq!(unbonding.amount.u128(), 70_000);
+
+    cancel(&mut deps, env_at(DAY), 70_000);
+    assert!(LEDGER.load(&deps.storage).unwrap().unbonding.is_zero());
+    assert!(UNBONDINGS.is_empty(&deps.storage).unwrap());
+}
+
+#[test]
+fn cancel_books_the_rewards_it_withdraws() {
+    let mut deps = unbonding_setup();
+    set_delegation(&mut deps, 900_000, 500);
+    cancel(&mut deps, env_at(DAY), 30_000);
+    // the rewards are booked before the amount is minted back
+    let supply = TOTAL_SUPPLY.load(&deps.storage).unwrap();
+    assert_eq!(supply.native.u128(), 900_000 + 500 + 30_000);
+    assert_eq!(LEDGER.load(&deps.storage).unwrap().balance.u128(), 500);
+    let (_, snapshot) = RATIO_HISTORY
+        .range(&deps.storage, None, None, Order::Descending)
+        .next()
+        .unwrap()
+        .unwrap();
+    assert_eq!(snapshot.native.u128(), 900_500);
+}
+
+#[test]
+fn sweep_after_cancel_leaves_withdrawn_rewards() {
+    let mut deps = unbonding_setup();
+    set_delegation(&mut deps, 900_000, 500);
+    cancel(&mut deps, env_at(DAY), 100_000);
+    assert!(LEDGER.load(&deps.storage).unwrap().unbonding.is_zero());
+    // the cancel withdrew the pending rewards to the contract
+    set_balance(&mut deps, 500);
+    let msg = ExecuteMsg::SweepUnaccounted { recipient: None };
+    let err = execute(deps.as_mut(), env_at(DAY), mock_info(OWNER, &[]), msg).unwrap_err();
+    assert!(matches!(err, ContractError::NothingToSweep { .. }));
+}
+
+#[test]
+fn cancel_notifies_hooks_of_a_stake() {
+    let mut deps = unbonding_setup();
+    execute_admin(&mut deps, ExecuteMsg::AddHook { address: "hook".to_string(), revert_on_error: true });
+    let msgs = cancel(&mut deps, env_at(DAY), 30_000);
+    // the mocked liquid supply still holds the burnt tokens, 1_000_000 for 900_000 native
+    let expected = HookExecuteMsg::StakeHook {
+        address: "alice".to_string(),
+        amount: Uint128::new(30_000),
+        minted: Uint128::new(33_333),
+    };
+    assert!(msgs.contains(&expected.into_cosmos_msg("hook").unwrap()));
+}
+
+#[test]
+fn cancel_after_completion_leaves_the_unbonding_to_be_booked() {
+    let mut deps = unbonding_setup();
+    let msgs = cancel(&mut deps, env_at(UNBONDING_PERIOD), 100_000);
+    assert!(!msgs.iter().any(|msg| matches!(msg, CosmosMsg::Stargate { .. })));
+    assert_eq!(LEDGER.load(&deps.storage).unwrap().unbonding.u128(), 100_000);
+}
+
+#[test]
+fn cancel_is_closed_while_staking_is_paused() {
+    let mut deps = unbonding_setup();
+    let msg = ExecuteMsg::Pause { operation: Some(Operation::Stake) };
+    execute(deps.as_mut(), env_at(0), mock_info(OWNER, &[]), msg).unwrap();
+    let (request_id, _) = UNSTAKING_QUEUE.front(&deps.storage).unwrap().unwrap();
+    let msg = ExecuteMsg::CancelUnstake { request_id, amount: None };
+    let err = execute(deps.as_mut(), env_at(DAY), mock_info("alice", &[]), msg).unwrap_err();
+    assert!(matches!(err, ContractError::Paused { .. }));
+}
+
+#[test]
+fn cancel_into_a_drained_pool_fails() {
+    let mut deps = unbonding_setup();
+    TOTAL_SUPPLY
+        .update(deps.as_mut().storage, |supply| -> StdResult<_> { Ok(Supply { native: Uint128::zero(), ..supply }) })
+        .unwrap();
+    let (request_id, _) = UNSTAKING_QUEUE.front(&deps.storage).unwrap().unwrap();
+    let msg = ExecuteMsg::CancelUnstake { request_id, amount: None };
+    let err = execute(deps.as_mut(), env_at(DAY), mock_info("alice", &[]), msg).unwrap_err();
+    assert!(matches!(err, ContractError::DrainedPool { .. }));
+}
@@ -0,0 +1,54 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use cosmwasm_std::{Timestamp, Uint128};
+use cw20::Expiration;
+
+/// TicketExecuteMsg is the part of the cw721-base execute interface used for claim tickets
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum TicketExecuteMsg {
+    /// Mint a new ticket, this contract has to be the minter of the cw721 contract
+    Mint(TicketMintMsg),
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct TicketMintMsg {
+    /// id of the unstaking request
+    pub token_id: String,
+    /// address receiving the ticket
+    pub owner: String,
+    pub token_uri: Option<String>,
+    pub extension: TicketMetadata,
+}
+
+/// TicketMetadata describes the unstaking request behind a ticket
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct TicketMetadata {
+    /// native tokens requested
+    pub amount: Uint128,
+    /// block height of the unstake
+    pub height: u64,
+    /// estimated block time from which the request can be claimed
+    pub eta: Timestamp,
+}
+
+/// TicketQueryMsg is the part of the cw721 query interface used for claim tickets
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum TicketQueryMsg {
+    /// OwnerOf shows the owner of a ticket
+    OwnerOf { token_id: String, include_expired: Option<bool> },
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Approval {
+    pub spender: String,
+    pub expires: Expiration,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct OwnerOfResponse {
+    pub owner: String,
+    pub approvals: Vec<Approval>,
+}
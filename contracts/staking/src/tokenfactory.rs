@@ -0,0 +1,40 @@
+use cosmwasm_std::{Coin, CosmosMsg};
+
+use crate::proto::ProtoWriter;
+
+/// Type url of the token factory message creating a denom
+pub const CREATE_DENOM_TYPE_URL: &str = "/osmosis.tokenfactory.v1beta1.MsgCreateDenom";
+/// Type url of the token factory message minting tokens of a denom
+pub const MINT_TYPE_URL: &str = "/osmosis.tokenfactory.v1beta1.MsgMint";
+/// Type url of the token factory message burning tokens of a denom
+pub const BURN_TYPE_URL: &str = "/osmosis.tokenfactory.v1beta1.MsgBurn";
+
+/// create_denom returns the message creating the denom `factory/{sender}/{subdenom}`, administered by
+/// the sender
+pub fn create_denom<T>(sender: &str, subdenom: &str) -> CosmosMsg<T> {
+    let value = ProtoWriter::new()
+        .string(1, sender)
+        .string(2, subdenom)
+        .into_binary();
+    CosmosMsg::Stargate { type_url: CREATE_DENOM_TYPE_URL.to_string(), value }
+}
+
+/// mint returns the message minting tokens of a denom administered by the sender to `mint_to_address`
+pub fn mint<T>(sender: &str, amount: &Coin, mint_to_address: &str) -> CosmosMsg<T> {
+    let value = ProtoWriter::new()
+        .string(1, sender)
+        .coin(2, amount)
+        .string(3, mint_to_address)
+        .into_binary();
+    CosmosMsg::Stargate { type_url: MINT_TYPE_URL.to_string(), value }
+}
+
+/// burn returns the message burning tokens of a denom administered by the sender from `burn_from_address`
+pub fn burn<T>(sender: &str, amount: &Coin, burn_from_address: &str) -> CosmosMsg<T> {
+    let value = ProtoWriter::new()
+        .string(1, sender)
+        .coin(2, amount)
+        .string(3, burn_from_address)
+        .into_binary();
+    CosmosMsg::Stargate { type_url: BURN_TYPE_URL.to_string(), value }
+}
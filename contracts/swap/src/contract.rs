@@ -1,24 +1,37 @@
+use std::collections::BTreeMap;
+
 #[cfg(not(feature = "library"))]
 use cosmwasm_std::entry_point;
 use cosmwasm_std::{
-    coins, to_binary, Addr, BankMsg, Binary, Decimal, Deps, DepsMut, Env, MessageInfo, 
-    QueryRequest, WasmQuery, Response, StdError, StdResult, Uint128, 
+    coins, to_json_binary, Addr, BankMsg, Binary, Decimal, Deps, DepsMut, Empty, Env, MessageInfo, 
+    Order, QuerierWrapper, QueryRequest, WasmQuery, Response, StdError, StdResult, Storage, 
+    Timestamp, Uint128, 
 };
 
+use cw_storage_plus::Bound;
 use cw2::set_contract_version;
 use cw20::{BalanceResponse, Cw20Contract, Cw20ExecuteMsg, Cw20ReceiveMsg, 
     };
 
-use crate::linked_list::{LinkedList, NodeWithId, node_read, node_update_value, 
-    linked_list, linked_list_read, linked_list_append, linked_list_remove_head, 
-    linked_list_remove, linked_list_get_list};
 use crate::error::ContractError;
 use crate::msg::{ExecuteMsg, ConfigResponse, StatusResponse, InstantiateMsg, QueryMsg, 
     OrderInfoOfResponse, OrderBookResponse, StakingManagerQueryMsg, 
-    StakingManagerStatusResponse};
-use crate::state::{ConfigInfo, Supply, CONFIG, TOTAL_SUPPLY, CLAIMABLE, QUEUE_ID};
+    StakingManagerStatusResponse, RatioOracleResponse, Operation, PendingOwnerResponse,
+    PendingChangeWithId, PendingChangesResponse, Role, RoleMembersResponse, HasRoleResponse,
+    NodeWithId, VerifyQueueResponse};
+use crate::state::{ConfigInfo, Supply, Node, ORDER_BOOK, PausedInfo, PendingOwner, AdminChange, PendingChange, 
+    RatioObservation, CONFIG, PAUSED, PENDING_OWNER, PENDING_CHANGES, PENDING_CHANGES_NEXT_ID, 
+    DEFAULT_TIMELOCK_DELAY, MINIMUM_INITIAL_DEPOSIT, LOCKED_LP_SHARES, ROLES, TOTAL_SUPPLY, CLAIMABLE, QUEUE_ID, RATIO_OBSERVATIONS, 
+    RATIO_OBSERVATIONS_NEXT_ID, RATIO_OBSERVATIONS_CAPACITY};
 
 const FALLBACK_RATIO: Decimal = Decimal::one();
+const DEFAULT_TWAP_WINDOW: u64 = 30 * 60;
+
+// settings for pagination
+const DEFAULT_LIMIT: u32 = 10;
+const MAX_LIMIT: u32 = 50;
+// number of orders walked by VerifyQueue by default
+const DEFAULT_VERIFY_LIMIT: u32 = 1000;
 
 // version info for migration info
 const CONTRACT_NAME: &str = "crates.io:liquid-swap";
@@ -33,22 +46,19 @@ pub fn instantiate(
 ) -> Result<Response, ContractError> {
     set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
 
-    let linked_list_init = LinkedList {
-        head_id: 0,
-        tail_id: 0,
-        length: 0
-    };
-    linked_list(deps.storage).save(&linked_list_init)?;
-
     let denom = deps.querier.query_bonded_denom()?;
     let config_init = ConfigInfo {
-        owner: info.sender,
+        owner: Some(info.sender),
         bond_denom: denom,
         liquid_token_addr: deps.api.addr_validate(&msg.liquid_token_addr)?,
         staking_manager_addr: deps.api.addr_validate(&msg.staking_manager_addr)?,
         swap_fee: Uint128::from(100u32),
+        twap_window: DEFAULT_TWAP_WINDOW,
+        max_ratio_deviation: Decimal::percent(5),
+        timelock_delay: DEFAULT_TIMELOCK_DELAY,
     };
     CONFIG.save(deps.storage, &config_init)?;
+    PAUSED.save(deps.storage, &PausedInfo::default())?;
 
     // set supply to 0
     let supply_init = Supply::default();
@@ -68,7 +78,24 @@ pub fn execute(
         ExecuteMsg::Add {} => execute_add(deps, env, info),
         ExecuteMsg::Remove {} => execute_remove(deps, env, info),
         ExecuteMsg::Claim {} => execute_claim(deps, info),
-        ExecuteMsg::SetSwapFee { swap_fee } => execute_set_swap_fee(deps, info, swap_fee),
+        ExecuteMsg::SetSwapFee { swap_fee } => execute_set_swap_fee(deps, env, info, swap_fee),
+        ExecuteMsg::SetRatioOracle { twap_window, max_ratio_deviation } => {
+            execute_set_ratio_oracle(deps, env, info, twap_window, max_ratio_deviation)
+        },
+        ExecuteMsg::UpdateRatioOracle {} => execute_update_ratio_oracle(deps, env),
+        ExecuteMsg::SetTimelockDelay { delay } => execute_set_timelock_delay(deps, env, info, delay),
+        ExecuteMsg::SweepUnaccounted { recipient } => execute_sweep_unaccounted(deps, env, info, recipient),
+        ExecuteMsg::RepairQueue {} => execute_repair_queue(deps, info),
+        ExecuteMsg::ExecutePendingChange { id } => execute_pending_change(deps, env, info, id),
+        ExecuteMsg::CancelPendingChange { id } => execute_cancel_pending_change(deps, info, id),
+        ExecuteMsg::ProposeNewOwner { owner, expiry } => execute_propose_new_owner(deps, info, owner, expiry),
+        ExecuteMsg::AcceptOwnership {} => execute_accept_ownership(deps, env, info),
+        ExecuteMsg::CancelOwnershipProposal {} => execute_cancel_ownership_proposal(deps, info),
+        ExecuteMsg::RenounceOwnership {} => execute_renounce_ownership(deps, info),
+        ExecuteMsg::GrantRole { role, address } => execute_grant_role(deps, info, role, address),
+        ExecuteMsg::RevokeRole { role, address } => execute_revoke_role(deps, info, role, address),
+        ExecuteMsg::Pause { operation } => execute_pause(deps, info, operation),
+        ExecuteMsg::Unpause { operation } => execute_unpause(deps, info, operation),
         ExecuteMsg::Receive(msg) => execute_receive(deps, env, info, msg),
     }
 }
@@ -81,7 +108,143 @@ fn get_ratio(numerator: Uint128, denominator: Uint128) -> Decimal {
     }
 }
 
+// has_role tells whether the address holds the role, the owner holds every role
+fn has_role(storage: &dyn Storage, address: &Addr, role: Role) -> StdResult<bool> {
+    let config = CONFIG.load(storage)?;
+    if config.owner.as_ref() == Some(address) {
+        return Ok(true);
+    }
+    match role {
+        Role::Owner => Ok(false),
+        _ => Ok(ROLES.may_load(storage, (role.as_str(), address))?.is_some()),
+    }
+}
+
+// ensure_has_role fails unless the address holds the role
+fn ensure_has_role(storage: &dyn Storage, address: &Addr, role: Role) -> Result<(), ContractError> {
+    if !has_role(storage, address, role)? {
+        return Err(ContractError::Unauthorized {});
+    }
+    Ok(())
+}
+
+// ensure_not_paused fails if the given operation is halted
+fn ensure_not_paused(storage: &dyn Storage, operation: Operation) -> Result<(), ContractError> {
+    let paused = PAUSED.load(storage)?;
+    let is_paused = match operation {
+        Operation::Add => paused.add,
+        Operation::Remove => paused.remove,
+        Operation::Swap => paused.swap,
+        Operation::Claim => paused.claim,
+    };
+    if is_paused {
+        return Err(ContractError::Paused { operation: operation.to_string() });
+    }
+    Ok(())
+}
+
+// set_paused flags one operation, or all of them when none is given
+fn set_paused(paused: &mut PausedInfo, operation: Option<Operation>, value: bool) {
+    match operation {
+        Some(Operation::Add) => paused.add = value,
+        Some(Operation::Remove) => paused.remove = value,
+        Some(Operation::Swap) => paused.swap = value,
+        Some(Operation::Claim) => paused.claim = value,
+        None => {
+            paused.add = value;
+            paused.remove = value;
+            paused.swap = value;
+            paused.claim = value;
+        },
+    }
+}
+
+// query_staking_ratio returns the spot native / liquid ratio of the staking manager
+fn query_staking_ratio(querier: &QuerierWrapper, staking_manager_addr: &Addr) -> StdResult<Decimal> {
+    let staking_query_msg: StakingManagerQueryMsg = StakingManagerQueryMsg::StatusInfo {};
+    let staking_query_response: StakingManagerStatusResponse =
+       querier.query(&QueryRequest::Wasm(WasmQuery::Smart {
+            contract_addr: staking_manager_addr.to_string(),
+            msg: to_json_binary(&staking_query_msg)?,
+    }))?;
+    Ok(staking_query_response.ratio)
+}
+
+// accumulated carries the ratio accumulator of an observation forward to `time`
+fn accumulated(observation: &RatioObservation, time: u64) -> Decimal {
+    let elapsed = time.saturating_sub(observation.time.seconds());
+    observation.cumulative + observation.ratio * Decimal::from_ratio(elapsed, 1u64)
+}
+
+// record_ratio_observation updates the ratio accumulator with the spot ratio,
+// only the first observation of a block is kept so the ratio can't be moved within a block
+fn record_ratio_observation(storage: &mut dyn Storage, time: Timestamp, ratio: Decimal) -> StdResult<()> {
+    let id = RATIO_OBSERVATIONS_NEXT_ID.may_load(storage)?.unwrap_or_default();
+    let cumulative = if id == 0 {
+        Decimal::zero()
+    } else {
+        let last = RATIO_OBSERVATIONS.load(storage, id - 1)?;
+        if last.time >= time {
+            return Ok(());
+        }
+        accumulated(&last, time.seconds())
+    };
+    RATIO_OBSERVATIONS.save(storage, id, &RatioObservation { time, ratio, cumulative })?;
+    if id >= RATIO_OBSERVATIONS_CAPACITY {
+        RATIO_OBSERVATIONS.remove(storage, id - RATIO_OBSERVATIONS_CAPACITY);
+    }
+    RATIO_OBSERVATIONS_NEXT_ID.save(storage, &(id + 1))?;
+    Ok(())
+}
+
+// get_twap_ratio returns the time-weighted average ratio over the window ending at `now`
+// and the number of seconds it covers, falling back to spot when nothing was observed
+fn get_twap_ratio(storage: &dyn Storage, now: u64, window: u64, spot: Decimal) -> StdResult<(Decimal, u64)> {
+    if window == 0 {
+        return Ok((spot, 0));
+    }
+    let window_start = now.saturating_sub(window);
+    let mut latest: Option<RatioObservation> = None;
+    let mut start: Option<RatioObservation> = None;
+    for item in RATIO_OBSERVATIONS.range(storage, None, None, Order::Descending) {
+        let (_, observation) = item?;
+        if latest.is_none() {
+            latest = Some(observation.clone());
+        }
+        let before_window = observation.time.seconds() <= window_start;
+        start = Some(observation);
+        if before_window {
+            break;
+        }
+    }
+    let (latest, start) = match (latest, start) {
+        (Some(latest), Some(start)) => (latest, start),
+        _ => return Ok((spot, 0)),
+    };
+    let from = start.time.seconds().max(window_start);
+    let observed_seconds = now.saturating_sub(from);
+    if observed_seconds == 0 {
+        return Ok((latest.ratio, 0));
+    }
+    let twap = (accumulated(&latest, now) - accumulated(&start, from))
+        / Decimal::from_ratio(observed_seconds, 1u64);
+    Ok((twap, observed_seconds))
+}
+
+// check_ratio_deviation ensures the spot ratio is within the accepted distance of the TWAP ratio
+fn check_ratio_deviation(spot: Decimal, twap: Decimal, max_deviation: Decimal) -> Result<(), ContractError> {
+    if twap.is_zero() {
+        return Ok(());
+    }
+    let diff = if spot > twap { spot - twap } else { twap - spot };
+    if diff / twap > max_deviation {
+        return Err(ContractError::RatioDeviation { spot, twap });
+    }
+    Ok(())
+}
+
 pub fn execute_add(deps: DepsMut, env: Env, info: MessageInfo) -> Result<Response, ContractError> {
+    ensure_not_paused(deps.storage, Operation::Add)?;
     // ensure we have the proper denom
     let config = CONFIG.load(deps.storage)?;
     // payment finds the proper coin (or throws an error)
@@ -92,33 +255,44 @@ pub fn execute_add(deps: DepsMut, env: Env, info: MessageInfo) -> Result<Respons
         .ok_or_else(|| ContractError::EmptyBalance {
             denom: config.bond_denom.clone(),
         })?;
-    let balance = deps
-        .querier
-        .query_balance(&env.contract.address, &config.bond_denom)?;
     let mut supply = TOTAL_SUPPLY.load(deps.storage)?;
-    let mut cur_native = balance.amount.checked_sub(payment.amount).map_err(StdError::overflow)?;
+    // shares without backing can't be priced, adding 1:1 would hand the deposit to them
+    if supply.native.is_zero() && !supply.issued.is_zero() {
+        return Err(ContractError::DrainedPool { issued: supply.issued });
+    }
     let mut res = Response::new();
-    // withdraw all native token from contract when there is no lp token in the pool
-    if !cur_native.is_zero() && supply.issued.is_zero() {
-        res = res.add_message(BankMsg::Send {
-            to_address: config.owner.to_string(),
-            amount: coins(cur_native.u128(), config.bond_denom),
-        });
-        cur_native = Uint128::zero();
-    }
-    let mut new_node_value = payment.amount * get_ratio(supply.issued, cur_native);
+    let mut new_node_value = payment.amount * get_ratio(supply.issued, supply.native);
+    // first deposit: lock part of the lp shares in the pool for good
+    if supply.issued.is_zero() {
+        if payment.amount < Uint128::new(MINIMUM_INITIAL_DEPOSIT) {
+            return Err(ContractError::InsufficientInitialDeposit { min: Uint128::new(MINIMUM_INITIAL_DEPOSIT) });
+        }
+        let locked = Uint128::new(LOCKED_LP_SHARES);
+        new_node_value = new_node_value.checked_sub(locked).map_err(StdError::overflow)?;
+        supply.issued += locked;
+        supply.locked += locked;
+        res = res.add_attribute("locked", locked);
+    }
+    if new_node_value.is_zero() {
+        return Err(ContractError::ZeroShares {});
+    }
     // update supply info
     supply.issued += new_node_value;
+    supply.native += payment.amount;
     TOTAL_SUPPLY.save(deps.storage, &supply)?;
     // update node id of user in the queue
     let old_node_id = QUEUE_ID.may_load(deps.storage, &info.sender)?.unwrap_or_default();
     if old_node_id > 0 {
-        let old_node_key = &old_node_id.to_be_bytes();
-        let old_node = node_read(deps.storage).load(old_node_key)?;
-        new_node_value += old_node.value;
-        linked_list_remove(deps.storage, old_node_id)?;
+        if let Some(old_node) = ORDER_BOOK.remove(deps.storage, old_node_id)? {
+            new_node_value += old_node.value;
+        }
     }
-    let new_node_id = linked_list_append(deps.storage, info.sender.clone(), new_node_value, env.block.height)?;
+    let new_node = Node {
+        receiver: info.sender.clone(),
+        value: new_node_value,
+        height: env.block.height,
+    };
+    let new_node_id = ORDER_BOOK.push_back(deps.storage, new_node)?;
     QUEUE_ID.save(deps.storage, &info.sender, &new_node_id)?;
     
     res = res.add_attribute("action", "add")
@@ -127,7 +301,8 @@ pub fn execute_add(deps: DepsMut, env: Env, info: MessageInfo) -> Result<Respons
     Ok(res)
 }
 
-pub fn execute_remove(deps: DepsMut, env: Env, info: MessageInfo) -> Result<Response, ContractError> {
+pub fn execute_remove(deps: DepsMut, _env: Env, info: MessageInfo) -> Result<Response, ContractError> {
+    ensure_not_paused(deps.storage, Operation::Remove)?;
     // ensure we have the proper denom
     let config = CONFIG.load(deps.storage)?;
 
@@ -136,16 +311,14 @@ pub fn execute_remove(deps: DepsMut, env: Env, info: MessageInfo) -> Result<Resp
         return Err(ContractError::NothingToRemove {});
     }
 
-    let node_key = &node_id.to_be_bytes();
-    let cur_node = node_read(deps.storage).load(node_key)?;
-    linked_list_remove(deps.storage, node_id)?;
+    let cur_node = ORDER_BOOK
+        .remove(deps.storage, node_id)?
+        .ok_or(ContractError::NothingToRemove {})?;
     QUEUE_ID.save(deps.storage, &info.sender, &0)?;
-    let balance = deps
-        .querier
-        .query_balance(&env.contract.address, &config.bond_denom)?;
     let mut supply = TOTAL_SUPPLY.load(deps.storage)?;
-    let native_amount = cur_node.value.multiply_ratio(balance.amount, supply.issued);
+    let native_amount = cur_node.value.multiply_ratio(supply.native, supply.issued);
     supply.issued = supply.issued.checked_sub(cur_node.value).map_err(StdError::overflow)?;
+    supply.native = supply.native.checked_sub(native_amount).map_err(StdError::overflow)?;
     TOTAL_SUPPLY.save(deps.storage, &supply)?;
 
     // transfer tokens to the sender
@@ -165,6 +338,7 @@ pub fn execute_claim(
     deps: DepsMut,
     info: MessageInfo,
 ) -> Result<Response, ContractError> {
+    ensure_not_paused(deps.storage, Operation::Claim)?;
     let config = CONFIG.load(deps.storage)?;
     let to_send = CLAIMABLE.may_load(deps.storage, &info.sender)?.unwrap_or_default();
     if to_send == Uint128::zero() {
@@ -221,28 +395,27 @@ pub fn execute_swap(
     sender: Addr,
     amount: Uint128,
 ) -> Result<Response, ContractError> {
-    let contract_addr = env.contract.address;
+    ensure_not_paused(deps.storage, Operation::Swap)?;
     let config = CONFIG.load(deps.storage)?;
 
     let swap_fee = amount.multiply_ratio(config.swap_fee, 10000u128);
     let order_liquid_token_value = amount.checked_sub(swap_fee).map_err(StdError::overflow)?;
-    // get liquid -> native ratio
-    let staking_query_msg: StakingManagerQueryMsg = StakingManagerQueryMsg::StatusInfo {};
-    let staking_query_response: StakingManagerStatusResponse =
-       deps.querier.query(&QueryRequest::Wasm(WasmQuery::Smart {
-            contract_addr: config.staking_manager_addr.to_string(),
-            msg: to_binary(&staking_query_msg)?,
-    }))?;
-    let order_native_value = order_liquid_token_value * staking_query_response.ratio;
-    let balance = deps
-        .querier
-        .query_balance(contract_addr, &config.bond_denom)?;
+    // get liquid -> native ratio, averaged over the window to resist same-block moves
+    let spot_ratio = query_staking_ratio(&deps.querier, &config.staking_manager_addr)?;
+    let (twap_ratio, _) = get_twap_ratio(deps.storage, env.block.time.seconds(), config.twap_window, spot_ratio)?;
+    check_ratio_deviation(spot_ratio, twap_ratio, config.max_ratio_deviation)?;
+    record_ratio_observation(deps.storage, env.block.time, spot_ratio)?;
+    let order_native_value = order_liquid_token_value * twap_ratio;
     let mut supply = TOTAL_SUPPLY.load(deps.storage)?;
-    let order_lp_token_value = order_native_value * get_ratio(supply.issued, balance.amount);
-    if order_lp_token_value > supply.issued {
+    let order_lp_token_value = order_native_value * get_ratio(supply.issued, supply.native);
+    // locked shares are not in the queue and can't fill orders
+    let fillable = supply.issued.checked_sub(supply.locked).map_err(StdError::overflow)?;
+    // the pool keeps some native tokens so its locked shares stay priced
+    if order_lp_token_value > fillable || order_native_value >= supply.native {
         return Err(ContractError::InsufficientLiquidity {});
     }
     supply.issued = supply.issued.checked_sub(order_lp_token_value).map_err(StdError::overflow)?;
+    supply.native = supply.native.checked_sub(order_native_value).map_err(StdError::overflow)?;
     supply.claims += amount;
     TOTAL_SUPPLY.save(deps.storage, &supply)?;
     
@@ -250,11 +423,10 @@ pub fn execute_swap(
     let mut remain_lp_token = order_lp_token_value;
     while !is_filled {
         // Get next order from the queue
-        let linked_list_info = linked_list_read(deps.storage).load()?;
-        let counterparty_id = linked_list_info.head_id;
-        let counterparty_key = &counterparty_id.to_be_bytes();
-        let counterparty_order = node_read(deps.storage).load(counterparty_key)?;
-        let counterparty_address = counterparty_order.receiver;
+        let (counterparty_id, counterparty_order) = ORDER_BOOK
+            .front(deps.storage)?
+            .ok_or(ContractError::InsufficientLiquidity {})?;
+        let counterparty_address = counterparty_order.receiver.clone();
         let counterparty_lp_amount = counterparty_order.value;
         let mut counterparty_filled = false;
         // Perform match. Matched amount is up to order size
@@ -273,12 +445,11 @@ pub fn execute_swap(
         )?;
         
         if counterparty_filled {
-            linked_list_remove_head(deps.storage)?;
+            ORDER_BOOK.pop_front(deps.storage)?;
             QUEUE_ID.save(deps.storage, &counterparty_address, &0)?;
         } else {
             let new_counterparty_value = counterparty_order.value.checked_sub(matched_lp).map_err(StdError::overflow)?;
-            // counterparty_order.value = new_counterparty_value;
-            node_update_value(deps.storage, counterparty_id, new_counterparty_value)?;
+            ORDER_BOOK.save(deps.storage, counterparty_id, Node { value: new_counterparty_value, ..counterparty_order })?;
         }
         // If no more remaining lp token, the order is fully filled
         if remain_lp_token == Uint128::zero() {
@@ -294,27 +465,358 @@ pub fn execute_swap(
         })
         .add_attribute("action", "swap")
         .add_attribute("from", sender)
-        .add_attribute("amount", order_native_value);
+        .add_attribute("amount", order_native_value)
+        .add_attribute("ratio", twap_ratio.to_string());
+    Ok(res)
+}
+
+pub fn execute_sweep_unaccounted(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    recipient: Option<String>,
+) -> Result<Response, ContractError> {
+    ensure_has_role(deps.storage, &info.sender, Role::Owner)?;
+    let config = CONFIG.load(deps.storage)?;
+    let recipient = match recipient {
+        Some(recipient) => deps.api.addr_validate(&recipient)?,
+        None => info.sender.clone(),
+    };
+    let balance = deps
+        .querier
+        .query_balance(&env.contract.address, &config.bond_denom)?;
+    let supply = TOTAL_SUPPLY.load(deps.storage)?;
+    let unaccounted = balance.amount.saturating_sub(supply.native);
+    if unaccounted.is_zero() {
+        return Err(ContractError::NothingToSweep { denom: config.bond_denom });
+    }
+
+    let res = Response::new()
+        .add_message(BankMsg::Send {
+            to_address: recipient.to_string(),
+            amount: coins(unaccounted.u128(), config.bond_denom),
+        })
+        .add_attribute("action", "sweepUnaccounted")
+        .add_attribute("from", info.sender)
+        .add_attribute("to", recipient)
+        .add_attribute("amount", unaccounted);
+    Ok(res)
+}
+
+pub fn execute_repair_queue(deps: DepsMut, info: MessageInfo) -> Result<Response, ContractError> {
+    ensure_has_role(deps.storage, &info.sender, Role::Owner)?;
+    let state = ORDER_BOOK.repair(deps.storage)?;
+
+    let res = Response::new()
+        .add_attribute("action", "repairQueue")
+        .add_attribute("from", info.sender)
+        .add_attribute("length", state.length.to_string())
+        .add_attribute("head_id", state.head_id.to_string())
+        .add_attribute("tail_id", state.tail_id.to_string());
     Ok(res)
 }
 
 pub fn execute_set_swap_fee(
     deps: DepsMut,
+    env: Env,
     info: MessageInfo,
     swap_fee: Uint128,
 ) -> Result<Response, ContractError> {
+    ensure_has_role(deps.storage, &info.sender, Role::Owner)?;
+    let config = CONFIG.load(deps.storage)?;
+    schedule_change(deps, env, info.sender, AdminChange::SetSwapFee { swap_fee }, config.timelock_delay)
+}
+
+pub fn execute_set_ratio_oracle(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    twap_window: u64,
+    max_ratio_deviation: Decimal,
+) -> Result<Response, ContractError> {
+    ensure_has_role(deps.storage, &info.sender, Role::Owner)?;
+    // no deviation would halt swaps, a full one would let any spot ratio through
+    if max_ratio_deviation.is_zero() || max_ratio_deviation >= Decimal::one() {
+        return Err(ContractError::InvalidRatioDeviation { max_ratio_deviation });
+    }
+    let config = CONFIG.load(deps.storage)?;
+    let change = AdminChange::SetRatioOracle { twap_window, max_ratio_deviation };
+    schedule_change(deps, env, info.sender, change, config.timelock_delay)
+}
+
+// apply_change performs a queued admin change
+fn apply_change(deps: DepsMut, change: AdminChange) -> Result<Response, ContractError> {
     let mut config = CONFIG.load(deps.storage)?;
-    // only allow liquid token contract to call 
-    if info.sender != config.owner {
+    let res = match change {
+        AdminChange::SetSwapFee { swap_fee } => {
+            config.swap_fee = swap_fee;
+            Response::new()
+                .add_attribute("action", "setSwapFee")
+                .add_attribute("amount", swap_fee)
+        },
+        AdminChange::SetRatioOracle { twap_window, max_ratio_deviation } => {
+            config.twap_window = twap_window;
+            config.max_ratio_deviation = max_ratio_deviation;
+            Response::new()
+                .add_attribute("action", "setRatioOracle")
+                .add_attribute("twap_window", twap_window.to_string())
+                .add_attribute("max_ratio_deviation", max_ratio_deviation.to_string())
+        },
+        AdminChange::SetTimelockDelay { delay } => {
+            config.timelock_delay = delay;
+            Response::new()
+                .add_attribute("action", "setTimelockDelay")
+                .add_attribute("delay", delay.to_string())
+        },
+    };
+    CONFIG.save(deps.storage, &config)?;
+    Ok(res)
+}
+
+pub fn execute_update_ratio_oracle(deps: DepsMut, env: Env) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    let spot_ratio = query_staking_ratio(&deps.querier, &config.staking_manager_addr)?;
+    record_ratio_observation(deps.storage, env.block.time, spot_ratio)?;
+
+    let res = Response::new()
+        .add_attribute("action", "updateRatioOracle")
+        .add_attribute("ratio", spot_ratio.to_string());
+    Ok(res)
+}
+
+// schedule_change queues an admin change behind the timelock, or applies it at once without delay
+fn schedule_change(
+    deps: DepsMut,
+    env: Env,
+    sender: Addr,
+    change: AdminChange,
+    delay: u64,
+) -> Result<Response, ContractError> {
+    if delay == 0 {
+        return Ok(apply_change(deps, change)?.add_attribute("from", sender));
+    }
+    let id = PENDING_CHANGES_NEXT_ID.may_load(deps.storage)?.unwrap_or_default();
+    let eta = env.block.time.plus_seconds(delay);
+    PENDING_CHANGES.save(deps.storage, id, &PendingChange { change, eta })?;
+    PENDING_CHANGES_NEXT_ID.save(deps.storage, &(id + 1))?;
+
+    let res = Response::new()
+        .add_attribute("action", "queueChange")
+        .add_attribute("from", sender)
+        .add_attribute("change_id", id.to_string())
+        .add_attribute("eta", eta.seconds().to_string());
+    Ok(res)
+}
+
+pub fn execute_set_timelock_delay(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    delay: u64,
+) -> Result<Response, ContractError> {
+    ensure_has_role(deps.storage, &info.sender, Role::Owner)?;
+    let config = CONFIG.load(deps.storage)?;
+    schedule_change(deps, env, info.sender, AdminChange::SetTimelockDelay { delay }, config.timelock_delay)
+}
+
+pub fn execute_pending_change(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    id: u64,
+) -> Result<Response, ContractError> {
+    ensure_has_role(deps.storage, &info.sender, Role::Owner)?;
+    let pending = PENDING_CHANGES
+        .may_load(deps.storage, id)?
+        .ok_or(ContractError::PendingChangeNotFound { id })?;
+    if env.block.time < pending.eta {
+        return Err(ContractError::TimelockNotExpired { id, eta: pending.eta.seconds() });
+    }
+    PENDING_CHANGES.remove(deps.storage, id);
+
+    let res = apply_change(deps, pending.change)?
+        .add_attribute("from", info.sender)
+        .add_attribute("change_id", id.to_string());
+    Ok(res)
+}
+
+pub fn execute_cancel_pending_change(
+    deps: DepsMut,
+    info: MessageInfo,
+    id: u64,
+) -> Result<Response, ContractError> {
+    ensure_has_role(deps.storage, &info.sender, Role::Guardian)?;
+    if PENDING_CHANGES.may_load(deps.storage, id)?.is_none() {
+        return Err(ContractError::PendingChangeNotFound { id });
+    }
+    PENDING_CHANGES.remove(deps.storage, id);
+
+    let res = Response::new()
+        .add_attribute("action", "cancelChange")
+        .add_attribute("from", info.sender)
+        .add_attribute("change_id", id.to_string());
+    Ok(res)
+}
+
+pub fn execute_propose_new_owner(
+    deps: DepsMut,
+    info: MessageInfo,
+    owner: String,
+    expiry: Option<u64>,
+) -> Result<Response, ContractError> {
+    ensure_has_role(deps.storage, &info.sender, Role::Owner)?;
+    let pending = PendingOwner {
+        owner: deps.api.addr_validate(&owner)?,
+        expiry: expiry.map(Timestamp::from_seconds),
+    };
+    PENDING_OWNER.save(deps.storage, &pending)?;
+
+    let res = Response::new()
+        .add_attribute("action", "proposeNewOwner")
+        .add_attribute("from", info.sender)
+        .add_attribute("owner", pending.owner)
+        .add_attribute("expiry", expiry.map(|e| e.to_string()).unwrap_or_else(|| "none".to_string()));
+    Ok(res)
+}
+
+pub fn execute_accept_ownership(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+) -> Result<Response, ContractError> {
+    let pending = PENDING_OWNER
+        .may_load(deps.storage)?
+        .ok_or(ContractError::NoPendingOwner {})?;
+    // only allow proposed owner to call
+    if info.sender != pending.owner {
         return Err(ContractError::Unauthorized {});
     }
-    config.swap_fee = swap_fee.clone();
+    if let Some(expiry) = pending.expiry {
+        if env.block.time >= expiry {
+            return Err(ContractError::OwnershipProposalExpired {});
+        }
+    }
+    let mut config = CONFIG.load(deps.storage)?;
+    let previous_owner = config.owner.map(String::from).unwrap_or_else(|| "none".to_string());
+    config.owner = Some(pending.owner);
+    CONFIG.save(deps.storage, &config)?;
+    PENDING_OWNER.remove(deps.storage);
+
+    let res = Response::new()
+        .add_attribute("action", "acceptOwnership")
+        .add_attribute("from", info.sender)
+        .add_attribute("previous_owner", previous_owner);
+    Ok(res)
+}
+
+pub fn execute_cancel_ownership_proposal(
+    deps: DepsMut,
+    info: MessageInfo,
+) -> Result<Response, ContractError> {
+    ensure_has_role(deps.storage, &info.sender, Role::Owner)?;
+    if PENDING_OWNER.may_load(deps.storage)?.is_none() {
+        return Err(ContractError::NoPendingOwner {});
+    }
+    PENDING_OWNER.remove(deps.storage);
+
+    let res = Response::new()
+        .add_attribute("action", "cancelOwnershipProposal")
+        .add_attribute("from", info.sender);
+    Ok(res)
+}
+
+pub fn execute_renounce_ownership(
+    deps: DepsMut,
+    info: MessageInfo,
+) -> Result<Response, ContractError> {
+    ensure_has_role(deps.storage, &info.sender, Role::Owner)?;
+    let mut config = CONFIG.load(deps.storage)?;
+    config.owner = None;
     CONFIG.save(deps.storage, &config)?;
+    PENDING_OWNER.remove(deps.storage);
+
+    let res = Response::new()
+        .add_attribute("action", "renounceOwnership")
+        .add_attribute("from", info.sender);
+    Ok(res)
+}
+
+pub fn execute_grant_role(
+    deps: DepsMut,
+    info: MessageInfo,
+    role: Role,
+    address: String,
+) -> Result<Response, ContractError> {
+    ensure_has_role(deps.storage, &info.sender, Role::Owner)?;
+    if role == Role::Owner {
+        return Err(ContractError::OwnerRoleNotGrantable {});
+    }
+    let address = deps.api.addr_validate(&address)?;
+    ROLES.save(deps.storage, (role.as_str(), &address), &Empty {})?;
 
     let res = Response::new()
-        .add_attribute("action", "setSwapFee")
+        .add_attribute("action", "grantRole")
         .add_attribute("from", info.sender)
-        .add_attribute("amount", swap_fee);
+        .add_attribute("role", role.as_str())
+        .add_attribute("address", address);
+    Ok(res)
+}
+
+pub fn execute_revoke_role(
+    deps: DepsMut,
+    info: MessageInfo,
+    role: Role,
+    address: String,
+) -> Result<Response, ContractError> {
+    ensure_has_role(deps.storage, &info.sender, Role::Owner)?;
+    if role == Role::Owner {
+        return Err(ContractError::OwnerRoleNotGrantable {});
+    }
+    let address = deps.api.addr_validate(&address)?;
+    ROLES.remove(deps.storage, (role.as_str(), &address));
+
+    let res = Response::new()
+        .add_attribute("action", "revokeRole")
+        .add_attribute("from", info.sender)
+        .add_attribute("role", role.as_str())
+        .add_attribute("address", address);
+    Ok(res)
+}
+
+pub fn execute_pause(
+    deps: DepsMut,
+    info: MessageInfo,
+    operation: Option<Operation>,
+) -> Result<Response, ContractError> {
+    ensure_has_role(deps.storage, &info.sender, Role::Guardian)?;
+    let mut paused = PAUSED.load(deps.storage)?;
+    set_paused(&mut paused, operation, true);
+    PAUSED.save(deps.storage, &paused)?;
+
+    let res = Response::new()
+        .add_attribute("action", "pause")
+        .add_attribute("from", info.sender)
+        .add_attribute("operation", operation.map(|op| op.to_string()).unwrap_or_else(|| "all".to_string()));
+    Ok(res)
+}
+
+pub fn execute_unpause(
+    deps: DepsMut,
+    info: MessageInfo,
+    operation: Option<Operation>,
+) -> Result<Response, ContractError> {
+    // once ownership is renounced the guardian unpauses, so a pause can't last for good
+    let config = CONFIG.load(deps.storage)?;
+    let role = if config.owner.is_some() { Role::Owner } else { Role::Guardian };
+    ensure_has_role(deps.storage, &info.sender, role)?;
+    let mut paused = PAUSED.load(deps.storage)?;
+    set_paused(&mut paused, operation, false);
+    PAUSED.save(deps.storage, &paused)?;
+
+    let res = Response::new()
+        .add_attribute("action", "unpause")
+        .add_attribute("from", info.sender)
+        .add_attribute("operation", operation.map(|op| op.to_string()).unwrap_or_else(|| "all".to_string()));
     Ok(res)
 }
 
@@ -322,14 +824,24 @@ pub fn execute_set_swap_fee(
 pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
     match msg {
         QueryMsg::ClaimableOf { address } => {
-            to_binary(&query_claimable_of(deps, address)?)
+            to_json_binary(&query_claimable_of(deps, address)?)
+        },
+        QueryMsg::ConfigInfo {} => to_json_binary(&query_config(deps)?),
+        QueryMsg::PendingOwner {} => to_json_binary(&query_pending_owner(deps)?),
+        QueryMsg::RoleMembers { role, start_after, limit } => {
+            to_json_binary(&query_role_members(deps, role, start_after, limit)?)
         },
-        QueryMsg::ConfigInfo {} => to_binary(&query_config(deps)?),
-        QueryMsg::StatusInfo {} => to_binary(&query_status(deps, _env)?),
-        QueryMsg::OrderBook {} => to_binary(&query_order_book(deps)?),
+        QueryMsg::HasRole { role, address } => to_json_binary(&query_has_role(deps, role, address)?),
+        QueryMsg::PendingChanges { start_after, limit } => {
+            to_json_binary(&query_pending_changes(deps, start_after, limit)?)
+        },
+        QueryMsg::StatusInfo {} => to_json_binary(&query_status(deps, _env)?),
+        QueryMsg::OrderBook {} => to_json_binary(&query_order_book(deps)?),
         QueryMsg::OrderInfoOf { address } => {
-            to_binary(&query_order_info_of(deps, _env, address)?)
+            to_json_binary(&query_order_info_of(deps, _env, address)?)
         },
+        QueryMsg::RatioOracle {} => to_json_binary(&query_ratio_oracle(deps, _env)?),
+        QueryMsg::VerifyQueue { limit } => to_json_binary(&query_verify_queue(deps, limit)?),
     }
 }
 
@@ -345,11 +857,15 @@ pub fn query_config(deps: Deps) -> StdResult<ConfigResponse> {
     let config = CONFIG.load(deps.storage)?;
 
     let res = ConfigResponse {
-        owner: config.owner.to_string(),
+        owner: config.owner.map(String::from),
         bond_denom: config.bond_denom,
         liquid_token_addr: config.liquid_token_addr.to_string(),
         staking_manager_addr: config.staking_manager_addr.to_string(),
         swap_fee: config.swap_fee,
+        twap_window: config.twap_window,
+        max_ratio_deviation: config.max_ratio_deviation,
+        paused: PAUSED.load(deps.storage)?,
+        timelock_delay: config.timelock_delay,
     };
     Ok(res)
 }
@@ -366,44 +882,160 @@ pub fn query_status(deps: Deps, _env: Env) -> StdResult<StatusResponse> {
         issued: supply.issued,
         claims: supply.claims,
         balance: balance.amount,
-        ratio: get_ratio(balance.amount, supply.issued),
+        native: supply.native,
+        ratio: get_ratio(supply.native, supply.issued),
     };
     Ok(res)
 }
 
 pub fn query_order_info_of(deps: Deps, _env: Env, address: String) -> StdResult<OrderInfoOfResponse> {
-    let config = CONFIG.load(deps.storage)?;
     let supply = TOTAL_SUPPLY.load(deps.storage)?;
 
-    let balance = deps
-        .querier
-        .query_balance(&_env.contract.address, &config.bond_denom)?;
-
     let address = deps.api.addr_validate(&address)?;
     let node_id = QUEUE_ID
         .may_load(deps.storage, &address)?
         .unwrap_or_default();
     let mut issued = Uint128::zero();
     let mut height = 0;
-    if node_id > 0 {
-        let node_key = &node_id.to_be_bytes();
-        let cur_node = node_read(deps.storage).load(node_key)?;
+    if let Some(cur_node) = ORDER_BOOK.may_load(deps.storage, node_id)? {
         issued = cur_node.value;
         height = cur_node.height;
     }
-    let native = issued * get_ratio(balance.amount, supply.issued);
+    let native = issued * get_ratio(supply.native, supply.issued);
 
     Ok(OrderInfoOfResponse { issued, native, height, node_id})
 }
 
 pub fn query_order_book(deps: Deps) -> StdResult<OrderBookResponse> {
-    let state = linked_list_read(deps.storage).load()?;
+    let state = ORDER_BOOK.state(deps.storage)?;
 
-    let unstaking_requests: Vec<NodeWithId> = linked_list_get_list(deps.storage, 50)?;
+    let orders: Vec<NodeWithId> = ORDER_BOOK
+        .range(deps.storage, None, 50)?
+        .into_iter()
+        .map(|(id, info)| NodeWithId { id, info })
+        .collect();
 
     let res = OrderBookResponse {
         state,
-        queue: unstaking_requests,
+        queue: orders,
     };
     Ok(res)
 }
+
+pub fn query_verify_queue(deps: Deps, limit: Option<u32>) -> StdResult<VerifyQueueResponse> {
+    let limit = limit.unwrap_or(DEFAULT_VERIFY_LIMIT) as usize;
+    let report = ORDER_BOOK.verify(deps.storage, limit)?;
+    let mut problems = report.problems;
+    let mut complete = report.complete;
+
+    // every linked order must be the one QUEUE_ID points to
+    let mut node_ids: BTreeMap<Addr, u64> = BTreeMap::new();
+    let mut total = Uint128::zero();
+    for (id, node) in report.entries.iter() {
+        total += node.value;
+        if let Some(other_id) = node_ids.insert(node.receiver.clone(), *id) {
+            problems.push(format!("{} has orders {} and {}", node.receiver, other_id, id));
+        }
+        let queue_id = QUEUE_ID.may_load(deps.storage, &node.receiver)?.unwrap_or_default();
+        if queue_id != *id {
+            problems.push(format!("order {} of {} but queue id is {}", id, node.receiver, queue_id));
+        }
+    }
+    // totals only add up over the whole order book
+    if report.complete {
+        let supply = TOTAL_SUPPLY.load(deps.storage)?;
+        let queued = supply.issued.checked_sub(supply.locked)?;
+        if total != queued {
+            problems.push(format!("order book holds {} but issued unlocked shares are {}", total, queued));
+        }
+        // removed orders keep a zero queue id, they don't count against the limit
+        let mut checked = 0;
+        for item in QUEUE_ID.range(deps.storage, None, None, Order::Ascending) {
+            let (address, queue_id) = item?;
+            if queue_id == 0 {
+                continue;
+            }
+            if checked == limit {
+                complete = false;
+                break;
+            }
+            checked += 1;
+            if !node_ids.contains_key(&address) {
+                problems.push(format!("queue id of {} is {} but the order is not linked", address, queue_id));
+            }
+        }
+    }
+
+    Ok(VerifyQueueResponse {
+        walked: report.entries.len() as u64,
+        complete,
+        problems,
+    })
+}
+
+pub fn query_ratio_oracle(deps: Deps, env: Env) -> StdResult<RatioOracleResponse> {
+    let config = CONFIG.load(deps.storage)?;
+    let spot = query_staking_ratio(&deps.querier, &config.staking_manager_addr)?;
+    let (twap, observed_seconds) = get_twap_ratio(deps.storage, env.block.time.seconds(), config.twap_window, spot)?;
+
+    Ok(RatioOracleResponse {
+        twap,
+        spot,
+        twap_window: config.twap_window,
+        observed_seconds,
+    })
+}
+
+pub fn query_pending_owner(deps: Deps) -> StdResult<PendingOwnerResponse> {
+    let pending = PENDING_OWNER.may_load(deps.storage)?;
+    Ok(PendingOwnerResponse {
+        owner: pending.as_ref().map(|p| p.owner.to_string()),
+        expiry: pending.and_then(|p| p.expiry).map(|e| e.seconds()),
+    })
+}
+
+pub fn query_pending_changes(
+    deps: Deps,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+) -> StdResult<PendingChangesResponse> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after.map(Bound::exclusive);
+    let changes = PENDING_CHANGES
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|item| item.map(|(id, pending)| PendingChangeWithId {
+            id,
+            change: pending.change,
+            eta: pending.eta.seconds(),
+        }))
+        .collect::<StdResult<Vec<_>>>()?;
+    Ok(PendingChangesResponse { changes })
+}
+
+pub fn query_role_members(
+    deps: Deps,
+    role: Role,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<RoleMembersResponse> {
+    if role == Role::Owner {
+        let config = CONFIG.load(deps.storage)?;
+        return Ok(RoleMembersResponse { members: config.owner.into_iter().map(String::from).collect() });
+    }
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start_after = start_after.map(|addr| deps.api.addr_validate(&addr)).transpose()?;
+    let start = start_after.as_ref().map(Bound::exclusive);
+    let members = ROLES
+        .prefix(role.as_str())
+        .keys(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|item| item.map(String::from))
+        .collect::<StdResult<Vec<_>>>()?;
+    Ok(RoleMembersResponse { members })
+}
+
+pub fn query_has_role(deps: Deps, role: Role, address: String) -> StdResult<HasRoleResponse> {
+    let address = deps.api.addr_validate(&address)?;
+    Ok(HasRoleResponse { has_role: has_role(deps.storage, &address, role)? })
+}
@@ -1,4 +1,4 @@
-use cosmwasm_std::StdError;
+use cosmwasm_std::{Decimal, StdError, Uint128};
 use thiserror::Error;
 
 #[derive(Error, Debug)]
@@ -20,13 +20,49 @@ pub enum ContractError {
     #[error("No claims that can be released currently")]
     NothingToClaim {},
 
+    #[error("Owner role can only be transferred with ProposeNewOwner")]
+    OwnerRoleNotGrantable {},
+
+    #[error("No unaccounted {denom} tokens to sweep")]
+    NothingToSweep { denom: String },
+
+    #[error("No ownership proposal to accept or cancel")]
+    NoPendingOwner {},
+
+    #[error("Ownership proposal has expired")]
+    OwnershipProposalExpired {},
+
+    #[error("No pending change with id {id}")]
+    PendingChangeNotFound { id: u64 },
+
+    #[error("Change {id} can't be executed before {eta}")]
+    TimelockNotExpired { id: u64, eta: u64 },
+
+    #[error("Operation {operation} is paused")]
+    Paused { operation: String },
+
     #[error("User is not a liquidity provider to remove")]
     NothingToRemove{},
 
+    #[error("First deposit must be at least {min}")]
+    InsufficientInitialDeposit { min: Uint128 },
+
+    #[error("Deposit is too small to mint any shares")]
+    ZeroShares {},
+
+    #[error("Pool holds no native tokens for its {issued} shares")]
+    DrainedPool { issued: Uint128 },
+
     #[error("Not enough liquidity to swap")]
     InsufficientLiquidity{},
     
 
+    #[error("Spot ratio {spot} deviates too much from TWAP ratio {twap}")]
+    RatioDeviation { spot: Decimal, twap: Decimal },
+
+    #[error("Max ratio deviation {max_ratio_deviation} must be above 0 and below 1")]
+    InvalidRatioDeviation { max_ratio_deviation: Decimal },
+
     #[error("Balance should be zero but: '{balance}'")]
     BalanceShouldBeZero { balance: String },
 }
@@ -1,7 +1,9 @@
 pub mod contract;
-pub mod linked_list;
 mod error;
 pub mod msg;
 pub mod state;
 
+#[cfg(test)]
+mod testing;
+
 pub use crate::error::ContractError;
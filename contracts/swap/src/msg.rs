@@ -1,10 +1,14 @@
+use std::fmt;
+
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
 use cosmwasm_std::{Uint128, Decimal, Coin};
 use cw20::{Cw20ReceiveMsg};
 
-use crate::linked_list::{NodeWithId, LinkedList};
+use deque::DequeState;
+
+use crate::state::{AdminChange, Node, PausedInfo};
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct InstantiateMsg {
@@ -23,14 +27,89 @@ pub enum ExecuteMsg {
     Remove {},
     /// Claim is called by liquidity provider to claim liquid token from swapping
     Claim {},
-    /// Admin call this method to set up swap fee
+    /// Admin call this method to queue a new swap fee
     SetSwapFee { swap_fee: Uint128 },
+    /// Admin call this method to queue a new TWAP window and accepted spot deviation,
+    /// the deviation must be above 0 and below 1
+    SetRatioOracle { twap_window: u64, max_ratio_deviation: Decimal },
+    /// UpdateRatioOracle records the current staking ratio, anyone can call it
+    UpdateRatioOracle {},
+    /// Admin call this method to queue a new timelock delay (in seconds)
+    SetTimelockDelay { delay: u64 },
+    /// Admin call this method to send native tokens the pool doesn't account for (sent directly
+    /// to the contract) to the recipient, or to the sender if none is given
+    SweepUnaccounted { recipient: Option<String> },
+    /// Admin call this method to relink the order book from the stored orders, in id order
+    RepairQueue {},
+    /// Admin call this method to apply a queued change once its timelock has passed
+    ExecutePendingChange { id: u64 },
+    /// Admin or guardian call this method to drop a queued change
+    CancelPendingChange { id: u64 },
+    /// Admin call this method to propose a new admin, who has to accept before `expiry` (block time in seconds)
+    ProposeNewOwner { owner: String, expiry: Option<u64> },
+    /// Proposed admin call this method to take over the admin role
+    AcceptOwnership {},
+    /// Admin call this method to drop the pending admin proposal
+    CancelOwnershipProposal {},
+    /// Admin call this method to give up the admin role for good, the guardian can still unpause
+    RenounceOwnership {},
+    /// Admin call this method to give an address the operator or guardian role
+    GrantRole { role: Role, address: String },
+    /// Admin call this method to take the operator or guardian role from an address
+    RevokeRole { role: Role, address: String },
+    /// Guardian call this method to pause one operation, or all of them if none is given
+    Pause { operation: Option<Operation> },
+    /// Admin call this method to unpause one operation, or all of them if none is given,
+    /// guardian call it once ownership is renounced
+    Unpause { operation: Option<Operation> },
 
     /// This accepts a properly-encoded ReceiveMsg from a cw20 contract (to process swapping request)
     Receive(Cw20ReceiveMsg),
 
 }
 
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum Operation {
+    Add,
+    Remove,
+    Swap,
+    Claim,
+}
+
+impl fmt::Display for Operation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Operation::Add => write!(f, "add"),
+            Operation::Remove => write!(f, "remove"),
+            Operation::Swap => write!(f, "swap"),
+            Operation::Claim => write!(f, "claim"),
+        }
+    }
+}
+
+/// Role is a set of privileges, the owner holds every role
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum Role {
+    /// Owner manages economics and roles
+    Owner,
+    /// Operator runs keeper tasks
+    Operator,
+    /// Guardian can pause operations in an emergency
+    Guardian,
+}
+
+impl Role {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Role::Owner => "owner",
+            Role::Operator => "operator",
+            Role::Guardian => "guardian",
+        }
+    }
+}
+
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum QueryMsg {
@@ -38,18 +117,30 @@ pub enum QueryMsg {
     ClaimableOf { address: String },
     /// ConfigInfo shows the config of the contract
     ConfigInfo {},
+    /// PendingOwner shows the admin proposed by the current admin, if any
+    PendingOwner {},
+    /// RoleMembers shows the addresses holding a role
+    RoleMembers { role: Role, start_after: Option<String>, limit: Option<u32> },
+    /// HasRole shows whether the address holds a role
+    HasRole { role: Role, address: String },
+    /// PendingChanges shows the queued admin changes waiting for their timelock
+    PendingChanges { start_after: Option<u64>, limit: Option<u32> },
     /// StatusInfo shows staking info of the contract
     StatusInfo {},
     /// Order book shows first 50 order in the swapping queue of the contract
     OrderBook {},
     /// OrderInfoOf shows status of the liquidity pool deposit of the address 
     OrderInfoOf { address: String },
+    /// RatioOracle shows the time-weighted average staking ratio used to price the liquid token
+    RatioOracle {},
+    /// VerifyQueue walks up to `limit` orders of the order book and reports inconsistencies
+    VerifyQueue { limit: Option<u32> },
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct ConfigResponse {
-    /// Admin to change config
-    pub owner: String,
+    /// Admin to change config, none once ownership is renounced
+    pub owner: Option<String>,
     /// This is the denomination we can stake (and only one we accept for payments)
     pub bond_denom: String,
     /// Liquid token address
@@ -57,7 +148,15 @@ pub struct ConfigResponse {
     /// Staking manager contract address
     pub staking_manager_addr: String,
     /// Swap fee
-    pub swap_fee: Uint128
+    pub swap_fee: Uint128,
+    /// Window in seconds the staking ratio is averaged over
+    pub twap_window: u64,
+    /// Maximum relative deviation of the spot ratio from the TWAP ratio
+    pub max_ratio_deviation: Decimal,
+    /// Operations currently paused
+    pub paused: PausedInfo,
+    /// Delay in seconds before a queued admin change can be executed
+    pub timelock_delay: u64,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -68,7 +167,9 @@ pub struct StatusResponse {
     pub claims: Uint128,
     /// available native token balance of this contract
     pub balance: Uint128,
-    /// ratio of balance / issued (or how many native tokens that one derivative token is nominally worth)
+    /// native tokens held by the pool, excluding tokens sent directly to the contract
+    pub native: Uint128,
+    /// ratio of native / issued (or how many native tokens that one derivative token is nominally worth)
     pub ratio: Decimal,
 }
 
@@ -86,10 +187,28 @@ pub struct OrderInfoOfResponse {
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct OrderBookResponse {
-    pub state: LinkedList,
+    pub state: DequeState,
     pub queue: Vec<NodeWithId>,
 }
 
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct NodeWithId {
+    pub id: u64,
+    pub info: Node,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct RatioOracleResponse {
+    /// time-weighted average staking ratio over the window
+    pub twap: Decimal,
+    /// current staking ratio reported by the staking manager
+    pub spot: Decimal,
+    /// configured window in seconds
+    pub twap_window: u64,
+    /// number of seconds actually covered, may be shorter than the window if history is short
+    pub observed_seconds: u64,
+}
+
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum StakingManagerQueryMsg {
@@ -113,4 +232,45 @@ pub struct StakingManagerStatusResponse {
     pub balance: Uint128,
     /// ratio of native / issued (or how many native tokens that one derivative token is nominally worth)
     pub ratio: Decimal,
-}
\ No newline at end of file
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PendingOwnerResponse {
+    /// proposed admin, none if there is no pending proposal
+    pub owner: Option<String>,
+    /// block time in seconds after which the proposal can no longer be accepted
+    pub expiry: Option<u64>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PendingChangeWithId {
+    pub id: u64,
+    pub change: AdminChange,
+    /// block time in seconds from which the change can be executed
+    pub eta: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PendingChangesResponse {
+    pub changes: Vec<PendingChangeWithId>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct RoleMembersResponse {
+    pub members: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct HasRoleResponse {
+    pub has_role: bool,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct VerifyQueueResponse {
+    /// number of orders walked
+    pub walked: u64,
+    /// whether the whole order book was walked, totals are only checked then
+    pub complete: bool,
+    /// problems found, empty if the order book is consistent
+    pub problems: Vec<String>,
+}
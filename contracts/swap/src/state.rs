@@ -1,13 +1,15 @@
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
-use cosmwasm_std::{Addr, Uint128};
+use cosmwasm_std::{Addr, Decimal, Timestamp, Uint128};
+use cosmwasm_std::Empty;
 use cw_storage_plus::{Item, Map};
+use deque::Deque;
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct ConfigInfo {
-    /// Admin to change config
-    pub owner: Addr,
+    /// Admin to change config, none once ownership is renounced
+    pub owner: Option<Addr>,
     /// This is the denomination we can stake (and only one we accept for payments)
     pub bond_denom: String,
     /// Liquid token address
@@ -15,7 +17,33 @@ pub struct ConfigInfo {
     /// Staking manager contract address
     pub staking_manager_addr: Addr,
     /// Swap fee for liquidity provider
-    pub swap_fee: Uint128
+    pub swap_fee: Uint128,
+    /// Window in seconds the staking ratio is averaged over to price swaps, zero uses spot ratio
+    pub twap_window: u64,
+    /// Maximum relative deviation of the spot ratio from the TWAP ratio accepted for a swap
+    pub max_ratio_deviation: Decimal,
+    /// Delay in seconds before a queued admin change can be executed
+    pub timelock_delay: u64,
+}
+
+/// PausedInfo tracks which user operations are halted
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema, Default)]
+pub struct PausedInfo {
+    pub add: bool,
+    pub remove: bool,
+    pub swap: bool,
+    pub claim: bool,
+}
+
+/// Node is a liquidity provider order waiting in the order book to be matched
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Node {
+    /// liquidity provider
+    pub receiver: Addr,
+    /// lp shares not matched yet
+    pub value: Uint128,
+    /// block height of the last add
+    pub height: u64,
 }
 
 /// Supply is dynamic and tracks the current supply of staked and ERC20 tokens.
@@ -25,9 +53,69 @@ pub struct Supply {
     pub issued: Uint128,
     /// claims is how many tokens need to be reserved paying back those who unbonded
     pub claims: Uint128,
+    /// native is how many native tokens the pool holds, tokens sent directly to the contract are not counted
+    pub native: Uint128,
+    /// locked is how many of the issued lp shares are locked in the pool and can't be removed
+    pub locked: Uint128,
+}
+
+/// RatioObservation is a point of the staking ratio accumulator
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct RatioObservation {
+    /// block time of the observation
+    pub time: Timestamp,
+    /// spot staking ratio observed, it holds until the next observation
+    pub ratio: Decimal,
+    /// sum of ratio * seconds since the first observation
+    pub cumulative: Decimal,
+}
+
+/// Maximum number of ratio observations kept, older ones are dropped
+pub const RATIO_OBSERVATIONS_CAPACITY: u64 = 1000;
+
+/// PendingOwner is the address proposed to take over the admin role
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PendingOwner {
+    pub owner: Addr,
+    /// block time after which the proposal can no longer be accepted
+    pub expiry: Option<Timestamp>,
 }
 
+/// AdminChange is an admin action that only takes effect after the timelock delay
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum AdminChange {
+    SetSwapFee { swap_fee: Uint128 },
+    SetRatioOracle { twap_window: u64, max_ratio_deviation: Decimal },
+    SetTimelockDelay { delay: u64 },
+}
+
+/// PendingChange is an admin change waiting for the timelock to pass
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PendingChange {
+    pub change: AdminChange,
+    /// block time from which the change can be executed
+    pub eta: Timestamp,
+}
+
+/// Default delay in seconds before a queued admin change can be executed
+pub const DEFAULT_TIMELOCK_DELAY: u64 = 24 * 60 * 60;
+/// Smallest native amount accepted for the first deposit into an empty pool
+pub const MINIMUM_INITIAL_DEPOSIT: u128 = 1_000_000;
+/// Lp shares issued on the first deposit without an owner, locked in the pool for good
+/// so the share price can't be inflated by a tiny first deposit
+pub const LOCKED_LP_SHARES: u128 = 1_000;
+
 pub const CONFIG: Item<ConfigInfo> = Item::new("config");
+pub const PENDING_OWNER: Item<PendingOwner> = Item::new("pending_owner");
+pub const PENDING_CHANGES: Map<u64, PendingChange> = Map::new("pending_changes");
+pub const PENDING_CHANGES_NEXT_ID: Item<u64> = Item::new("pending_changes_next_id");
+/// ROLES holds the operator and guardian members, keyed by role name then address
+pub const ROLES: Map<(&str, &Addr), Empty> = Map::new("roles");
+pub const PAUSED: Item<PausedInfo> = Item::new("paused");
 pub const TOTAL_SUPPLY: Item<Supply> = Item::new("total_supply");
 pub const CLAIMABLE: Map<&Addr, Uint128> = Map::new("claimable");
-pub const QUEUE_ID: Map<&Addr, u64> = Map::new("queue_id");
\ No newline at end of file
+pub const QUEUE_ID: Map<&Addr, u64> = Map::new("queue_id");
+pub const ORDER_BOOK: Deque<Node> = Deque::new("order_book", "order_book_nodes");
+pub const RATIO_OBSERVATIONS: Map<u64, RatioObservation> = Map::new("ratio_observations");
+pub const RATIO_OBSERVATIONS_NEXT_ID: Item<u64> = Item::new("ratio_observations_next_id");
@@ -0,0 +1,60 @@
+use cosmwasm_std::testing::{mock_dependencies, mock_env, mock_info, MockApi, MockQuerier, MockStorage};
+use cosmwasm_std::{
+    coin, to_json_binary, ContractResult, Decimal, Env, OwnedDeps, SystemError, SystemResult, Uint128, WasmQuery,
+};
+
+use crate::contract::{execute, instantiate};
+use crate::msg::{ExecuteMsg, InstantiateMsg, StakingManagerStatusResponse};
+use crate::ContractError;
+
+pub const OWNER: &str = "owner";
+pub const BOND_DENOM: &str = "uarch";
+pub const LIQUID_TOKEN: &str = "liquid_token";
+pub const STAKING_MANAGER: &str = "staking_manager";
+
+pub type MockDeps = OwnedDeps<MockStorage, MockApi, MockQuerier>;
+
+// setup instantiates the contract with the staking manager reporting a 1:1 ratio
+pub fn setup() -> MockDeps {
+    let mut deps = mock_dependencies();
+    deps.querier.update_staking(BOND_DENOM, &[], &[]);
+    set_staking_ratio(&mut deps, Decimal::one());
+    let msg = InstantiateMsg {
+        liquid_token_addr: LIQUID_TOKEN.to_string(),
+        staking_manager_addr: STAKING_MANAGER.to_string(),
+    };
+    instantiate(deps.as_mut(), mock_env(), mock_info(OWNER, &[]), msg).unwrap();
+    deps
+}
+
+// set_staking_ratio sets the spot ratio reported by the staking manager
+pub fn set_staking_ratio(deps: &mut MockDeps, ratio: Decimal) {
+    deps.querier.update_wasm(move |query| match query {
+        WasmQuery::Smart { contract_addr, .. } if contract_addr == STAKING_MANAGER => {
+            let response = StakingManagerStatusResponse {
+                issued: Uint128::zero(),
+                native: coin(0, BOND_DENOM),
+                unstakings: Uint128::zero(),
+                claims: Uint128::zero(),
+                bonded: Uint128::zero(),
+                balance: Uint128::zero(),
+                ratio,
+            };
+            SystemResult::Ok(ContractResult::Ok(to_json_binary(&response).unwrap()))
+        },
+        _ => SystemResult::Err(SystemError::UnsupportedRequest { kind: format!("{:?}", query) }),
+    });
+}
+
+// run executes a message from the sender, dropping the response
+pub fn run(deps: &mut MockDeps, sender: &str, msg: ExecuteMsg) -> Result<(), ContractError> {
+    execute(deps.as_mut(), mock_env(), mock_info(sender, &[]), msg).map(|_| ())
+}
+
+// env_at returns the mock env `seconds` later, with a block every 5 seconds
+pub fn env_at(seconds: u64) -> Env {
+    let mut env = mock_env();
+    env.block.time = env.block.time.plus_seconds(seconds);
+    env.block.height += seconds / 5;
+    env
+}
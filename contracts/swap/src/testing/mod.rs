@@ -0,0 +1,5 @@
+mod mock_querier;
+
+mod oracle;
+mod ownership;
+mod pool;
@@ -0,0 +1,106 @@
+use cosmwasm_std::testing::{mock_env, mock_info};
+use cosmwasm_std::{from_json, Decimal, Env, Uint128};
+use cw20::Cw20ReceiveMsg;
+
+use crate::contract::{execute, query};
+use crate::msg::{ExecuteMsg, QueryMsg, RatioOracleResponse};
+use crate::state::{RATIO_OBSERVATIONS, RATIO_OBSERVATIONS_CAPACITY};
+use crate::testing::mock_querier::{env_at, set_staking_ratio, setup, MockDeps, LIQUID_TOKEN, OWNER};
+use crate::ContractError;
+
+fn observe(deps: &mut MockDeps, env: Env, ratio: Decimal) {
+    set_staking_ratio(deps, ratio);
+    execute(deps.as_mut(), env, mock_info("keeper", &[]), ExecuteMsg::UpdateRatioOracle {}).unwrap();
+}
+
+fn query_oracle(deps: &MockDeps, env: Env) -> RatioOracleResponse {
+    from_json(query(deps.as_ref(), env, QueryMsg::RatioOracle {}).unwrap()).unwrap()
+}
+
+#[test]
+fn twap_falls_back_to_spot_without_observations() {
+    let mut deps = setup();
+    set_staking_ratio(&mut deps, Decimal::percent(110));
+    let oracle = query_oracle(&deps, env_at(0));
+    assert_eq!(oracle.twap, Decimal::percent(110));
+    assert_eq!(oracle.spot, Decimal::percent(110));
+    assert_eq!(oracle.observed_seconds, 0);
+}
+
+#[test]
+fn twap_averages_over_the_window() {
+    // the default window is 30 minutes
+    let mut deps = setup();
+    observe(&mut deps, env_at(0), Decimal::percent(100));
+    observe(&mut deps, env_at(600), Decimal::percent(120));
+
+    // the history is shorter than the window, it covers what was observed
+    let oracle = query_oracle(&deps, env_at(1200));
+    assert_eq!(oracle.observed_seconds, 1200);
+    assert_eq!(oracle.twap, Decimal::percent(110));
+    assert_eq!(oracle.spot, Decimal::percent(120));
+
+    // the window starts within the first observation
+    let oracle = query_oracle(&deps, env_at(2100));
+    assert_eq!(oracle.observed_seconds, 1800);
+    assert_eq!(oracle.twap, Decimal::from_ratio(100u128 * 300 + 120 * 1500, 1800u128 * 100));
+
+    // the window starts after the last observation
+    let oracle = query_oracle(&deps, env_at(3000));
+    assert_eq!(oracle.observed_seconds, 1800);
+    assert_eq!(oracle.twap, Decimal::percent(120));
+}
+
+#[test]
+fn record_keeps_first_observation_of_a_block() {
+    let mut deps = setup();
+    observe(&mut deps, env_at(0), Decimal::percent(100));
+    // moving the ratio later in the same block is not recorded
+    observe(&mut deps, env_at(0), Decimal::percent(200));
+    let oracle = query_oracle(&deps, env_at(600));
+    assert_eq!(oracle.twap, Decimal::percent(100));
+
+    // nor is an observation older than the last one
+    observe(&mut deps, env_at(300), Decimal::percent(100));
+    observe(&mut deps, env_at(200), Decimal::percent(200));
+    let oracle = query_oracle(&deps, env_at(600));
+    assert_eq!(oracle.twap, Decimal::percent(100));
+}
+
+#[test]
+fn record_drops_oldest_observation_at_capacity() {
+    let mut deps = setup();
+    for i in 0..=RATIO_OBSERVATIONS_CAPACITY {
+        observe(&mut deps, env_at(i), Decimal::one());
+    }
+    assert!(!RATIO_OBSERVATIONS.has(&deps.storage, 0));
+    assert!(RATIO_OBSERVATIONS.has(&deps.storage, 1));
+    assert!(RATIO_OBSERVATIONS.has(&deps.storage, RATIO_OBSERVATIONS_CAPACITY));
+}
+
+#[test]
+fn swap_rejects_spot_away_from_twap() {
+    let mut deps = setup();
+    observe(&mut deps, env_at(0), Decimal::percent(100));
+    set_staking_ratio(&mut deps, Decimal::percent(110));
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: "alice".to_string(),
+        amount: Uint128::new(1_000),
+        msg: Default::default(),
+    });
+    let err = execute(deps.as_mut(), env_at(600), mock_info(LIQUID_TOKEN, &[]), msg).unwrap_err();
+    assert!(matches!(err, ContractError::RatioDeviation { .. }));
+}
+
+#[test]
+fn set_ratio_oracle_bounds_deviation() {
+    let mut deps = setup();
+    for max_ratio_deviation in [Decimal::zero(), Decimal::one(), Decimal::percent(150)] {
+        let msg = ExecuteMsg::SetRatioOracle { twap_window: 600, max_ratio_deviation };
+        let err = execute(deps.as_mut(), mock_env(), mock_info(OWNER, &[]), msg).unwrap_err();
+        assert!(matches!(err, ContractError::InvalidRatioDeviation { .. }));
+    }
+    let msg = ExecuteMsg::SetRatioOracle { twap_window: 600, max_ratio_deviation: Decimal::percent(10) };
+    let res = execute(deps.as_mut(), mock_env(), mock_info(OWNER, &[]), msg).unwrap();
+    assert_eq!(res.attributes[0].value, "queueChange");
+}
@@ -0,0 +1,28 @@
+use cosmwasm_std::testing::mock_env;
+use cosmwasm_std::from_json;
+
+use crate::contract::query;
+use crate::msg::{ConfigResponse, ExecuteMsg, Operation, QueryMsg, Role};
+use crate::testing::mock_querier::{run, setup, OWNER};
+use crate::ContractError;
+
+const GUARDIAN: &str = "guardian";
+
+#[test]
+fn guardian_unpauses_once_ownership_is_renounced() {
+    let mut deps = setup();
+    run(&mut deps, OWNER, ExecuteMsg::GrantRole { role: Role::Guardian, address: GUARDIAN.to_string() }).unwrap();
+    run(&mut deps, GUARDIAN, ExecuteMsg::Pause { operation: Some(Operation::Swap) }).unwrap();
+    let err = run(&mut deps, GUARDIAN, ExecuteMsg::Unpause { operation: Some(Operation::Swap) }).unwrap_err();
+    assert!(matches!(err, ContractError::Unauthorized {}));
+
+    run(&mut deps, OWNER, ExecuteMsg::RenounceOwnership {}).unwrap();
+    let config: ConfigResponse = from_json(query(deps.as_ref(), mock_env(), QueryMsg::ConfigInfo {}).unwrap()).unwrap();
+    assert_eq!(config.owner, None);
+    let err = run(&mut deps, OWNER, ExecuteMsg::SetSwapFee { swap_fee: 0u128.into() }).unwrap_err();
+    assert!(matches!(err, ContractError::Unauthorized {}));
+
+    run(&mut deps, GUARDIAN, ExecuteMsg::Unpause { operation: Some(Operation::Swap) }).unwrap();
+    let config: ConfigResponse = from_json(query(deps.as_ref(), mock_env(), QueryMsg::ConfigInfo {}).unwrap()).unwrap();
+    assert!(!config.paused.swap);
+}
@@ -0,0 +1,89 @@
+use cosmwasm_std::testing::{mock_env, mock_info, MOCK_CONTRACT_ADDR};
+use cosmwasm_std::{coins, from_json, Addr, BankMsg, Coin, CosmosMsg, Uint128};
+
+use crate::contract::{execute, query};
+use crate::msg::{ExecuteMsg, QueryMsg, VerifyQueueResponse};
+use crate::state::{Supply, LOCKED_LP_SHARES, QUEUE_ID, TOTAL_SUPPLY};
+use crate::testing::mock_querier::{setup, MockDeps, BOND_DENOM};
+use crate::ContractError;
+
+fn add(deps: &mut MockDeps, sender: &str, amount: u128) -> Result<(), ContractError> {
+    execute(deps.as_mut(), mock_env(), mock_info(sender, &coins(amount, BOND_DENOM)), ExecuteMsg::Add {})?;
+    Ok(())
+}
+
+fn remove(deps: &mut MockDeps, sender: &str) -> Uint128 {
+    let res = execute(deps.as_mut(), mock_env(), mock_info(sender, &[]), ExecuteMsg::Remove {}).unwrap();
+    match &res.messages[0].msg {
+        CosmosMsg::Bank(BankMsg::Send { amount, .. }) => amount[0].amount,
+        other => panic!("unexpected message {:?}", other),
+    }
+}
+
+fn verify_queue(deps: &MockDeps, limit: u32) -> VerifyQueueResponse {
+    from_json(query(deps.as_ref(), mock_env(), QueryMsg::VerifyQueue { limit: Some(limit) }).unwrap()).unwrap()
+}
+
+#[test]
+fn first_add_locks_shares() {
+    let mut deps = setup();
+    let err = add(&mut deps, "alice", 999_999).unwrap_err();
+    assert!(matches!(err, ContractError::InsufficientInitialDeposit { .. }));
+
+    add(&mut deps, "alice", 1_000_000).unwrap();
+    let supply = TOTAL_SUPPLY.load(&deps.storage).unwrap();
+    assert_eq!(supply.issued.u128(), 1_000_000);
+    assert_eq!(supply.locked.u128(), LOCKED_LP_SHARES);
+    assert_eq!(supply.native.u128(), 1_000_000);
+    // the locked shares keep their native tokens in the pool
+    assert_eq!(remove(&mut deps, "alice").u128(), 1_000_000 - LOCKED_LP_SHARES);
+}
+
+#[test]
+fn donation_does_not_dilute_next_depositor() {
+    let mut deps = setup();
+    add(&mut deps, "attacker", 1_000_000).unwrap();
+    // tokens sent directly to the pool are not counted in the share price
+    deps.querier.update_balance(MOCK_CONTRACT_ADDR, vec![Coin::new(10_000_000, BOND_DENOM)]);
+    add(&mut deps, "victim", 500_000).unwrap();
+    assert_eq!(remove(&mut deps, "victim").u128(), 500_000);
+}
+
+#[test]
+fn drained_pool_rejects_deposits() {
+    let mut deps = setup();
+    let supply = Supply {
+        issued: Uint128::new(LOCKED_LP_SHARES),
+        locked: Uint128::new(LOCKED_LP_SHARES),
+        ..Supply::default()
+    };
+    TOTAL_SUPPLY.save(deps.as_mut().storage, &supply).unwrap();
+    let err = add(&mut deps, "victim", 1_000_000).unwrap_err();
+    assert!(matches!(err, ContractError::DrainedPool { issued } if issued.u128() == LOCKED_LP_SHARES));
+}
+
+#[test]
+fn verify_queue_skips_removed_orders_and_reports_truncation() {
+    let mut deps = setup();
+    add(&mut deps, "alice", 1_000_000).unwrap();
+    // removed orders leave a zero queue id behind
+    for sender in ["a0", "a1", "a2"] {
+        add(&mut deps, sender, 1_000).unwrap();
+        remove(&mut deps, sender);
+    }
+    add(&mut deps, "bob", 1_000).unwrap();
+    let report = verify_queue(&deps, 2);
+    assert_eq!(report.walked, 2);
+    assert!(report.complete);
+    assert!(report.problems.is_empty(), "{:?}", report.problems);
+
+    // a queue id beyond the limit is not checked, so the report is not complete
+    QUEUE_ID.save(deps.as_mut().storage, &Addr::unchecked("zed"), &99).unwrap();
+    let report = verify_queue(&deps, 2);
+    assert!(!report.complete);
+    assert!(report.problems.is_empty(), "{:?}", report.problems);
+
+    let report = verify_queue(&deps, 3);
+    assert!(report.complete);
+    assert_eq!(report.problems, vec!["queue id of zed is 99 but the order is not linked".to_string()]);
+}
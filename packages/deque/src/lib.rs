@@ -0,0 +1,338 @@
+use std::collections::BTreeSet;
+
+use schemars::JsonSchema;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+
+use cosmwasm_std::{Order, OverflowError, OverflowOperation, StdError, StdResult, Storage};
+use cw_storage_plus::{Item, Map};
+
+/// DequeState tracks both ends of the deque. Ids start at 1 and are never reused,
+/// 0 stands for no entry
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema, Default)]
+pub struct DequeState {
+    pub head_id: u64,
+    pub tail_id: u64,
+    pub length: u64,
+    /// id given to the last pushed entry
+    pub last_id: u64,
+}
+
+/// Entry is a stored value linked to its neighbours
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Entry<T> {
+    pub prev: u64,
+    pub next: u64,
+    pub value: T,
+}
+
+/// DequeReport is the outcome of walking a deque to check its links
+#[derive(Clone, Debug, PartialEq)]
+pub struct DequeReport<T> {
+    /// entries reached from the head, in order
+    pub entries: Vec<(u64, T)>,
+    /// whether the walk reached the tail and every stored entry was checked within the limit
+    pub complete: bool,
+    /// problems found, empty if the deque is consistent
+    pub problems: Vec<String>,
+}
+
+/// Deque is a doubly linked list in contract storage. Entries keep the id they got when pushed,
+/// so they can be read, updated or removed by id in O(1) wherever they are in the deque
+pub struct Deque<'a, T> {
+    state: Item<'a, DequeState>,
+    entries: Map<'a, u64, Entry<T>>,
+}
+
+impl<'a, T> Deque<'a, T>
+where
+    T: Serialize + DeserializeOwned,
+{
+    pub const fn new(state_namespace: &'a str, entries_namespace: &'a str) -> Self {
+        Deque {
+            state: Item::new(state_namespace),
+            entries: Map::new(entries_namespace),
+        }
+    }
+
+    /// state returns the ends and length of the deque, a deque never written to is empty
+    pub fn state(&self, storage: &dyn Storage) -> StdResult<DequeState> {
+        Ok(self.state.may_load(storage)?.unwrap_or_default())
+    }
+
+    pub fn len(&self, storage: &dyn Storage) -> StdResult<u64> {
+        Ok(self.state(storage)?.length)
+    }
+
+    pub fn is_empty(&self, storage: &dyn Storage) -> StdResult<bool> {
+        Ok(self.len(storage)? == 0)
+    }
+
+    /// push_back appends a value after the tail and returns its id
+    pub fn push_back(&self, storage: &mut dyn Storage, value: T) -> StdResult<u64> {
+        let mut state = self.state(storage)?;
+        let id = state.last_id + 1;
+        if state.tail_id == 0 {
+            state.head_id = id;
+        } else {
+            self.update_entry(storage, state.tail_id, |entry| entry.next = id)?;
+        }
+        self.entries.save(storage, id, &Entry { prev: state.tail_id, next: 0, value })?;
+        state.tail_id = id;
+        state.length += 1;
+        state.last_id = id;
+        self.state.save(storage, &state)?;
+        Ok(id)
+    }
+
+    /// push_front inserts a value before the head and returns its id
+    pub fn push_front(&self, storage: &mut dyn Storage, value: T) -> StdResult<u64> {
+        let mut state = self.state(storage)?;
+        let id = state.last_id + 1;
+        if state.head_id == 0 {
+            state.tail_id = id;
+        } else {
+            self.update_entry(storage, state.head_id, |entry| entry.prev = id)?;
+        }
+        self.entries.save(storage, id, &Entry { prev: 0, next: state.head_id, value })?;
+        state.head_id = id;
+        state.length += 1;
+        state.last_id = id;
+        self.state.save(storage, &state)?;
+        Ok(id)
+    }
+
+    /// front returns the head entry, if any
+    pub fn front(&self, storage: &dyn Storage) -> StdResult<Option<(u64, T)>> {
+        let head_id = self.state(storage)?.head_id;
+        self.entry_at(storage, head_id)
+    }
+
+    /// back returns the tail entry, if any
+    pub fn back(&self, storage: &dyn Storage) -> StdResult<Option<(u64, T)>> {
+        let tail_id = self.state(storage)?.tail_id;
+        self.entry_at(storage, tail_id)
+    }
+
+    /// pop_front removes and returns the head entry, if any
+    pub fn pop_front(&self, storage: &mut dyn Storage) -> StdResult<Option<(u64, T)>> {
+        let head_id = self.state(storage)?.head_id;
+        if head_id == 0 {
+            return Ok(None);
+        }
+        Ok(self.remove(storage, head_id)?.map(|value| (head_id, value)))
+    }
+
+    /// pop_back removes and returns the tail entry, if any
+    pub fn pop_back(&self, storage: &mut dyn Storage) -> StdResult<Option<(u64, T)>> {
+        let tail_id = self.state(storage)?.tail_id;
+        if tail_id == 0 {
+            return Ok(None);
+        }
+        Ok(self.remove(storage, tail_id)?.map(|value| (tail_id, value)))
+    }
+
+    pub fn load(&self, storage: &dyn Storage, id: u64) -> StdResult<T> {
+        Ok(self.entries.load(storage, id)?.value)
+    }
+
+    pub fn may_load(&self, storage: &dyn Storage, id: u64) -> StdResult<Option<T>> {
+        Ok(self.entries.may_load(storage, id)?.map(|entry| entry.value))
+    }
+
+    /// save replaces the value of an entry already in the deque, keeping its place
+    pub fn save(&self, storage: &mut dyn Storage, id: u64, value: T) -> StdResult<()> {
+        self.update_entry(storage, id, |entry| entry.value = value)
+    }
+
+    /// remove unlinks an entry wherever it is in the deque and returns its value, if it was there
+    pub fn remove(&self, storage: &mut dyn Storage, id: u64) -> StdResult<Option<T>> {
+        let entry = match self.entries.may_load(storage, id)? {
+            Some(entry) => entry,
+            None => return Ok(None),
+        };
+        let mut state = self.state(storage)?;
+        if entry.prev == 0 {
+            state.head_id = entry.next;
+        } else {
+            self.update_entry(storage, entry.prev, |prev| prev.next = entry.next)?;
+        }
+        if entry.next == 0 {
+            state.tail_id = entry.prev;
+        } else {
+            self.update_entry(storage, entry.next, |next| next.prev = entry.prev)?;
+        }
+        // a length already at zero means the state doesn't match the entries, repair fixes it
+        state.length = state
+            .length
+            .checked_sub(1)
+            .ok_or_else(|| StdError::overflow(OverflowError::new(OverflowOperation::Sub, state.length, 1)))?;
+        self.entries.remove(storage, id);
+        self.state.save(storage, &state)?;
+        Ok(Some(entry.value))
+    }
+
+    /// iter walks the deque from head to tail, loading one entry at a time
+    pub fn iter<'b>(&'b self, storage: &'b dyn Storage) -> StdResult<DequeIter<'a, 'b, T>> {
+        let head_id = self.state(storage)?.head_id;
+        Ok(DequeIter { deque: self, storage, next_id: head_id, forward: true })
+    }
+
+    /// iter_rev walks the deque from tail to head, loading one entry at a time
+    pub fn iter_rev<'b>(&'b self, storage: &'b dyn Storage) -> StdResult<DequeIter<'a, 'b, T>> {
+        let tail_id = self.state(storage)?.tail_id;
+        Ok(DequeIter { deque: self, storage, next_id: tail_id, forward: false })
+    }
+
+    /// range reads up to `limit` entries from head to tail, starting after the entry `start_after`
+    /// or at the head
+    pub fn range(&self, storage: &dyn Storage, start_after: Option<u64>, limit: usize) -> StdResult<Vec<(u64, T)>> {
+        let next_id = match start_after {
+            Some(id) => self.entries.load(storage, id)?.next,
+            None => self.state(storage)?.head_id,
+        };
+        DequeIter { deque: self, storage, next_id, forward: true }.take(limit).collect()
+    }
+
+    /// verify walks up to `limit` entries from the head and checks the links against each other
+    /// and against the state. Once the walk reaches the tail, stored entries that were not reached
+    /// are reported as orphans. Up to `limit` orphans are reported, the report is not complete if
+    /// there are more
+    pub fn verify(&self, storage: &dyn Storage, limit: usize) -> StdResult<DequeReport<T>>
+    where
+        T: 'static,
+    {
+        let state = self.state(storage)?;
+        let mut entries = vec![];
+        let mut problems = vec![];
+        let mut seen = BTreeSet::new();
+        let mut prev_id = 0;
+        let mut id = state.head_id;
+        while id != 0 && entries.len() < limit {
+            let entry = match self.entries.may_load(storage, id)? {
+                Some(entry) => entry,
+                None => {
+                    problems.push(format!("entry {} is linked from {} but missing", id, prev_id));
+                    break;
+                }
+            };
+            if !seen.insert(id) {
+                problems.push(format!("entry {} is linked twice", id));
+                break;
+            }
+            if entry.prev != prev_id {
+                problems.push(format!("entry {} links back to {} instead of {}", id, entry.prev, prev_id));
+            }
+            if id > state.last_id {
+                problems.push(format!("entry {} is above the last id {}", id, state.last_id));
+            }
+            entries.push((id, entry.value));
+            prev_id = id;
+            id = entry.next;
+        }
+        let mut complete = id == 0 && problems.is_empty();
+        if complete {
+            if prev_id != state.tail_id {
+                problems.push(format!("tail is {} but the last linked entry is {}", state.tail_id, prev_id));
+            }
+            if entries.len() as u64 != state.length {
+                problems.push(format!("length is {} but {} entries are linked", state.length, entries.len()));
+            }
+            // linked entries were already counted by the walk, only orphans count against the limit
+            let mut orphans = 0;
+            for stored_id in self.entries.keys(storage, None, None, Order::Ascending) {
+                let stored_id = stored_id?;
+                if seen.contains(&stored_id) {
+                    continue;
+                }
+                if orphans == limit {
+                    complete = false;
+                    break;
+                }
+                orphans += 1;
+                problems.push(format!("entry {} is stored but not linked", stored_id));
+            }
+        }
+        Ok(DequeReport { entries, complete, problems })
+    }
+
+    /// repair relinks all stored entries in id order, which is push order for a deque only
+    /// pushed at the back, and rebuilds the state from them
+    pub fn repair(&self, storage: &mut dyn Storage) -> StdResult<DequeState>
+    where
+        T: 'static,
+    {
+        let stored: Vec<(u64, Entry<T>)> = self
+            .entries
+            .range(storage, None, None, Order::Ascending)
+            .collect::<StdResult<_>>()?;
+        let mut state = self.state(storage)?;
+        state.head_id = stored.first().map(|(id, _)| *id).unwrap_or_default();
+        state.tail_id = stored.last().map(|(id, _)| *id).unwrap_or_default();
+        state.length = stored.len() as u64;
+        state.last_id = state.last_id.max(state.tail_id);
+        let ids: Vec<u64> = stored.iter().map(|(id, _)| *id).collect();
+        for (index, (id, mut entry)) in stored.into_iter().enumerate() {
+            entry.prev = if index == 0 { 0 } else { ids[index - 1] };
+            entry.next = ids.get(index + 1).copied().unwrap_or_default();
+            self.entries.save(storage, id, &entry)?;
+        }
+        self.state.save(storage, &state)?;
+        Ok(state)
+    }
+
+    fn entry_at(&self, storage: &dyn Storage, id: u64) -> StdResult<Option<(u64, T)>> {
+        if id == 0 {
+            return Ok(None);
+        }
+        Ok(Some((id, self.load(storage, id)?)))
+    }
+
+    // update_entry loads an entry, changes it and saves it back
+    fn update_entry<F>(&self, storage: &mut dyn Storage, id: u64, change: F) -> StdResult<()>
+    where
+        F: FnOnce(&mut Entry<T>),
+    {
+        let mut entry = self
+            .entries
+            .may_load(storage, id)?
+            .ok_or_else(|| StdError::not_found(format!("deque entry {}", id)))?;
+        change(&mut entry);
+        self.entries.save(storage, id, &entry)
+    }
+}
+
+/// DequeIter walks a deque by following the links between entries
+pub struct DequeIter<'a, 'b, T> {
+    deque: &'b Deque<'a, T>,
+    storage: &'b dyn Storage,
+    next_id: u64,
+    forward: bool,
+}
+
+impl<'a, 'b, T> Iterator for DequeIter<'a, 'b, T>
+where
+    T: Serialize + DeserializeOwned,
+{
+    type Item = StdResult<(u64, T)>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.next_id == 0 {
+            return None;
+        }
+        let id = self.next_id;
+        match self.deque.entries.load(self.storage, id) {
+            Ok(entry) => {
+                self.next_id = if self.forward { entry.next } else { entry.prev };
+                Some(Ok((id, entry.value)))
+            }
+            Err(err) => {
+                self.next_id = 0;
+                Some(Err(err))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod testing;
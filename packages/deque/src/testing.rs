@@ -0,0 +1,106 @@
+use cosmwasm_std::testing::MockStorage;
+use cosmwasm_std::StdError;
+use cw_storage_plus::{Item, Map};
+
+use crate::{Deque, DequeState, Entry};
+
+const DEQUE: Deque<u32> = Deque::new("deque", "deque_entries");
+
+fn values(storage: &MockStorage) -> Vec<u32> {
+    DEQUE.iter(storage).unwrap().map(|item| item.unwrap().1).collect()
+}
+
+#[test]
+fn push_and_pop_at_both_ends() {
+    let mut storage = MockStorage::new();
+    assert!(DEQUE.is_empty(&storage).unwrap());
+    assert_eq!(DEQUE.pop_front(&mut storage).unwrap(), None);
+    assert_eq!(DEQUE.pop_back(&mut storage).unwrap(), None);
+
+    assert_eq!(DEQUE.push_back(&mut storage, 2).unwrap(), 1);
+    assert_eq!(DEQUE.push_back(&mut storage, 3).unwrap(), 2);
+    assert_eq!(DEQUE.push_front(&mut storage, 1).unwrap(), 3);
+    assert_eq!(values(&storage), vec![1, 2, 3]);
+    assert_eq!(DEQUE.len(&storage).unwrap(), 3);
+    assert_eq!(DEQUE.front(&storage).unwrap(), Some((3, 1)));
+    assert_eq!(DEQUE.back(&storage).unwrap(), Some((2, 3)));
+
+    assert_eq!(DEQUE.pop_front(&mut storage).unwrap(), Some((3, 1)));
+    assert_eq!(DEQUE.pop_back(&mut storage).unwrap(), Some((2, 3)));
+    assert_eq!(DEQUE.pop_back(&mut storage).unwrap(), Some((1, 2)));
+    assert!(DEQUE.is_empty(&storage).unwrap());
+    assert_eq!(DEQUE.state(&storage).unwrap().head_id, 0);
+    assert_eq!(DEQUE.state(&storage).unwrap().tail_id, 0);
+
+    // ids are never reused
+    assert_eq!(DEQUE.push_back(&mut storage, 4).unwrap(), 4);
+}
+
+#[test]
+fn remove_from_the_middle_relinks_the_neighbours() {
+    let mut storage = MockStorage::new();
+    for value in 1..=4 {
+        DEQUE.push_back(&mut storage, value).unwrap();
+    }
+    assert_eq!(DEQUE.remove(&mut storage, 2).unwrap(), Some(2));
+    assert_eq!(DEQUE.remove(&mut storage, 2).unwrap(), None);
+    assert_eq!(values(&storage), vec![1, 3, 4]);
+    assert_eq!(DEQUE.len(&storage).unwrap(), 3);
+    let report = DEQUE.verify(&storage, 10).unwrap();
+    assert!(report.complete);
+    assert!(report.problems.is_empty());
+
+    DEQUE.save(&mut storage, 3, 30).unwrap();
+    assert_eq!(values(&storage), vec![1, 30, 4]);
+}
+
+#[test]
+fn iterates_backward_and_ranges_after_an_entry() {
+    let mut storage = MockStorage::new();
+    for value in 1..=5 {
+        DEQUE.push_back(&mut storage, value).unwrap();
+    }
+    let backward: Vec<u32> = DEQUE.iter_rev(&storage).unwrap().map(|item| item.unwrap().1).collect();
+    assert_eq!(backward, vec![5, 4, 3, 2, 1]);
+
+    assert_eq!(DEQUE.range(&storage, None, 2).unwrap(), vec![(1, 1), (2, 2)]);
+    assert_eq!(DEQUE.range(&storage, Some(2), 2).unwrap(), vec![(3, 3), (4, 4)]);
+    assert_eq!(DEQUE.range(&storage, Some(4), 10).unwrap(), vec![(5, 5)]);
+    assert!(DEQUE.range(&storage, Some(5), 10).unwrap().is_empty());
+    assert!(DEQUE.range(&storage, Some(9), 10).is_err());
+}
+
+#[test]
+fn remove_with_a_corrupt_length_fails() {
+    let mut storage = MockStorage::new();
+    DEQUE.push_back(&mut storage, 1).unwrap();
+    let corrupt = DequeState { length: 0, ..DEQUE.state(&storage).unwrap() };
+    Item::<DequeState>::new("deque").save(&mut storage, &corrupt).unwrap();
+
+    let err = DEQUE.remove(&mut storage, 1).unwrap_err();
+    assert!(matches!(err, StdError::Overflow { .. }));
+    assert_eq!(DEQUE.repair(&mut storage).unwrap().length, 1);
+    assert_eq!(DEQUE.remove(&mut storage, 1).unwrap(), Some(1));
+}
+
+#[test]
+fn verify_reports_orphans_past_the_limit() {
+    let mut storage = MockStorage::new();
+    for value in 1..=3 {
+        DEQUE.push_back(&mut storage, value).unwrap();
+    }
+    let entries = Map::<u64, Entry<u32>>::new("deque_entries");
+    for id in 4..=6 {
+        entries.save(&mut storage, id, &Entry { prev: 0, next: 0, value: 0 }).unwrap();
+    }
+    // linked entries don't count against the limit, so every orphan is reported
+    let report = DEQUE.verify(&storage, 3).unwrap();
+    assert!(report.complete);
+    assert_eq!(report.problems.len(), 3);
+    assert_eq!(report.problems[2], "entry 6 is stored but not linked");
+
+    entries.save(&mut storage, 7, &Entry { prev: 0, next: 0, value: 0 }).unwrap();
+    let report = DEQUE.verify(&storage, 3).unwrap();
+    assert!(!report.complete);
+    assert_eq!(report.problems.len(), 3);
+}